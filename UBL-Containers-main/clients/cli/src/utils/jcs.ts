@@ -0,0 +1,65 @@
+// RFC 8785 (JCS) strict mode — must match ubl_atom::canonicalize_jcs byte-for-byte.
+// Numbers and strings use JSON.stringify (ECMAScript rules), keys sort by UTF-16 code units.
+
+function isPlainObject(x: any): x is Record<string, any> {
+  return Object.prototype.toString.call(x) === '[object Object]';
+}
+
+export function canonicalizeJcs(input: any): string {
+  if (input === null) return 'null';
+  const t = typeof input;
+  if (t === 'boolean') return String(input);
+  if (t === 'number') {
+    if (!Number.isFinite(input)) throw new Error('NonFiniteNumber');
+    return JSON.stringify(input);
+  }
+  if (t === 'string') return JSON.stringify(input);
+  if (Array.isArray(input)) return '[' + input.map(canonicalizeJcs).join(',') + ']';
+  if (isPlainObject(input)) {
+    // Default sort compares UTF-16 code units, which is what JCS requires
+    const keys = Object.keys(input).sort();
+    return '{' + keys.map(k => JSON.stringify(k) + ':' + canonicalizeJcs(input[k])).join(',') + '}';
+  }
+  throw new Error('InvalidType');
+}
+
+// Reject objects that repeat a key (JSON.parse silently keeps the last one)
+function assertNoDuplicateKeys(text: string): void {
+  const stack: (Set<string> | null)[] = [];
+  let expectKey = false;
+  for (let i = 0; i < text.length; i++) {
+    const c = text[i];
+    if (c === '"') {
+      let j = i + 1;
+      while (text[j] !== '"') j += text[j] === '\\' ? 2 : 1;
+      if (expectKey) {
+        const key: string = JSON.parse(text.slice(i, j + 1));
+        const keys = stack[stack.length - 1]!;
+        if (keys.has(key)) throw new Error(`DuplicateKey: ${key}`);
+        keys.add(key);
+        expectKey = false;
+      }
+      i = j;
+    } else if (c === '{') {
+      stack.push(new Set());
+      expectKey = true;
+    } else if (c === '[') {
+      stack.push(null);
+    } else if (c === '}' || c === ']') {
+      stack.pop();
+      expectKey = false;
+    } else if (c === ',') {
+      expectKey = stack[stack.length - 1] != null;
+    }
+  }
+}
+
+export function parseStrict(text: string): any {
+  const value = JSON.parse(text);
+  assertNoDuplicateKeys(text);
+  return value;
+}
+
+export function canonicalizeJcsText(text: string): string {
+  return canonicalizeJcs(parseStrict(text));
+}
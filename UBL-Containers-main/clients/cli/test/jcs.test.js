@@ -0,0 +1,27 @@
+import { describe, it, expect } from 'vitest';
+import { readFileSync } from 'node:fs';
+import { canonicalizeJcs, canonicalizeJcsText } from '../src/utils/jcs.js';
+import { blake3hex } from '../src/utils/hash.js';
+// Shared with ubl-atom/tests/jcs_vectors.rs
+const file = JSON.parse(readFileSync(new URL('../../../specs/ubl-atom/jcs-vectors.json', import.meta.url), 'utf8'));
+describe('jcs golden vectors', () => {
+    for (const v of file.vectors) {
+        it(v.name, () => {
+            if (v.error) {
+                expect(() => canonicalizeJcsText(v.input)).toThrow(v.error);
+                return;
+            }
+            const out = canonicalizeJcsText(v.input);
+            expect(out).toBe(v.canonical);
+            expect(blake3hex(Buffer.from(out, 'utf8'))).toBe(v.atom_hash);
+        });
+    }
+});
+describe('canonicalizeJcs', () => {
+    it('keeps ECMAScript exponent form', () => {
+        expect(canonicalizeJcs({ x: 1e21, y: 1e-7 })).toBe('{"x":1e+21,"y":1e-7}');
+    });
+    it('rejects non-finite numbers', () => {
+        expect(() => canonicalizeJcs({ x: NaN })).toThrow();
+    });
+});
@@ -0,0 +1,51 @@
+//! Canonicalization hot path: every atom is canonicalized before it is hashed.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+
+fn message(i: usize) -> Value {
+    json!({
+        "type": "message.sent",
+        "id": format!("msg_{:08}", i),
+        "conversation_id": "conv_0001",
+        "from": "ubl:sid:alice",
+        "body": "The quick brown fox jumps over the lazy dog",
+        "ts": 1_735_000_000_000_i64 + i as i64,
+        "attachments": [],
+        "meta": {"z": 1, "a": [1, 2, 3], "m": {"y": true, "b": null}},
+    })
+}
+
+fn transcript(len: usize) -> Value {
+    json!({"messages": (0..len).map(message).collect::<Vec<_>>()})
+}
+
+fn bench_canonicalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("canonicalize");
+    for len in [1usize, 100, 1000] {
+        let atom = transcript(len);
+        let size = ubl_atom::canonicalize(&atom).unwrap().len();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &atom, |b, atom| {
+            b.iter(|| ubl_atom::canonicalize(black_box(atom)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_hash(c: &mut Criterion) {
+    let mut group = c.benchmark_group("atom_hash");
+    let atom = transcript(1000);
+    let size = ubl_atom::canonicalize(&atom).unwrap().len();
+    group.throughput(Throughput::Bytes(size as u64));
+    group.bench_function("buffered", |b| {
+        b.iter(|| ubl_kernel::hash_atom(&ubl_atom::canonicalize(black_box(&atom)).unwrap()))
+    });
+    group.bench_function("streaming", |b| {
+        b.iter(|| ubl_atom::hash_value(black_box(&atom)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_canonicalize, bench_hash);
+criterion_main!(benches);
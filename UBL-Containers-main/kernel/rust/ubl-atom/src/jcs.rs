@@ -0,0 +1,281 @@
+//! RFC 8785 JSON Canonicalization Scheme (JCS)
+//!
+//! Strict mode for atoms that must hash identically on the Mind (TypeScript)
+//! side, where `JSON.stringify` defines how numbers and strings look:
+//! - Numbers are IEEE 754 doubles, printed with ECMAScript `Number::toString`
+//! - Object keys are ordered by their UTF-16 code units
+//! - Strings use the minimal JSON escapes (as `JSON.stringify`)
+//! - Raw input with duplicate object keys is rejected
+//!
+//! Shared golden vectors live in `specs/ubl-atom/jcs-vectors.json`.
+
+use std::cell::RefCell;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+
+use crate::{AtomError, Result};
+
+/// Canonicalize a JSON value per RFC 8785
+pub fn canonicalize_jcs(value: &Value) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    write_value(value, &mut out)?;
+    Ok(out)
+}
+
+/// Parse raw JSON bytes strictly (no duplicate keys) and canonicalize per RFC 8785
+pub fn canonicalize_jcs_bytes(raw: &[u8]) -> Result<Vec<u8>> {
+    canonicalize_jcs(&parse_strict(raw)?)
+}
+
+/// Parse raw JSON bytes, rejecting duplicate object keys at any depth
+pub fn parse_strict(raw: &[u8]) -> Result<Value> {
+    let duplicate = RefCell::new(None);
+    let mut de = serde_json::Deserializer::from_slice(raw);
+    let value = StrictSeed {
+        duplicate: &duplicate,
+    }
+    .deserialize(&mut de)
+    .map_err(|e| match duplicate.take() {
+        Some(key) => AtomError::DuplicateKey(key),
+        None => AtomError::Serialization(e),
+    })?;
+    de.end()?;
+    Ok(value)
+}
+
+/// ECMAScript `Number::toString` for a finite double (ECMA-262 §6.1.6.1.20)
+pub fn format_number(value: f64) -> Result<String> {
+    if !value.is_finite() {
+        return Err(AtomError::NonFiniteNumber);
+    }
+    if value == 0.0 {
+        // Covers -0 as well
+        return Ok("0".to_string());
+    }
+
+    // Rust's `{:e}` yields the shortest digits that round-trip, like ECMAScript
+    let sci = format!("{:e}", value.abs());
+    let (mantissa, exp) = sci
+        .split_once('e')
+        .expect("LowerExp always has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().expect("LowerExp exponent is an integer") + 1;
+
+    let mut out = String::new();
+    if value < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n - 1 < 0 { '-' } else { '+' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    Ok(out)
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(n) => out.extend_from_slice(number_string(n)?.as_bytes()),
+        Value::String(s) => write_string(s, out)?,
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => write_object(map, out)?,
+    }
+    Ok(())
+}
+
+fn write_object(map: &Map<String, Value>, out: &mut Vec<u8>) -> Result<()> {
+    // RFC 8785 §3.2.3: sort on UTF-16 code units, not UTF-8 bytes
+    let mut entries: Vec<(Vec<u16>, &String, &Value)> = map
+        .iter()
+        .map(|(k, v)| (k.encode_utf16().collect(), k, v))
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    out.push(b'{');
+    for (i, (_, key, val)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        }
+        write_string(key, out)?;
+        out.push(b':');
+        write_value(val, out)?;
+    }
+    out.push(b'}');
+    Ok(())
+}
+
+fn write_string(s: &str, out: &mut Vec<u8>) -> Result<()> {
+    // serde_json escapes exactly what JSON.stringify does: `"`, `\` and
+    // control characters (short forms where defined, lowercase \u00xx otherwise)
+    serde_json::to_writer(&mut *out, s)?;
+    Ok(())
+}
+
+fn number_string(n: &Number) -> Result<String> {
+    let value = n.as_f64().ok_or(AtomError::NonFiniteNumber)?;
+    format_number(value)
+}
+
+/// Deserializes a `Value`, refusing duplicate object keys
+/// The offending key is recorded so the caller can report it precisely
+#[derive(Clone, Copy)]
+struct StrictSeed<'a> {
+    duplicate: &'a RefCell<Option<String>>,
+}
+
+impl<'de> DeserializeSeed<'de> for StrictSeed<'_> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for StrictSeed<'_> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_unit<E>(self) -> std::result::Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_bool<E>(self, v: bool) -> std::result::Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> std::result::Result<Value, E> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> std::result::Result<Value, E> {
+        Number::from_f64(v)
+            .map(Value::Number)
+            .ok_or_else(|| E::custom("non-finite number"))
+    }
+
+    fn visit_str<E>(self, v: &str) -> std::result::Result<Value, E> {
+        Ok(Value::String(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> std::result::Result<Value, E> {
+        Ok(Value::String(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element_seed(self)? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> std::result::Result<Value, A::Error> {
+        let mut map = Map::new();
+        while let Some(key) = access.next_key::<String>()? {
+            if map.contains_key(&key) {
+                let message = format!("duplicate key: {}", key);
+                *self.duplicate.borrow_mut() = Some(key);
+                return Err(de::Error::custom(message));
+            }
+            let value = access.next_value_seed(self)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn jcs(raw: &str) -> String {
+        String::from_utf8(canonicalize_jcs_bytes(raw.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ecmascript_numbers() {
+        assert_eq!(format_number(1e30).unwrap(), "1e+30");
+        assert_eq!(format_number(4.50).unwrap(), "4.5");
+        assert_eq!(format_number(2e-3).unwrap(), "0.002");
+        assert_eq!(format_number(1e-27).unwrap(), "1e-27");
+        assert_eq!(
+            format_number("333333333.33333329".parse().unwrap()).unwrap(),
+            "333333333.3333333"
+        );
+        assert_eq!(format_number(-0.0).unwrap(), "0");
+        assert_eq!(format_number(1e21).unwrap(), "1e+21");
+        assert_eq!(format_number(1e20).unwrap(), "100000000000000000000");
+        assert_eq!(format_number(1e-7).unwrap(), "1e-7");
+        assert_eq!(format_number(0.000001).unwrap(), "0.000001");
+        assert_eq!(format_number(-1.5e-9).unwrap(), "-1.5e-9");
+        assert!(format_number(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_large_integers_follow_doubles() {
+        // 2^53 + 1 is not representable; JSON.parse yields 2^53
+        assert_eq!(jcs("9007199254740993"), "9007199254740992");
+        assert_eq!(jcs("18446744073709551615"), "18446744073709552000");
+    }
+
+    #[test]
+    fn test_utf16_key_order() {
+        // U+FB33 sorts after U+1F600 in UTF-8 but before it in UTF-16
+        let value = json!({"\u{fb33}": 1, "\u{1f600}": 2});
+        let out = String::from_utf8(canonicalize_jcs(&value).unwrap()).unwrap();
+        assert_eq!(out, "{\"\u{1f600}\":2,\"\u{fb33}\":1}");
+    }
+
+    #[test]
+    fn test_rejects_duplicate_keys() {
+        let err = canonicalize_jcs_bytes(br#"{"a":1,"b":{"c":1,"c":2}}"#).unwrap_err();
+        assert!(matches!(err, AtomError::DuplicateKey(ref k) if k == "c"));
+    }
+
+    #[test]
+    fn test_rejects_trailing_data() {
+        assert!(canonicalize_jcs_bytes(br#"{"a":1} {"b":2}"#).is_err());
+    }
+}
@@ -15,6 +15,22 @@
 //! - No whitespace in output
 //! - Non-finite numbers are rejected
 //!
+//! ## Streaming
+//! [`canonicalize_to_writer`] emits canonical bytes straight into any
+//! `io::Write` without building a sorted copy of the tree; [`hash_value`]
+//! writes it straight into a BLAKE3 hasher, so large atoms (transcripts,
+//! manifests, policy bundles) are hashed without an intermediate buffer.
+//!
+//! ## Typed atoms
+//! [`SchemaRegistry`] maps `type_name@version` to an [`AtomSchema`] (a JSON
+//! Schema subset); `validate_atom` checks an atom and returns the stable
+//! `schema_hash` a ledger can record alongside it.
+//!
+//! ## Strict JCS mode
+//! [`canonicalize_jcs`] follows RFC 8785 exactly (ECMAScript numbers, UTF-16
+//! key order) so Rust and the TypeScript Mind agree byte-for-byte;
+//! [`canonicalize_jcs_bytes`] additionally rejects duplicate keys in raw input.
+//!
 //! ## Example
 //! ```
 //! use ubl_atom::canonicalize;
@@ -28,9 +44,17 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod jcs;
+pub mod schema;
+
+use std::io::{self, Write};
+
 use serde_json::{Map, Value};
 use thiserror::Error;
 
+pub use jcs::{canonicalize_jcs, canonicalize_jcs_bytes};
+pub use schema::{AtomSchema, SchemaRegistry};
+
 /// Errors that can occur during canonicalization
 #[derive(Error, Debug)]
 pub enum AtomError {
@@ -41,6 +65,35 @@ pub enum AtomError {
     /// Non-finite number detected (NaN, Infinity)
     #[error("Non-finite number detected")]
     NonFiniteNumber,
+
+    /// Writing canonical bytes to the sink failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The same key appears twice in one object (strict parsing)
+    #[error("Duplicate key: {0}")]
+    DuplicateKey(String),
+
+    /// No schema registered under this id
+    #[error("Unknown schema: {0}")]
+    UnknownSchema(String),
+
+    /// Schema uses a keyword or value outside the supported subset
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
+
+    /// A different schema is already registered under this id
+    #[error("Schema conflict: {0}")]
+    SchemaConflict(String),
+
+    /// Atom does not match its schema
+    #[error("Schema violation at {path}: {reason}")]
+    SchemaViolation {
+        /// JSON Pointer to the offending value (`#` is the root)
+        path: String,
+        /// What was expected
+        reason: String,
+    },
 }
 
 /// Result type for atom operations
@@ -54,8 +107,9 @@ pub type Result<T> = std::result::Result<T, AtomError>;
 /// - Arrays preserve order
 /// - Non-finite numbers are rejected
 pub fn canonicalize(value: &Value) -> Result<Vec<u8>> {
-    let sorted = sort_keys_recursive(value)?;
-    Ok(serde_json::to_vec(&sorted)?)
+    let mut out = Vec::new();
+    canonicalize_to_writer(value, &mut out)?;
+    Ok(out)
 }
 
 /// Canonicalize to string (for debugging/display)
@@ -64,27 +118,36 @@ pub fn canonicalize_string(value: &Value) -> Result<String> {
     Ok(String::from_utf8_lossy(&bytes).to_string())
 }
 
-/// Recursively sort object keys
-fn sort_keys_recursive(value: &Value) -> Result<Value> {
+/// Stream the canonical bytes of `value` into `writer`
+///
+/// Produces exactly the bytes of [`canonicalize`], without cloning the tree.
+pub fn canonicalize_to_writer<W: Write>(value: &Value, mut writer: W) -> Result<()> {
+    write_value(value, &mut writer)
+}
+
+/// BLAKE3 `atom_hash` (hex) of a JSON value, streamed from the tree
+///
+/// Equal to `ubl_kernel::hash_atom(&canonicalize(value)?)`.
+pub fn hash_value(value: &Value) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    canonicalize_to_writer(value, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+fn write_value<W: Write>(value: &Value, writer: &mut W) -> Result<()> {
     match value {
-        Value::Object(map) => {
-            let mut sorted_map = Map::new();
-            
-            // SPEC 5.2 R1: Lexicographic ordering
-            let mut keys: Vec<&String> = map.keys().collect();
-            keys.sort();
-
-            for key in keys {
-                let val = map.get(key).unwrap();
-                sorted_map.insert(key.clone(), sort_keys_recursive(val)?);
-            }
-            
-            Ok(Value::Object(sorted_map))
-        }
+        Value::Object(map) => write_object(map, writer),
         Value::Array(arr) => {
             // SPEC 5.2 R2: Arrays preserve order
-            let sorted: Result<Vec<Value>> = arr.iter().map(sort_keys_recursive).collect();
-            Ok(Value::Array(sorted?))
+            writer.write_all(b"[")?;
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    writer.write_all(b",")?;
+                }
+                write_value(item, writer)?;
+            }
+            writer.write_all(b"]")?;
+            Ok(())
         }
         Value::Number(n) => {
             // SPEC 5.2 R3: Numeric normalization
@@ -93,10 +156,33 @@ fn sort_keys_recursive(value: &Value) -> Result<Value> {
                     return Err(AtomError::NonFiniteNumber);
                 }
             }
-            Ok(value.clone())
+            serde_json::to_writer(writer, n)?;
+            Ok(())
         }
-        _ => Ok(value.clone()),
+        // Strings and literals: serde_json's compact encoding
+        _ => {
+            serde_json::to_writer(writer, value)?;
+            Ok(())
+        }
+    }
+}
+
+fn write_object<W: Write>(map: &Map<String, Value>, writer: &mut W) -> Result<()> {
+    // SPEC 5.2 R1: Lexicographic ordering (only references are sorted)
+    let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    writer.write_all(b"{")?;
+    for (i, (key, val)) in entries.into_iter().enumerate() {
+        if i > 0 {
+            writer.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut *writer, key)?;
+        writer.write_all(b":")?;
+        write_value(val, writer)?;
     }
+    writer.write_all(b"}")?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -139,6 +225,61 @@ mod tests {
         assert_eq!(c1, c2);
     }
 
+    /// Accepts at most one byte per call, so every partial write path runs
+    struct Trickle(Vec<u8>);
+
+    impl Write for Trickle {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.extend(buf.iter().take(1));
+            Ok(buf.len().min(1))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_streaming_known_vectors() {
+        let data = json!({
+            "z": [1, 2.5, -3, {"b": null, "a": "\u{1f600}\n"}],
+            "a": {"y": true, "x": false},
+            "": 18446744073709551615u64,
+        });
+        let mut streamed = Trickle(Vec::new());
+        canonicalize_to_writer(&data, &mut streamed).unwrap();
+        assert_eq!(
+            String::from_utf8(streamed.0).unwrap(),
+            "{\"\":18446744073709551615,\"a\":{\"x\":false,\"y\":true},\"z\":[1,2.5,-3,{\"a\":\"\u{1f600}\\n\",\"b\":null}]}"
+        );
+
+        // Shared golden vectors (specs/ubl-atom/jcs-vectors.json)
+        for (data, canonical, atom_hash) in [
+            (
+                json!([56, {"d": true, "10": null, "1": []}]),
+                r#"[56,{"1":[],"10":null,"d":true}]"#,
+                "cae57e23b8b115b3ced06afb46c20508462cfe52bdd46c60bc1f7b4606704aeb",
+            ),
+            (
+                json!({"b": [1, 2], "a": {}}),
+                r#"{"a":{},"b":[1,2]}"#,
+                "baeffc514ec56e4a502d9e3aa6210eae14263f7ce5ae2ec445a19129a4a22287",
+            ),
+        ] {
+            let mut streamed = Trickle(Vec::new());
+            canonicalize_to_writer(&data, &mut streamed).unwrap();
+            assert_eq!(streamed.0, canonical.as_bytes());
+            assert_eq!(hash_value(&data).unwrap(), atom_hash);
+        }
+    }
+
+    #[test]
+    fn test_hash_value_matches_atom_hash() {
+        let data = json!({"b": [1, 2], "a": {"d": 4, "c": "x".repeat(40_000)}});
+        let canonical = canonicalize(&data).unwrap();
+        assert_eq!(hash_value(&data).unwrap(), ubl_kernel::hash_atom(&canonical));
+    }
+
     #[test]
     fn test_no_whitespace() {
         let data = json!({"key": "value", "nested": {"a": 1}});
@@ -0,0 +1,560 @@
+//! Typed atom schemas
+//!
+//! An atom schema names a shape (`type_name`, `version`) and describes it with
+//! a small subset of JSON Schema. Schemas are immutable once registered: the
+//! `schema_hash` commits to the name, the version and the canonical schema
+//! body, so a ledger can record exactly which shape an atom claimed to be.
+//!
+//! Supported keywords:
+//! - `type` (string or array of `object`, `array`, `string`, `number`,
+//!   `integer`, `boolean`, `null`)
+//! - `properties`, `required`, `additionalProperties` (bool or schema)
+//! - `items`, `minItems`, `maxItems`
+//! - `enum`, `const`
+//! - `minLength`, `maxLength` (Unicode scalar values)
+//! - `minimum`, `maximum`
+//! - `title`, `description` (annotations only)
+//!
+//! Any other keyword is rejected when the schema is built, so a schema can
+//! never silently claim a check that is not performed.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::{canonicalize_to_writer, AtomError, Result};
+
+/// Domain tag for schema hashing
+pub const SCHEMA_DOMAIN: &[u8] = b"ubl:schema\n";
+
+const KEYWORDS: &[&str] = &[
+    "type",
+    "properties",
+    "required",
+    "additionalProperties",
+    "items",
+    "minItems",
+    "maxItems",
+    "enum",
+    "const",
+    "minLength",
+    "maxLength",
+    "minimum",
+    "maximum",
+    "title",
+    "description",
+];
+
+const TYPES: &[&str] = &[
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+/// Schema identifier: `type_name@version` (e.g. `git/ref@1`)
+pub fn schema_id(type_name: &str, version: u32) -> String {
+    format!("{}@{}", type_name, version)
+}
+
+/// A versioned atom shape
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AtomSchema {
+    type_name: String,
+    version: u32,
+    schema: Value,
+}
+
+impl AtomSchema {
+    /// Build a schema, rejecting keywords outside the supported subset
+    pub fn new(type_name: impl Into<String>, version: u32, schema: Value) -> Result<Self> {
+        let type_name = type_name.into();
+        if type_name.is_empty() || type_name.contains('@') {
+            return Err(AtomError::InvalidSchema(format!(
+                "invalid type name: {:?}",
+                type_name
+            )));
+        }
+        check_schema(&schema, "#")?;
+        Ok(Self {
+            type_name,
+            version,
+            schema,
+        })
+    }
+
+    /// Type name (e.g. `git/ref`)
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Schema version
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The JSON Schema body
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Identifier used for lookups (`type_name@version`)
+    pub fn id(&self) -> String {
+        schema_id(&self.type_name, self.version)
+    }
+
+    /// `BLAKE3("ubl:schema\n" || canonical({schema, type, version}))` (hex)
+    pub fn schema_hash(&self) -> String {
+        let body = json!({
+            "schema": self.schema,
+            "type": self.type_name,
+            "version": self.version,
+        });
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(SCHEMA_DOMAIN);
+        canonicalize_to_writer(&body, &mut hasher).expect("schema hashing cannot fail");
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// Check that `atom` has this shape
+    pub fn validate(&self, atom: &Value) -> Result<()> {
+        validate_node(&self.schema, atom, "#")
+    }
+}
+
+/// Registered atom schemas, keyed by `type_name@version`
+#[derive(Debug, Clone, Default)]
+pub struct SchemaRegistry {
+    schemas: BTreeMap<String, AtomSchema>,
+}
+
+impl SchemaRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry holding the kernel's built-in schemas
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        for schema in builtin_schemas() {
+            registry
+                .register(schema)
+                .expect("built-in schemas are distinct");
+        }
+        registry
+    }
+
+    /// Register a schema and return its `schema_hash`
+    ///
+    /// Re-registering an identical schema is a no-op; a different body under
+    /// an existing id is a conflict (versions are immutable).
+    pub fn register(&mut self, schema: AtomSchema) -> Result<String> {
+        let id = schema.id();
+        let hash = schema.schema_hash();
+        if let Some(existing) = self.schemas.get(&id) {
+            if existing.schema_hash() != hash {
+                return Err(AtomError::SchemaConflict(id));
+            }
+            return Ok(hash);
+        }
+        self.schemas.insert(id, schema);
+        Ok(hash)
+    }
+
+    /// Look up a schema by id
+    pub fn get(&self, schema_id: &str) -> Option<&AtomSchema> {
+        self.schemas.get(schema_id)
+    }
+
+    /// Latest registered version of a type
+    pub fn latest(&self, type_name: &str) -> Option<&AtomSchema> {
+        self.schemas
+            .values()
+            .filter(|s| s.type_name == type_name)
+            .max_by_key(|s| s.version)
+    }
+
+    /// Validate `atom` against `schema_id`, returning the `schema_hash` to record
+    pub fn validate_atom(&self, atom: &Value, schema_id: &str) -> Result<String> {
+        let schema = self
+            .get(schema_id)
+            .ok_or_else(|| AtomError::UnknownSchema(schema_id.to_string()))?;
+        schema.validate(atom)?;
+        Ok(schema.schema_hash())
+    }
+
+    /// Number of registered schemas
+    pub fn len(&self) -> usize {
+        self.schemas.len()
+    }
+
+    /// Whether the registry is empty
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+}
+
+/// Schemas for atoms the kernel and its services emit
+pub fn builtin_schemas() -> Vec<AtomSchema> {
+    let git_ref = json!({
+        "title": "Git ref update (repo:// containers)",
+        "type": "object",
+        "required": ["type", "tenant", "repo", "ref", "old", "new", "mode"],
+        "properties": {
+            "type": {"const": "git/ref"},
+            "tenant": {"type": "string", "minLength": 1},
+            "repo": {"type": "string", "minLength": 1},
+            "ref": {"type": "string", "minLength": 1},
+            "old": {"type": "string"},
+            "new": {"type": "string", "minLength": 1},
+            "mode": {"enum": ["ff", "force"]}
+        },
+        "additionalProperties": false
+    });
+    let message_created = json!({
+        "title": "Messenger message event",
+        "type": "object",
+        "required": ["event_type", "message_id", "conversation_id", "from", "content_hash", "timestamp"],
+        "properties": {
+            "event_type": {"const": "message.created"},
+            "message_id": {"type": "string", "minLength": 1},
+            "conversation_id": {"type": "string", "minLength": 1},
+            "from": {"type": "string", "minLength": 1},
+            "content_hash": {"type": "string", "minLength": 1},
+            "timestamp": {"type": "string"}
+        },
+        "additionalProperties": false
+    });
+    vec![
+        AtomSchema::new("git/ref", 1, git_ref).expect("valid built-in schema"),
+        AtomSchema::new("message.created", 1, message_created).expect("valid built-in schema"),
+    ]
+}
+
+fn check_schema(schema: &Value, path: &str) -> Result<()> {
+    let obj = schema
+        .as_object()
+        .ok_or_else(|| invalid(path, "schema must be an object"))?;
+
+    for (keyword, value) in obj {
+        if !KEYWORDS.contains(&keyword.as_str()) {
+            return Err(invalid(path, &format!("unsupported keyword: {}", keyword)));
+        }
+        let at = format!("{}/{}", path, keyword);
+        match keyword.as_str() {
+            "type" => {
+                let names: Vec<&Value> = match value {
+                    Value::Array(items) if !items.is_empty() => items.iter().collect(),
+                    other => vec![other],
+                };
+                for name in names {
+                    match name.as_str() {
+                        Some(t) if TYPES.contains(&t) => {}
+                        _ => return Err(invalid(&at, &format!("unknown type: {}", name))),
+                    }
+                }
+            }
+            "properties" => {
+                let props = value
+                    .as_object()
+                    .ok_or_else(|| invalid(&at, "must be an object"))?;
+                for (name, sub) in props {
+                    check_schema(sub, &format!("{}/{}", at, escape(name)))?;
+                }
+            }
+            "required"
+                if !value
+                    .as_array()
+                    .is_some_and(|names| names.iter().all(Value::is_string)) =>
+            {
+                return Err(invalid(&at, "must be an array of strings"));
+            }
+            "additionalProperties" if !value.is_boolean() => check_schema(value, &at)?,
+            "items" => check_schema(value, &at)?,
+            "enum" if value.as_array().is_none_or(|v| v.is_empty()) => {
+                return Err(invalid(&at, "must be a non-empty array"));
+            }
+            "minItems" | "maxItems" | "minLength" | "maxLength" if !value.is_u64() => {
+                return Err(invalid(&at, "must be a non-negative integer"));
+            }
+            "minimum" | "maximum" if !value.is_number() => {
+                return Err(invalid(&at, "must be a number"));
+            }
+            "title" | "description" if !value.is_string() => {
+                return Err(invalid(&at, "must be a string"));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn validate_node(schema: &Value, atom: &Value, path: &str) -> Result<()> {
+    let Some(rules) = schema.as_object() else {
+        return Ok(());
+    };
+
+    if let Some(expected) = rules.get("type") {
+        let matches = match expected {
+            Value::Array(names) => names.iter().any(|n| is_type(atom, n)),
+            name => is_type(atom, name),
+        };
+        if !matches {
+            return Err(violation(path, format!("expected type {}", expected)));
+        }
+    }
+    if let Some(expected) = rules.get("const") {
+        if atom != expected {
+            return Err(violation(path, format!("expected {}", expected)));
+        }
+    }
+    if let Some(Value::Array(options)) = rules.get("enum") {
+        if !options.contains(atom) {
+            return Err(violation(
+                path,
+                format!("not one of {}", Value::Array(options.clone())),
+            ));
+        }
+    }
+
+    match atom {
+        Value::Object(map) => validate_object(rules, map, path)?,
+        Value::Array(items) => {
+            check_bound(rules, "minItems", items.len(), path, |n, b| n >= b)?;
+            check_bound(rules, "maxItems", items.len(), path, |n, b| n <= b)?;
+            if let Some(item_schema) = rules.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_node(item_schema, item, &format!("{}/{}", path, i))?;
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count();
+            check_bound(rules, "minLength", len, path, |n, b| n >= b)?;
+            check_bound(rules, "maxLength", len, path, |n, b| n <= b)?;
+        }
+        Value::Number(n) => {
+            let value = n.as_f64().ok_or(AtomError::NonFiniteNumber)?;
+            if let Some(min) = rules.get("minimum").and_then(Value::as_f64) {
+                if value < min {
+                    return Err(violation(path, format!("below minimum {}", min)));
+                }
+            }
+            if let Some(max) = rules.get("maximum").and_then(Value::as_f64) {
+                if value > max {
+                    return Err(violation(path, format!("above maximum {}", max)));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn validate_object(rules: &Map<String, Value>, map: &Map<String, Value>, path: &str) -> Result<()> {
+    if let Some(Value::Array(required)) = rules.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !map.contains_key(name) {
+                return Err(violation(
+                    path,
+                    format!("missing required property {:?}", name),
+                ));
+            }
+        }
+    }
+
+    let empty = Map::new();
+    let props = rules
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let additional = rules.get("additionalProperties");
+
+    for (name, value) in map {
+        let at = format!("{}/{}", path, escape(name));
+        match (props.get(name), additional) {
+            (Some(sub), _) => validate_node(sub, value, &at)?,
+            (None, Some(Value::Bool(false))) => {
+                return Err(violation(&at, "unexpected property".to_string()));
+            }
+            (None, Some(sub @ Value::Object(_))) => validate_node(sub, value, &at)?,
+            (None, _) => {}
+        }
+    }
+    Ok(())
+}
+
+fn is_type(atom: &Value, name: &Value) -> bool {
+    match name.as_str() {
+        Some("object") => atom.is_object(),
+        Some("array") => atom.is_array(),
+        Some("string") => atom.is_string(),
+        Some("number") => atom.is_number(),
+        Some("integer") => match atom {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        Some("boolean") => atom.is_boolean(),
+        Some("null") => atom.is_null(),
+        _ => false,
+    }
+}
+
+fn check_bound(
+    rules: &Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    ok: impl Fn(u64, u64) -> bool,
+) -> Result<()> {
+    match rules.get(keyword).and_then(Value::as_u64) {
+        Some(bound) if !ok(actual as u64, bound) => Err(violation(
+            path,
+            format!("{} is {}, got {}", keyword, bound, actual),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// JSON Pointer escaping for one path segment (RFC 6901)
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn invalid(path: &str, reason: &str) -> AtomError {
+    AtomError::InvalidSchema(format!("{}: {}", path, reason))
+}
+
+fn violation(path: &str, reason: String) -> AtomError {
+    AtomError::SchemaViolation {
+        path: path.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git_ref(mode: &str) -> Value {
+        json!({
+            "type": "git/ref",
+            "tenant": "acme",
+            "repo": "core",
+            "ref": "refs/heads/main",
+            "old": "",
+            "new": "a1b2c3",
+            "mode": mode,
+        })
+    }
+
+    #[test]
+    fn test_builtin_git_ref() {
+        let registry = SchemaRegistry::with_builtins();
+        let hash = registry.validate_atom(&git_ref("ff"), "git/ref@1").unwrap();
+        assert_eq!(hash, registry.get("git/ref@1").unwrap().schema_hash());
+
+        let err = registry
+            .validate_atom(&git_ref("rebase"), "git/ref@1")
+            .unwrap_err();
+        assert!(matches!(err, AtomError::SchemaViolation { ref path, .. } if path == "#/mode"));
+    }
+
+    #[test]
+    fn test_reports_path_of_violation() {
+        let schema = AtomSchema::new(
+            "transcript",
+            1,
+            json!({
+                "type": "object",
+                "properties": {
+                    "messages": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["from"],
+                            "properties": {"from": {"type": "string", "minLength": 1}}
+                        }
+                    }
+                }
+            }),
+        )
+        .unwrap();
+
+        let atom = json!({"messages": [{"from": "a"}, {"from": ""}]});
+        let err = schema.validate(&atom).unwrap_err();
+        assert!(
+            matches!(err, AtomError::SchemaViolation { ref path, .. } if path == "#/messages/1/from")
+        );
+
+        let atom = json!({"messages": [{"to": "b"}]});
+        let err = schema.validate(&atom).unwrap_err();
+        assert!(
+            matches!(err, AtomError::SchemaViolation { ref path, .. } if path == "#/messages/0")
+        );
+    }
+
+    #[test]
+    fn test_numbers_and_additional_properties() {
+        let schema = AtomSchema::new(
+            "amount",
+            1,
+            json!({
+                "type": "object",
+                "properties": {"value": {"type": "integer", "minimum": 0, "maximum": 100}},
+                "additionalProperties": {"type": "string"}
+            }),
+        )
+        .unwrap();
+
+        assert!(schema.validate(&json!({"value": 42, "memo": "ok"})).is_ok());
+        assert!(schema.validate(&json!({"value": 4.5})).is_err());
+        assert!(schema.validate(&json!({"value": 101})).is_err());
+        assert!(schema.validate(&json!({"value": 1, "memo": 7})).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_keywords() {
+        let err = AtomSchema::new("x", 1, json!({"type": "string", "pattern": "^a"})).unwrap_err();
+        assert!(matches!(err, AtomError::InvalidSchema(_)));
+        assert!(AtomSchema::new("x", 1, json!({"type": "text"})).is_err());
+        assert!(AtomSchema::new("x@y", 1, json!({})).is_err());
+    }
+
+    #[test]
+    fn test_schema_hash_is_stable_and_versioned() {
+        let body = json!({"type": "object", "required": ["a"]});
+        let v1 = AtomSchema::new("thing", 1, body.clone()).unwrap();
+        let v2 = AtomSchema::new("thing", 2, body.clone()).unwrap();
+        let reordered =
+            AtomSchema::new("thing", 1, json!({"required": ["a"], "type": "object"})).unwrap();
+
+        assert_eq!(v1.schema_hash(), reordered.schema_hash());
+        assert_ne!(v1.schema_hash(), v2.schema_hash());
+        assert_eq!(v1.schema_hash().len(), 64);
+    }
+
+    #[test]
+    fn test_registry_versions_are_immutable() {
+        let mut registry = SchemaRegistry::new();
+        let v1 = AtomSchema::new("thing", 1, json!({"type": "object"})).unwrap();
+        let hash = registry.register(v1.clone()).unwrap();
+        assert_eq!(registry.register(v1).unwrap(), hash);
+
+        let changed = AtomSchema::new("thing", 1, json!({"type": "array"})).unwrap();
+        assert!(matches!(
+            registry.register(changed),
+            Err(AtomError::SchemaConflict(_))
+        ));
+
+        registry
+            .register(AtomSchema::new("thing", 2, json!({"type": "array"})).unwrap())
+            .unwrap();
+        assert_eq!(registry.latest("thing").unwrap().version(), 2);
+        assert!(matches!(
+            registry.validate_atom(&json!({}), "thing@3"),
+            Err(AtomError::UnknownSchema(_))
+        ));
+    }
+}
@@ -0,0 +1,54 @@
+//! Shared RFC 8785 golden vectors (also run by the TypeScript CLI)
+
+use serde::Deserialize;
+use ubl_atom::{canonicalize_jcs_bytes, AtomError};
+
+const VECTORS: &str = include_str!("../../../../specs/ubl-atom/jcs-vectors.json");
+
+#[derive(Deserialize)]
+struct VectorFile {
+    vectors: Vec<Vector>,
+}
+
+#[derive(Deserialize)]
+struct Vector {
+    name: String,
+    input: String,
+    canonical: Option<String>,
+    atom_hash: Option<String>,
+    error: Option<String>,
+}
+
+#[test]
+fn jcs_golden_vectors() {
+    let file: VectorFile = serde_json::from_str(VECTORS).unwrap();
+    assert!(!file.vectors.is_empty());
+
+    for v in file.vectors {
+        let result = canonicalize_jcs_bytes(v.input.as_bytes());
+        match (v.canonical, v.error) {
+            (Some(expected), None) => {
+                let out = result.unwrap_or_else(|e| panic!("{}: {}", v.name, e));
+                assert_eq!(
+                    String::from_utf8(out.clone()).unwrap(),
+                    expected,
+                    "{}",
+                    v.name
+                );
+                let hash = v
+                    .atom_hash
+                    .unwrap_or_else(|| panic!("{}: missing atom_hash", v.name));
+                assert_eq!(blake3::hash(&out).to_hex().as_str(), hash, "{}", v.name);
+            }
+            (None, Some(error)) => {
+                assert_eq!(error, "DuplicateKey", "{}: unknown error kind", v.name);
+                assert!(
+                    matches!(result, Err(AtomError::DuplicateKey(_))),
+                    "{}: expected DuplicateKey",
+                    v.name
+                );
+            }
+            _ => panic!("{}: vector needs exactly one of canonical/error", v.name),
+        }
+    }
+}
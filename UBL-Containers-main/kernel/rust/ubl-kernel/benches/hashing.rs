@@ -0,0 +1,54 @@
+//! Kernel primitives used on every commit: hashing and Ed25519 verification.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+fn bench_hash_atom(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_atom");
+    for size in [256usize, 4 * 1024, 64 * 1024] {
+        let data = vec![0x5au8; size];
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &data, |b, data| {
+            b.iter(|| ubl_kernel::hash_atom(black_box(data)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_hash_link(c: &mut Criterion) {
+    // Roughly the size of a v1 signing_bytes payload
+    let signing_bytes = vec![0xa5u8; 170];
+    c.bench_function("hash_link", |b| {
+        b.iter(|| ubl_kernel::hash_link(black_box(&signing_bytes)))
+    });
+    c.bench_function("hash_merkle", |b| {
+        b.iter(|| ubl_kernel::hash_merkle(black_box(&[1u8; 32]), black_box(&[2u8; 32])))
+    });
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let (pubkey, key) = ubl_kernel::generate_keypair();
+    let message = vec![0xa5u8; 170];
+    let signature = ubl_kernel::sign(&key, &message);
+    c.bench_function("verify", |b| {
+        b.iter(|| ubl_kernel::verify(black_box(&pubkey), black_box(&message), black_box(&signature)))
+    });
+
+    let signed: Vec<(String, Vec<u8>, String)> = (0..64)
+        .map(|i| {
+            let (pk, sk) = ubl_kernel::generate_keypair();
+            let msg = format!("commit {}", i).into_bytes();
+            let sig = ubl_kernel::sign(&sk, &msg);
+            (pk, msg, sig)
+        })
+        .collect();
+    let items: Vec<(&str, &[u8], &str)> = signed
+        .iter()
+        .map(|(pk, msg, sig)| (pk.as_str(), msg.as_slice(), sig.as_str()))
+        .collect();
+    c.bench_function("verify_batch/64", |b| {
+        b.iter(|| ubl_kernel::verify_batch(black_box(&items)))
+    });
+}
+
+criterion_group!(benches, bench_hash_atom, bench_hash_link, bench_verify);
+criterion_main!(benches);
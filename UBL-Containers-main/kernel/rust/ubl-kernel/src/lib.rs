@@ -12,9 +12,11 @@
 #![warn(missing_docs)]
 
 use blake3::Hasher;
-use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use ed25519_dalek::{Signature, Signer, VerifyingKey};
 use thiserror::Error;
 
+pub use ed25519_dalek::SigningKey;
+
 /// Domain prefixes for hash separation
 /// NOTE: atom_hash does NOT use domain tag per JSON✯Atomic binding
 pub mod domains {
@@ -24,6 +26,10 @@ pub mod domains {
     pub const LEDGER: &[u8] = b"ubl:ledger\n";
     /// Domain for merkle root
     pub const ROOT: &[u8] = b"ubl:root\n";
+    /// Domain for pact signatures
+    pub const PACT: &[u8] = b"ubl:pact\n";
+    /// Domain for ledger checkpoint signatures
+    pub const CHECKPOINT: &[u8] = b"ubl:checkpoint\n";
 }
 
 /// Errors from kernel operations
@@ -62,6 +68,27 @@ pub fn hash_link(signing_bytes: &[u8]) -> String {
     hex::encode(hasher.finalize().as_bytes())
 }
 
+/// Hash a ledger entry with domain separation
+///
+/// `entry_hash := BLAKE3("ubl:ledger\n" || container_id || sequence || link_hash || previous_hash)`
+/// Variable-length fields are prefixed with their length (u32, big-endian).
+/// Everything here is known before the commit is accepted, so a Mind can chain
+/// several sequential commits without waiting for each receipt.
+pub fn hash_entry(container_id: &str, sequence: u64, link_hash: &str, previous_hash: &str) -> String {
+    let mut hasher = Hasher::new();
+    hasher.update(domains::LEDGER);
+    update_prefixed(&mut hasher, container_id.as_bytes());
+    hasher.update(&sequence.to_be_bytes());
+    update_prefixed(&mut hasher, link_hash.as_bytes());
+    update_prefixed(&mut hasher, previous_hash.as_bytes());
+    hex::encode(hasher.finalize().as_bytes())
+}
+
+fn update_prefixed(hasher: &mut Hasher, bytes: &[u8]) {
+    hasher.update(&(bytes.len() as u32).to_be_bytes());
+    hasher.update(bytes);
+}
+
 /// Hash for merkle tree nodes
 pub fn hash_merkle(left: &[u8], right: &[u8]) -> Vec<u8> {
     let mut hasher = Hasher::new();
@@ -89,14 +116,46 @@ pub fn verify(pubkey_hex: &str, message: &[u8], signature_hex: &str) -> Result<(
     let signature = Signature::try_from(sig_bytes.as_slice())
         .map_err(|e| KernelError::InvalidKey(e.to_string()))?;
     
-    // Verify
+    // Verify (strict: rejects weak keys and non-canonical R, like verify_batch)
     verifying_key
-        .verify(message, &signature)
+        .verify_strict(message, &signature)
         .map_err(|_| KernelError::SignatureVerification)?;
     
     Ok(())
 }
 
+/// Verify many Ed25519 signatures at once
+///
+/// Each item is `(pubkey_hex, message, signature_hex)`. Succeeds only if every
+/// signature is valid; on failure, callers needing the culprit fall back to
+/// `verify` per item. Weak (small-order) keys are rejected up front, as
+/// `verify` rejects them, so both paths agree on what is valid.
+pub fn verify_batch(items: &[(&str, &[u8], &str)]) -> Result<()> {
+    let mut messages = Vec::with_capacity(items.len());
+    let mut signatures = Vec::with_capacity(items.len());
+    let mut keys = Vec::with_capacity(items.len());
+
+    for (pubkey_hex, message, signature_hex) in items {
+        let pubkey_bytes = hex::decode(pubkey_hex)?;
+        let verifying_key = VerifyingKey::try_from(pubkey_bytes.as_slice())
+            .map_err(|e| KernelError::InvalidKey(e.to_string()))?;
+        if verifying_key.is_weak() {
+            return Err(KernelError::InvalidKey("weak key".to_string()));
+        }
+
+        let sig_bytes = hex::decode(signature_hex)?;
+        let signature = Signature::try_from(sig_bytes.as_slice())
+            .map_err(|e| KernelError::InvalidKey(e.to_string()))?;
+
+        messages.push(*message);
+        signatures.push(signature);
+        keys.push(verifying_key);
+    }
+
+    ed25519_dalek::verify_batch(&messages, &signatures, &keys)
+        .map_err(|_| KernelError::SignatureVerification)
+}
+
 /// Generate a new signing keypair
 pub fn generate_keypair() -> (String, SigningKey) {
     let signing_key = SigningKey::generate(&mut rand::thread_rng());
@@ -104,6 +163,14 @@ pub fn generate_keypair() -> (String, SigningKey) {
     (pubkey_hex, signing_key)
 }
 
+/// Load a signing key from its 32-byte seed (hex)
+pub fn signing_key_from_hex(seed_hex: &str) -> Result<SigningKey> {
+    let seed: [u8; 32] = hex::decode(seed_hex)?
+        .try_into()
+        .map_err(|_| KernelError::InvalidKey("seed must be 32 bytes".to_string()))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
 /// Get the public key hex from a signing key
 pub fn pubkey_from_signing_key(signing_key: &SigningKey) -> String {
     hex::encode(signing_key.verifying_key().as_bytes())
@@ -178,6 +245,57 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_hash_entry_is_length_prefixed() {
+        // Shifting bytes between adjacent fields must change the hash
+        let a = hash_entry("ab", 1, "cd", "ef");
+        let b = hash_entry("a", 1, "bcd", "ef");
+        assert_ne!(a, b);
+        assert_eq!(a, hash_entry("ab", 1, "cd", "ef"));
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let keys: Vec<_> = (0..8).map(|_| generate_keypair()).collect();
+        let messages: Vec<Vec<u8>> = (0..8).map(|i| format!("msg{}", i).into_bytes()).collect();
+        let signatures: Vec<String> = keys
+            .iter()
+            .zip(&messages)
+            .map(|((_, sk), m)| sign(sk, m))
+            .collect();
+
+        let items: Vec<(&str, &[u8], &str)> = keys
+            .iter()
+            .zip(&messages)
+            .zip(&signatures)
+            .map(|(((pk, _), m), s)| (pk.as_str(), m.as_slice(), s.as_str()))
+            .collect();
+        assert!(verify_batch(&items).is_ok());
+
+        let mut tampered = items.clone();
+        tampered[5].1 = b"other";
+        assert!(verify_batch(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_weak_key_fails_both_paths() {
+        // Identity key with R = identity, s = 0 satisfies the loose equation
+        // for any message
+        let identity = format!("01{}", "00".repeat(31));
+        let signature = format!("{identity}{}", "00".repeat(32));
+        assert!(verify(&identity, b"anything", &signature).is_err());
+        let items = [(identity.as_str(), b"anything".as_slice(), signature.as_str())];
+        assert!(verify_batch(&items).is_err());
+    }
+
+    #[test]
+    fn test_signing_key_from_hex() {
+        let (pubkey, key) = generate_keypair();
+        let restored = signing_key_from_hex(&hex::encode(key.to_bytes())).unwrap();
+        assert_eq!(pubkey_from_signing_key(&restored), pubkey);
+        assert!(signing_key_from_hex("abcd").is_err());
+    }
+
     #[test]
     fn test_genesis_hash_length() {
         assert_eq!(GENESIS_HASH.len(), 64);
@@ -0,0 +1,245 @@
+//! Signed state checkpoints (SPEC-UBL-LEDGER v1.0 §8.2)
+//!
+//! A checkpoint freezes the projection `(sequence, last_hash, balance,
+//! merkle_root)` at one point of the chain and is signed by the node that
+//! computed it:
+//!
+//! ```text
+//! signing_bytes := "ubl:checkpoint\n" || container_id:str || sequence:u64 BE
+//!                  || last_hash:str || physical_balance:i128 BE || merkle_root:str
+//!
+//! str := len:u32 BE || UTF-8 bytes
+//! ```
+//!
+//! Projections resume from the latest checkpoint instead of folding from
+//! genesis. Trust is anchored twice: the signature must come from the
+//! expected signer, and `last_hash` must be the stored hash of the entry at
+//! `sequence`, so a checkpoint can never describe a different history.
+
+use serde::{Deserialize, Serialize};
+use ubl_kernel::SigningKey;
+
+use crate::store::LedgerStore;
+use crate::{LedgerError, LedgerState, Result};
+
+/// A signed projection of a chain prefix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Container the checkpoint belongs to
+    pub container_id: String,
+    /// Last sequence covered (0 for an empty chain)
+    pub sequence: u64,
+    /// Hash of the entry at `sequence`
+    pub last_hash: String,
+    /// Sum of all physics deltas up to `sequence`
+    pub physical_balance: i128,
+    /// Merkle root of the first `sequence` entries
+    pub merkle_root: String,
+    /// Public key of the signer (hex)
+    pub signer: String,
+    /// Ed25519 signature over the signing bytes (hex)
+    pub signature: String,
+}
+
+impl Checkpoint {
+    /// Sign a projected state
+    pub fn sign(state: LedgerState, key: &SigningKey) -> Self {
+        let mut checkpoint = Self {
+            container_id: state.container_id,
+            sequence: state.sequence,
+            last_hash: state.last_hash,
+            physical_balance: state.physical_balance,
+            merkle_root: state.merkle_root,
+            signer: ubl_kernel::pubkey_from_signing_key(key),
+            signature: String::new(),
+        };
+        checkpoint.signature = ubl_kernel::sign(key, &checkpoint.signing_bytes());
+        checkpoint
+    }
+
+    /// Bytes covered by the signature
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut out = ubl_kernel::domains::CHECKPOINT.to_vec();
+        write_str(&mut out, &self.container_id);
+        out.extend_from_slice(&self.sequence.to_be_bytes());
+        write_str(&mut out, &self.last_hash);
+        out.extend_from_slice(&self.physical_balance.to_be_bytes());
+        write_str(&mut out, &self.merkle_root);
+        out
+    }
+
+    /// Check that `signer` produced this checkpoint
+    pub fn verify(&self, signer: &str) -> Result<()> {
+        if self.signer != signer {
+            return Err(invalid(format!(
+                "signed by {}, expected {}",
+                self.signer, signer
+            )));
+        }
+        ubl_kernel::verify(&self.signer, &self.signing_bytes(), &self.signature)
+            .map_err(|_| invalid("bad signature"))
+    }
+
+    /// Check that the checkpoint describes `store`'s chain: same container,
+    /// and `last_hash` is the hash stored at `sequence`
+    pub fn verify_against<S: LedgerStore + ?Sized>(&self, store: &S) -> Result<()> {
+        if self.container_id != store.container_id() {
+            return Err(LedgerError::ContainerMismatch {
+                expected: store.container_id().to_string(),
+                actual: self.container_id.clone(),
+            });
+        }
+        let anchored = match self.sequence {
+            0 => true,
+            sequence => store
+                .get_entry(sequence)?
+                .is_some_and(|entry| entry.entry_hash == self.last_hash),
+        };
+        if !anchored {
+            return Err(invalid(format!(
+                "last_hash does not match entry {}",
+                self.sequence
+            )));
+        }
+        Ok(())
+    }
+
+    /// The projected state this checkpoint records
+    pub fn state(&self) -> LedgerState {
+        LedgerState {
+            container_id: self.container_id.clone(),
+            sequence: self.sequence,
+            last_hash: self.last_hash.clone(),
+            physical_balance: self.physical_balance,
+            merkle_root: self.merkle_root.clone(),
+        }
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, value: &str) {
+    out.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn invalid(reason: impl Into<String>) -> LedgerError {
+    LedgerError::InvalidCheckpoint(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Author};
+    use crate::{ChainBreak, Ledger, SegmentStore};
+    use ubl_link::{IntentClass, LinkCommit};
+
+    /// Commit `count` Entropy links of `delta` by `author`
+    fn extend(store: &mut impl LedgerStore, author: &Author, count: u64, delta: i128) {
+        testing::extend(store, author, count, IntentClass::Entropy, delta);
+    }
+
+    #[test]
+    fn test_resume_matches_full_replay() {
+        let (author, node) = (Author::new(), Author::new());
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 4, 10);
+        let checkpoint = ledger.checkpoint(&node.key).unwrap();
+        extend(&mut ledger, &author, 3, -5);
+
+        assert_eq!(checkpoint.sequence, 4);
+        assert_eq!(checkpoint.physical_balance, 40);
+        assert_eq!(checkpoint.merkle_root, ledger.merkle_root_at(4).unwrap());
+        assert_eq!(
+            ledger.resume(&node.pubkey).unwrap(),
+            ledger.verify_chain().unwrap()
+        );
+        assert_eq!(ledger.resume(&node.pubkey).unwrap().physical_balance, 25);
+    }
+
+    #[test]
+    fn test_resume_only_checks_the_tail() {
+        let (author, node) = (Author::new(), Author::new());
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 3, 1);
+        ledger.checkpoint(&node.key).unwrap();
+
+        // An invalid entry after the checkpoint is still caught
+        let mut bad = LinkCommit {
+            expected_sequence: 4,
+            previous_hash: ledger.last_hash(),
+            ..ledger.get_entry(3).unwrap().link.clone()
+        };
+        bad.physics_delta = 1_000;
+        ledger.append(bad).unwrap();
+        assert!(matches!(
+            ledger.resume(&node.pubkey),
+            Err(LedgerError::ChainBroken(ChainBreak { sequence: 4, .. }))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_untrusted_or_unanchored_checkpoints() {
+        let (author, node) = (Author::new(), Author::new());
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 2, 1);
+        let checkpoint = ledger.checkpoint(&node.key).unwrap();
+        assert!(matches!(
+            ledger.resume(&author.pubkey),
+            Err(LedgerError::InvalidCheckpoint(_))
+        ));
+
+        let forged = Checkpoint {
+            physical_balance: 1_000_000,
+            ..checkpoint.clone()
+        };
+        assert!(forged.verify(&node.pubkey).is_err());
+
+        // Same shape, different history
+        let mut other = Ledger::new("wallet".to_string());
+        extend(&mut other, &author, 2, 7);
+        assert!(matches!(
+            other.save_checkpoint(checkpoint),
+            Err(LedgerError::InvalidCheckpoint(_))
+        ));
+    }
+
+    #[test]
+    fn test_rollback_keeps_checkpointed_entries() {
+        let (author, node) = (Author::new(), Author::new());
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 3, 1);
+        ledger.checkpoint(&node.key).unwrap();
+        extend(&mut ledger, &author, 2, 5);
+
+        assert!(matches!(
+            ledger.rollback(2),
+            Err(LedgerError::RollbackPastCheckpoint {
+                sequence: 2,
+                checkpoint: 3
+            })
+        ));
+        ledger.rollback(3).unwrap();
+        assert_eq!(
+            (ledger.current_sequence(), ledger.physical_balance()),
+            (3, 3)
+        );
+        assert!(ledger.resume(&node.pubkey).is_ok());
+    }
+
+    #[test]
+    fn test_segment_store_keeps_checkpoints() {
+        let dir = tempfile::tempdir().unwrap();
+        let (author, node) = (Author::new(), Author::new());
+        let checkpoint = {
+            let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+            extend(&mut store, &author, 5, 2);
+            let checkpoint = store.checkpoint(&node.key).unwrap();
+            extend(&mut store, &author, 1, 2);
+            checkpoint
+        };
+
+        let store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        assert_eq!(store.latest_checkpoint().unwrap(), Some(checkpoint));
+        let resumed = store.resume(&node.pubkey).unwrap();
+        assert_eq!((resumed.entries, resumed.physical_balance), (6, 12));
+    }
+}
@@ -5,31 +5,203 @@
 //!
 //! ## Properties
 //! - Append-only (no UPDATE, no DELETE)
-//! - Hash chain (each entry links to previous)
+//! - Hash chain (each entry links to previous), re-verifiable offline
 //! - State is always a projection of history
-//! - Merkle root for daily anchoring
+//! - Merkle root for daily anchoring, with inclusion and consistency proofs
+//! - Pluggable storage: in memory ([`Ledger`]) or on disk ([`SegmentStore`])
+//! - Many containers with atomic cross-container transfers ([`LedgerManager`])
+//! - Named projections kept current by a [`ProjectionRunner`] with persisted cursors,
+//!   from a store or the server's SSE tail ([`SseTail`])
 
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod checkpoint;
+pub mod manager;
+pub mod merkle;
+pub mod projection;
+pub mod segment;
+pub mod store;
+pub mod tail;
+pub mod verify;
+pub mod views;
+
+#[cfg(test)]
+mod testing;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use ubl_link::{IntentClass, LinkCommit, LinkReceipt};
+use ubl_link::{LinkCommit, LinkReceipt};
+
+pub use checkpoint::Checkpoint;
+pub use manager::{LedgerManager, TransferReceipt};
+pub use merkle::{
+    verify_consistency, verify_inclusion, ConsistencyProof, Frontier, InclusionProof,
+};
+pub use projection::{
+    AtomIndex, BalanceProjection, Cursor, CursorStore, FileCursors, MemoryCursors, Projection,
+    ProjectionError, ProjectionRunner, Snapshot, DEFAULT_SNAPSHOT_EVERY,
+};
+pub use segment::SegmentStore;
+pub use store::LedgerStore;
+pub use tail::SseTail;
+pub use verify::{ChainBreak, ChainFault, ChainSummary, ChainVerifier};
+pub use views::{Affordance, Affordances, Message, MessageIndex, Obligation, Obligations};
 
 /// Errors from ledger operations
 #[derive(Error, Debug)]
 pub enum LedgerError {
     /// Sequence mismatch
     #[error("Sequence mismatch: expected {expected}, got {actual}")]
-    SequenceMismatch { expected: u64, actual: u64 },
+    SequenceMismatch {
+        /// Sequence the ledger expected
+        expected: u64,
+        /// Sequence that was supplied
+        actual: u64,
+    },
 
     /// Hash chain broken
     #[error("Reality drift: expected previous_hash {expected}, got {actual}")]
-    RealityDrift { expected: String, actual: String },
+    RealityDrift {
+        /// Hash the ledger expected
+        expected: String,
+        /// Hash that was supplied
+        actual: String,
+    },
 
     /// Container ID mismatch
     #[error("Container mismatch: expected {expected}, got {actual}")]
-    ContainerMismatch { expected: String, actual: String },
+    ContainerMismatch {
+        /// Container the ledger belongs to
+        expected: String,
+        /// Container that was supplied
+        actual: String,
+    },
+
+    /// No entry at this sequence
+    #[error("Entry not found: sequence {0}")]
+    EntryNotFound(u64),
+
+    /// Tree sizes do not describe a prefix of the ledger
+    #[error("Invalid tree size: old {old_size}, new {new_size}")]
+    InvalidTreeSize {
+        /// Size of the older tree
+        old_size: u64,
+        /// Size of the newer tree
+        new_size: u64,
+    },
+
+    /// Merkle proof does not verify
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
+
+    /// Stored data failed its integrity checks
+    #[error("Corrupt ledger file {path} at offset {offset}: {reason}")]
+    Corrupt {
+        /// File that failed
+        path: String,
+        /// Byte offset of the bad record
+        offset: u64,
+        /// What was wrong
+        reason: String,
+    },
+
+    /// Checkpoint is not signed by the expected key or not anchored in the chain
+    #[error("Invalid checkpoint: {0}")]
+    InvalidCheckpoint(String),
+
+    /// Chain failed verification
+    #[error(transparent)]
+    ChainBroken(#[from] ChainBreak),
+
+    /// Projection name is empty, not file-safe, or already registered
+    #[error("Invalid projection name: {0:?}")]
+    InvalidProjection(String),
+
+    /// A projection rejected an entry
+    #[error("Projection {name} failed at sequence {sequence}: {source}")]
+    Projection {
+        /// Projection that failed
+        name: String,
+        /// Entry it was applying
+        sequence: u64,
+        /// Why it failed
+        source: ProjectionError,
+    },
+
+    /// Tail skipped entries a projection has not applied
+    #[error("Projection {name} expected sequence {expected}, got {found}")]
+    ProjectionGap {
+        /// Projection that would skip entries
+        name: String,
+        /// Next sequence it needs
+        expected: u64,
+        /// Sequence that arrived
+        found: u64,
+    },
+
+    /// Saved cursor does not point at an entry of this chain
+    #[error("Projection {name} cursor diverged from the chain at sequence {sequence}")]
+    CursorDiverged {
+        /// Projection whose cursor diverged
+        name: String,
+        /// Cursor sequence
+        sequence: u64,
+    },
+
+    /// No store is managed for the container
+    #[error("Unknown container: {0}")]
+    UnknownContainer(String),
+
+    /// Commit rejected by the membrane
+    #[error(transparent)]
+    Rejected(#[from] ubl_membrane::MembraneError),
+
+    /// Transfer rejected by the membrane; neither leg was appended
+    #[error("Transfer rejected: {0}")]
+    TransferRejected(#[from] ubl_membrane::BatchRejection),
+
+    /// The credit could not be appended, so the debit was rolled back
+    #[error("Transfer {atom_hash} aborted: credit to {container_id} failed: {reason}")]
+    TransferAborted {
+        /// Transfer atom of the pair
+        atom_hash: String,
+        /// Container whose credit failed
+        container_id: String,
+        /// Why the credit failed
+        reason: String,
+    },
+
+    /// A staged transfer could be neither kept nor rolled back; its journal
+    /// stays until the manager is reopened
+    #[error("Transfer {atom_hash} unresolved: {reason}")]
+    TransferUnresolved {
+        /// Transfer atom of the pair
+        atom_hash: String,
+        /// Why it could not be resolved
+        reason: String,
+    },
+
+    /// Rolling back would drop entries a checkpoint covers
+    #[error("Cannot roll back to sequence {sequence}: checkpoint at {checkpoint}")]
+    RollbackPastCheckpoint {
+        /// Sequence the rollback would keep
+        sequence: u64,
+        /// Sequence of the latest checkpoint
+        checkpoint: u64,
+    },
+
+    /// Link has no signing layout (unknown version)
+    #[error(transparent)]
+    Link(#[from] ubl_link::LinkError),
+
+    /// SSE tail event could not be decoded into an entry
+    #[error("Malformed tail event: {0}")]
+    MalformedTail(String),
+
+    /// Storage I/O failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 /// Result type for ledger operations
@@ -52,6 +224,9 @@ pub struct LedgerEntry {
 pub struct Ledger {
     container_id: String,
     chain: Vec<LedgerEntry>,
+    /// Running Σ physics_delta, kept on append
+    balance: i128,
+    checkpoints: Vec<Checkpoint>,
 }
 
 /// Genesis hash constant
@@ -63,6 +238,8 @@ impl Ledger {
         Self {
             container_id,
             chain: Vec::new(),
+            balance: 0,
+            checkpoints: Vec::new(),
         }
     }
 
@@ -91,17 +268,21 @@ impl Ledger {
 
     /// Get the physical balance (sum of all deltas)
     pub fn physical_balance(&self) -> i128 {
-        self.chain.iter().map(|e| e.link.physics_delta).sum()
+        self.balance
     }
 
-    /// Append a validated commit to the ledger
+    /// Append a validated commit to the ledger, deriving its entry hash
+    /// from the link (`hash_link`, then `hash_entry`)
     /// NOTE: Validation should be done by the membrane before calling this
-    pub fn append(&mut self, link: LinkCommit, entry_hash: String) -> LinkReceipt {
+    pub fn append(&mut self, link: LinkCommit) -> Result<LinkReceipt> {
+        let entry_hash = verify::entry_hash(&link)?;
+        Ok(self.push(link, entry_hash))
+    }
+
+    /// Append `link` under `entry_hash` as given (tests tamper through this)
+    pub(crate) fn push(&mut self, link: LinkCommit, entry_hash: String) -> LinkReceipt {
         let sequence = self.next_sequence();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let timestamp = unix_now();
 
         let entry = LedgerEntry {
             sequence,
@@ -110,6 +291,7 @@ impl Ledger {
             timestamp,
         };
 
+        self.balance += entry.link.physics_delta;
         self.chain.push(entry);
 
         LinkReceipt {
@@ -133,14 +315,64 @@ impl Ledger {
         Some(&self.chain[(sequence - 1) as usize])
     }
 
-    /// Calculate merkle root of all entries (simplified version)
+    /// Calculate merkle root of all entries (SPEC-UBL-LEDGER v1.0 §9)
     pub fn merkle_root_hex(&self) -> String {
-        if self.chain.is_empty() {
-            return "0".repeat(64);
+        merkle::to_hex(&merkle::root(&self.leaves(self.chain.len())))
+    }
+
+    /// Merkle root of the first `tree_size` entries, as it was when anchored
+    pub fn merkle_root_at(&self, tree_size: u64) -> Result<String> {
+        if tree_size > self.current_sequence() {
+            return Err(LedgerError::InvalidTreeSize {
+                old_size: tree_size,
+                new_size: self.current_sequence(),
+            });
         }
-        // Simplified: return last hash
-        self.last_hash()
+        Ok(merkle::to_hex(&merkle::root(&self.leaves(tree_size as usize))))
     }
+
+    /// Prove that the entry at `sequence` is included in the current root
+    pub fn inclusion_proof(&self, sequence: u64) -> Result<InclusionProof> {
+        if self.get_entry(sequence).is_none() {
+            return Err(LedgerError::EntryNotFound(sequence));
+        }
+        let leaves = self.leaves(self.chain.len());
+        let path = merkle::inclusion_path(&leaves, (sequence - 1) as usize);
+        Ok(InclusionProof {
+            sequence,
+            tree_size: self.current_sequence(),
+            path: path.iter().map(merkle::to_hex).collect(),
+        })
+    }
+
+    /// Prove that the tree of `old_size` entries is a prefix of the tree of `new_size`
+    pub fn consistency_proof(&self, old_size: u64, new_size: u64) -> Result<ConsistencyProof> {
+        if old_size == 0 || old_size > new_size || new_size > self.current_sequence() {
+            return Err(LedgerError::InvalidTreeSize { old_size, new_size });
+        }
+        let leaves = self.leaves(new_size as usize);
+        let path = merkle::consistency_path(&leaves, old_size as usize);
+        Ok(ConsistencyProof {
+            old_size,
+            new_size,
+            path: path.iter().map(merkle::to_hex).collect(),
+        })
+    }
+
+    fn leaves(&self, tree_size: usize) -> Vec<merkle::Hash> {
+        self.chain[..tree_size]
+            .iter()
+            .map(|e| merkle::leaf_from_entry_hash(&e.entry_hash))
+            .collect()
+    }
+}
+
+/// Current Unix time in seconds, stamped on appended entries
+pub(crate) fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
 }
 
 /// State projection from ledger (derived, not stored)
@@ -173,6 +405,7 @@ impl From<&Ledger> for LedgerState {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ubl_link::IntentClass;
 
     fn make_commit(seq: u64, prev_hash: &str, delta: i128) -> LinkCommit {
         LinkCommit {
@@ -183,6 +416,7 @@ mod tests {
             atom_hash: "atom".to_string(),
             intent_class: IntentClass::Conservation,
             physics_delta: delta,
+            constraints: Vec::new(),
             pact: None,
             author_pubkey: "pk".to_string(),
             signature: "sig".to_string(),
@@ -203,8 +437,10 @@ mod tests {
         let mut ledger = Ledger::new("wallet".to_string());
         let commit = make_commit(1, GENESIS_HASH, 100);
 
-        let receipt = ledger.append(commit, "hash1".to_string());
+        let expected = verify::entry_hash(&commit).unwrap();
+        let receipt = ledger.append(commit).unwrap();
 
+        assert_eq!(receipt.entry_hash, expected);
         assert_eq!(receipt.sequence, 1);
         assert_eq!(ledger.next_sequence(), 2);
         assert_eq!(ledger.physical_balance(), 100);
@@ -215,10 +451,10 @@ mod tests {
         let mut ledger = Ledger::new("wallet".to_string());
 
         let commit1 = make_commit(1, GENESIS_HASH, 100);
-        let receipt1 = ledger.append(commit1, "hash1".to_string());
+        let receipt1 = ledger.append(commit1).unwrap();
 
         let commit2 = make_commit(2, &receipt1.entry_hash, -30);
-        ledger.append(commit2, "hash2".to_string());
+        ledger.append(commit2).unwrap();
 
         assert_eq!(ledger.current_sequence(), 2);
         assert_eq!(ledger.physical_balance(), 70);
@@ -228,7 +464,7 @@ mod tests {
     fn test_state_projection() {
         let mut ledger = Ledger::new("wallet".to_string());
         let commit = make_commit(1, GENESIS_HASH, 50);
-        ledger.append(commit, "hash1".to_string());
+        ledger.append(commit).unwrap();
 
         let state: LedgerState = (&ledger).into();
 
@@ -236,4 +472,56 @@ mod tests {
         assert_eq!(state.sequence, 1);
         assert_eq!(state.physical_balance, 50);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_merkle_root_changes_with_history() {
+        let mut ledger = Ledger::new("wallet".to_string());
+        assert_eq!(ledger.merkle_root_hex(), GENESIS_HASH);
+
+        let receipt = ledger.append(make_commit(1, GENESIS_HASH, 10)).unwrap();
+        let root1 = ledger.merkle_root_hex();
+        assert_ne!(root1, receipt.entry_hash);
+
+        ledger.append(make_commit(2, &receipt.entry_hash, 10)).unwrap();
+        assert_ne!(ledger.merkle_root_hex(), root1);
+        assert_eq!(ledger.merkle_root_at(1).unwrap(), root1);
+    }
+
+    #[test]
+    fn test_inclusion_proof_against_anchor() {
+        let mut ledger = Ledger::new("wallet".to_string());
+        for seq in 1..=6 {
+            let prev = ledger.last_hash();
+            ledger.append(make_commit(seq, &prev, 1)).unwrap();
+        }
+        let anchor = ledger.merkle_root_hex();
+
+        let proof = ledger.inclusion_proof(4).unwrap();
+        let entry = ledger.get_entry(4).unwrap();
+        assert!(verify_inclusion(&entry.entry_hash, &proof, 6, &anchor).is_ok());
+        assert!(verify_inclusion("hash5", &proof, 6, &anchor).is_err());
+        assert!(matches!(
+            ledger.inclusion_proof(7),
+            Err(LedgerError::EntryNotFound(7))
+        ));
+    }
+
+    #[test]
+    fn test_consistency_proof_between_anchors() {
+        let mut ledger = Ledger::new("wallet".to_string());
+        for seq in 1..=3 {
+            let prev = ledger.last_hash();
+            ledger.append(make_commit(seq, &prev, 1)).unwrap();
+        }
+        let old_anchor = ledger.merkle_root_hex();
+        for seq in 4..=9 {
+            let prev = ledger.last_hash();
+            ledger.append(make_commit(seq, &prev, 1)).unwrap();
+        }
+        let new_anchor = ledger.merkle_root_hex();
+
+        let proof = ledger.consistency_proof(3, 9).unwrap();
+        assert!(verify_consistency(&old_anchor, &new_anchor, &proof).is_ok());
+        assert!(ledger.consistency_proof(3, 10).is_err());
+    }
+}
@@ -0,0 +1,476 @@
+//! Many containers behind one membrane (SPEC-UBL-LEDGER v1.0 §7)
+//!
+//! A [`LedgerStore`] holds a single container's chain, so a Conservation
+//! commit on its own can only move value within that container. The
+//! [`LedgerManager`] owns one store per container and adds the transfer
+//! primitive: a debit in one container and a credit in another, validated
+//! together by [`Membrane::validate_transfer`] and appended as a pair.
+//!
+//! Both legs are validated against their current tips before either is
+//! appended, so a rejected transfer leaves both chains untouched. The pair
+//! then commits as a unit: if the credit cannot be appended, the debit is
+//! rolled back and the manager reports [`LedgerError::TransferAborted`].
+//!
+//! Separate on-disk stores cannot share a transaction, so a manager built
+//! with [`LedgerManager::open`] stages each transfer in a journal first:
+//!
+//! ```text
+//! { "atom_hash": "...", "legs": [["<debit container>", <sequence before>],
+//!                                ["<credit container>", <sequence before>]] }
+//! ```
+//!
+//! The journal is fsynced before either leg is appended and removed once
+//! both are. Opening the manager replays a journal a crash left behind: if
+//! every leg holds the transfer atom the pair is kept, otherwise the legs
+//! that were appended are rolled back. A journal torn while being written
+//! staged nothing and is discarded.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ubl_link::{LinkCommit, LinkReceipt};
+use ubl_membrane::{LedgerState as MembraneState, Membrane, TransferLeg};
+
+use crate::segment::sync_dir;
+use crate::store::LedgerStore;
+use crate::{Ledger, LedgerError, Result};
+
+/// Receipts for both legs of a transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferReceipt {
+    /// Transfer atom both legs commit
+    pub atom_hash: String,
+    /// Receipt of the debit leg
+    pub debit: LinkReceipt,
+    /// Receipt of the credit leg
+    pub credit: LinkReceipt,
+}
+
+/// A transfer staged in the journal: each leg's container and the
+/// sequence it held before the transfer
+#[derive(Debug, Serialize, Deserialize)]
+struct Staged {
+    atom_hash: String,
+    legs: Vec<(String, u64)>,
+}
+
+/// One store per container, keyed by container id
+#[derive(Debug, Default)]
+pub struct LedgerManager<S: LedgerStore = Ledger> {
+    stores: BTreeMap<String, S>,
+    /// Where transfers are staged, if they must survive a crash
+    journal: Option<PathBuf>,
+}
+
+impl<S: LedgerStore> LedgerManager<S> {
+    /// Manager without containers or journal
+    pub fn new() -> Self {
+        Self {
+            stores: BTreeMap::new(),
+            journal: None,
+        }
+    }
+
+    /// Manage `stores`, staging transfers in `journal`, and first settle any
+    /// transfer a crash left there
+    pub fn open(journal: impl Into<PathBuf>, stores: impl IntoIterator<Item = S>) -> Result<Self> {
+        let mut manager = Self {
+            stores: BTreeMap::new(),
+            journal: Some(journal.into()),
+        };
+        for store in stores {
+            manager.insert(store);
+        }
+        manager.recover()?;
+        Ok(manager)
+    }
+
+    /// Manage `store`, returning the store it replaces
+    pub fn insert(&mut self, store: S) -> Option<S> {
+        self.stores.insert(store.container_id().to_string(), store)
+    }
+
+    /// Store of a container
+    pub fn get(&self, container_id: &str) -> Option<&S> {
+        self.stores.get(container_id)
+    }
+
+    /// Managed container ids, in order
+    pub fn containers(&self) -> impl Iterator<Item = &str> {
+        self.stores.keys().map(String::as_str)
+    }
+
+    /// State the membrane validates a container's next commit against
+    pub fn state(&self, container_id: &str) -> Result<MembraneState> {
+        let store = self.store(container_id)?;
+        Ok(MembraneState {
+            container_id: container_id.to_string(),
+            last_hash: store.last_hash(),
+            next_sequence: store.next_sequence(),
+            physical_balance: store.physical_balance(),
+        })
+    }
+
+    /// Validate a single signed commit and append it to its container
+    pub fn commit(&mut self, membrane: &Membrane<'_>, link: LinkCommit) -> Result<LinkReceipt> {
+        let state = self.state(&link.container_id)?;
+        membrane.validate_signed(&link, &state)?;
+        self.store_mut(&link.container_id)?.append(link)
+    }
+
+    /// Validate a debit/credit pair and append both legs, or neither
+    pub fn transfer(
+        &mut self,
+        membrane: &Membrane<'_>,
+        debit: LinkCommit,
+        credit: LinkCommit,
+    ) -> Result<TransferReceipt> {
+        self.recover()?;
+        let debit_state = self.state(&debit.container_id)?;
+        let credit_state = self.state(&credit.container_id)?;
+        membrane.validate_transfer(
+            TransferLeg::new(&debit, &debit_state),
+            TransferLeg::new(&credit, &credit_state),
+        )?;
+
+        let staged = Staged {
+            atom_hash: debit.atom_hash.clone(),
+            legs: vec![
+                (debit.container_id.clone(), debit_state.next_sequence - 1),
+                (credit.container_id.clone(), credit_state.next_sequence - 1),
+            ],
+        };
+        if let Some(path) = &self.journal {
+            write_journal(path, &staged)?;
+        }
+
+        let debit = match self.store_mut(&debit.container_id)?.append(debit) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                self.abort(&staged)?;
+                return Err(e);
+            }
+        };
+        let credit_container = credit.container_id.clone();
+        let credit = match self.store_mut(&credit_container)?.append(credit) {
+            Ok(receipt) => receipt,
+            Err(e) => {
+                self.abort(&staged)?;
+                return Err(LedgerError::TransferAborted {
+                    atom_hash: staged.atom_hash,
+                    container_id: credit_container,
+                    reason: e.to_string(),
+                });
+            }
+        };
+
+        // Both legs are durable; a journal left behind is kept by recovery
+        let _ = self.clear_journal();
+        Ok(TransferReceipt {
+            atom_hash: staged.atom_hash,
+            debit,
+            credit,
+        })
+    }
+
+    /// Settle a staged transfer: keep it if every leg was appended,
+    /// otherwise roll back the legs that were
+    fn recover(&mut self) -> Result<()> {
+        let Some(path) = &self.journal else {
+            return Ok(());
+        };
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let Ok(staged) = serde_json::from_slice::<Staged>(&bytes) else {
+            return self.clear_journal();
+        };
+
+        let mut appended = 0;
+        for (container_id, base) in &staged.legs {
+            if self.holds(container_id, base + 1, &staged.atom_hash)? {
+                appended += 1;
+            }
+        }
+        if appended == staged.legs.len() {
+            self.clear_journal()
+        } else {
+            self.abort(&staged)
+        }
+    }
+
+    /// Roll every leg of `staged` back to the sequence it held before, then
+    /// drop the journal; on failure the journal stays for the next recovery
+    fn abort(&mut self, staged: &Staged) -> Result<()> {
+        let unresolved = |reason: String| LedgerError::TransferUnresolved {
+            atom_hash: staged.atom_hash.clone(),
+            reason,
+        };
+        for (container_id, base) in &staged.legs {
+            let sequence = self.store(container_id)?.current_sequence();
+            if sequence == *base {
+                continue;
+            }
+            if sequence != base + 1 || !self.holds(container_id, sequence, &staged.atom_hash)? {
+                return Err(unresolved(format!(
+                    "{} moved past its staged leg",
+                    container_id
+                )));
+            }
+            self.store_mut(container_id)?
+                .rollback(*base)
+                .map_err(|e| unresolved(e.to_string()))?;
+        }
+        self.clear_journal()
+    }
+
+    /// Whether the entry at `sequence` commits `atom_hash`
+    fn holds(&self, container_id: &str, sequence: u64, atom_hash: &str) -> Result<bool> {
+        Ok(self
+            .store(container_id)?
+            .get_entry(sequence)?
+            .is_some_and(|entry| entry.link.atom_hash == atom_hash))
+    }
+
+    fn clear_journal(&self) -> Result<()> {
+        match &self.journal {
+            Some(path) => match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    fn store(&self, container_id: &str) -> Result<&S> {
+        self.stores
+            .get(container_id)
+            .ok_or_else(|| LedgerError::UnknownContainer(container_id.to_string()))
+    }
+
+    fn store_mut(&mut self, container_id: &str) -> Result<&mut S> {
+        self.stores
+            .get_mut(container_id)
+            .ok_or_else(|| LedgerError::UnknownContainer(container_id.to_string()))
+    }
+}
+
+/// Durably stage `staged` before any leg is appended
+fn write_journal(path: &Path, staged: &Staged) -> Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(&serde_json::to_vec(staged).expect("staged transfers serialize"))?;
+    file.sync_all()?;
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        sync_dir(dir)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Author};
+    use crate::SegmentStore;
+    use ubl_link::IntentClass;
+    use ubl_membrane::{BatchRejection, MembraneError};
+
+    struct Wallets<S: LedgerStore = Ledger> {
+        manager: LedgerManager<S>,
+        author: Author,
+    }
+
+    impl Wallets {
+        fn new() -> Self {
+            let mut manager = LedgerManager::new();
+            for id in ["alice", "bob"] {
+                manager.insert(Ledger::new(id.to_string()));
+            }
+            Self {
+                manager,
+                author: Author::new(),
+            }
+        }
+    }
+
+    impl Wallets<SegmentStore> {
+        /// Wallets stored under `dir`, journaled at `dir/transfer.journal`
+        fn open(dir: &Path) -> Self {
+            let stores = ["alice", "bob"].map(|id| SegmentStore::open(dir.join(id), id).unwrap());
+            Self {
+                manager: LedgerManager::open(dir.join("transfer.journal"), stores).unwrap(),
+                author: Author::new(),
+            }
+        }
+    }
+
+    impl<S: LedgerStore> Wallets<S> {
+        fn link(&self, container: &str, class: IntentClass, delta: i128, atom: &str) -> LinkCommit {
+            let state = self.manager.state(container).unwrap();
+            self.author.sign(LinkCommit {
+                atom_hash: atom.repeat(64),
+                ..testing::link(
+                    container,
+                    state.next_sequence,
+                    &state.last_hash,
+                    class,
+                    delta,
+                )
+            })
+        }
+
+        /// Genesis funding: only Entropy creates value, a lone Conservation credit is refused
+        fn fund(&mut self, container: &str, amount: i128) {
+            let link = self.link(container, IntentClass::Entropy, amount, "f");
+            self.manager.commit(&Membrane::new(), link).unwrap();
+        }
+
+        fn transfer(&mut self, amount: i128, credit: i128) -> Result<TransferReceipt> {
+            let debit = self.link("alice", IntentClass::Conservation, -amount, "7");
+            let credit = self.link("bob", IntentClass::Conservation, credit, "7");
+            self.manager.transfer(&Membrane::new(), debit, credit)
+        }
+
+        fn balance(&self, container: &str) -> i128 {
+            self.manager.get(container).unwrap().physical_balance()
+        }
+    }
+
+    #[test]
+    fn test_transfer_moves_value_between_containers() {
+        let mut wallets = Wallets::new();
+        wallets.fund("alice", 100);
+        let receipt = wallets.transfer(30, 30).unwrap();
+
+        assert_eq!((receipt.debit.sequence, receipt.credit.sequence), (2, 1));
+        assert_eq!((wallets.balance("alice"), wallets.balance("bob")), (70, 30));
+        let alice = wallets.manager.get("alice").unwrap();
+        let bob = wallets.manager.get("bob").unwrap();
+        assert_eq!(
+            alice.get_entry(2).unwrap().link.atom_hash,
+            bob.get_entry(1).unwrap().link.atom_hash
+        );
+    }
+
+    #[test]
+    fn test_rejected_transfer_touches_neither_container() {
+        let mut wallets = Wallets::new();
+        wallets.fund("alice", 10);
+
+        assert!(matches!(
+            wallets.transfer(30, 30),
+            Err(LedgerError::TransferRejected(BatchRejection {
+                index: 0,
+                error: MembraneError::PhysicsViolation { .. }
+            }))
+        ));
+        assert!(matches!(
+            wallets.transfer(5, 6),
+            Err(LedgerError::TransferRejected(BatchRejection {
+                index: 1,
+                ..
+            }))
+        ));
+        assert_eq!((wallets.balance("alice"), wallets.balance("bob")), (10, 0));
+        assert_eq!(wallets.manager.get("bob").unwrap().current_sequence(), 0);
+    }
+
+    #[test]
+    fn test_failed_credit_rolls_back_debit() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut wallets = Wallets::open(dir.path());
+        wallets.fund("alice", 100);
+
+        // Bob's segment vanishes, so the credit cannot be written
+        for item in fs::read_dir(dir.path().join("bob")).unwrap() {
+            fs::remove_file(item.unwrap().path()).unwrap();
+        }
+        assert!(matches!(
+            wallets.transfer(30, 30),
+            Err(LedgerError::TransferAborted { container_id, .. }) if container_id == "bob"
+        ));
+        assert_eq!(wallets.balance("alice"), 100);
+        assert!(!dir.path().join("transfer.journal").exists());
+
+        let alice = SegmentStore::open(dir.path().join("alice"), "alice").unwrap();
+        assert_eq!(
+            (alice.current_sequence(), alice.physical_balance()),
+            (1, 100)
+        );
+    }
+
+    #[test]
+    fn test_open_settles_a_staged_transfer() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("transfer.journal");
+
+        // Crash after the debit: the journal names a pair only half appended
+        let mut wallets = Wallets::open(dir.path());
+        wallets.fund("alice", 100);
+        let debit = wallets.link("alice", IntentClass::Conservation, -30, "7");
+        let staged = Staged {
+            atom_hash: debit.atom_hash.clone(),
+            legs: vec![("alice".to_string(), 1), ("bob".to_string(), 0)],
+        };
+        write_journal(&journal, &staged).unwrap();
+        wallets
+            .manager
+            .store_mut("alice")
+            .unwrap()
+            .append(debit)
+            .unwrap();
+        drop(wallets);
+
+        let mut wallets = Wallets::open(dir.path());
+        assert_eq!(wallets.manager.get("alice").unwrap().current_sequence(), 1);
+        assert_eq!((wallets.balance("alice"), wallets.balance("bob")), (100, 0));
+        assert!(!journal.exists());
+
+        // Crash after both legs, before the journal was cleared: keep the pair
+        let debit = wallets.link("alice", IntentClass::Conservation, -30, "7");
+        let credit = wallets.link("bob", IntentClass::Conservation, 30, "7");
+        write_journal(&journal, &staged).unwrap();
+        wallets
+            .manager
+            .store_mut("alice")
+            .unwrap()
+            .append(debit)
+            .unwrap();
+        wallets
+            .manager
+            .store_mut("bob")
+            .unwrap()
+            .append(credit)
+            .unwrap();
+        drop(wallets);
+
+        let wallets = Wallets::open(dir.path());
+        assert_eq!((wallets.balance("alice"), wallets.balance("bob")), (70, 30));
+        assert!(!journal.exists());
+
+        // A journal torn while being written staged nothing
+        fs::write(&journal, b"{\"atom_ha").unwrap();
+        drop(wallets);
+        let wallets = Wallets::open(dir.path());
+        assert_eq!((wallets.balance("alice"), wallets.balance("bob")), (70, 30));
+        assert!(!journal.exists());
+    }
+
+    #[test]
+    fn test_unknown_container() {
+        let mut wallets = Wallets::new();
+        let link = wallets.link("alice", IntentClass::Observation, 0, "a");
+        let stray = LinkCommit {
+            container_id: "carol".to_string(),
+            ..link
+        };
+        assert!(matches!(
+            wallets.manager.commit(&Membrane::new(), stray),
+            Err(LedgerError::UnknownContainer(id)) if id == "carol"
+        ));
+    }
+}
@@ -0,0 +1,473 @@
+//! Merkle tree over ledger entry hashes (SPEC-UBL-LEDGER v1.0 §9)
+//!
+//! The tree shape follows RFC 9162 (Certificate Transparency v2): a tree of
+//! `n` leaves splits at the largest power of two strictly smaller than `n`.
+//! This shape is what makes append-only consistency proofs possible.
+//!
+//! As in RFC 9162, leaves are `BLAKE3(0x00 || entry_hash)` and interior
+//! nodes `BLAKE3(0x01 || left || right)`, so no interior node can be passed
+//! off as a leaf with a shorter proof.
+//!
+//! All verifiers are stateless: an auditor holding a published root and the
+//! tree size it covers only needs the entry hash and the proof, never the
+//! chain itself.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LedgerError, Result};
+
+/// A 32-byte Merkle node
+pub type Hash = [u8; 32];
+
+/// Prefix of leaf hashes (RFC 9162 §2.1.1)
+const LEAF_PREFIX: u8 = 0x00;
+
+/// Prefix of interior node hashes (RFC 9162 §2.1.1)
+const NODE_PREFIX: u8 = 0x01;
+
+/// Proof that an entry is part of the tree of a given size
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Sequence of the proven entry (1-indexed)
+    pub sequence: u64,
+    /// Number of entries in the tree the proof is against
+    pub tree_size: u64,
+    /// Sibling hashes from the leaf up to the root (hex)
+    pub path: Vec<String>,
+}
+
+/// Proof that the tree of `old_size` is a prefix of the tree of `new_size`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyProof {
+    /// Size of the older (anchored) tree
+    pub old_size: u64,
+    /// Size of the newer tree
+    pub new_size: u64,
+    /// Intermediate node hashes (hex)
+    pub path: Vec<String>,
+}
+
+/// Turn an entry hash into a Merkle leaf
+///
+/// Entry hashes are hex-encoded Hash32 and are hashed as their 32 bytes.
+/// Anything else (legacy or test data) is hashed as its text.
+pub fn leaf_from_entry_hash(entry_hash: &str) -> Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[LEAF_PREFIX]);
+    match hex::decode(entry_hash) {
+        Ok(bytes) if bytes.len() == 32 => hasher.update(&bytes),
+        _ => hasher.update(entry_hash.as_bytes()),
+    };
+    *hasher.finalize().as_bytes()
+}
+
+/// Root of a tree over `leaves` (32 zero bytes for an empty tree)
+pub fn root(leaves: &[Hash]) -> Hash {
+    match leaves.len() {
+        0 => [0u8; 32],
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            node(&root(&leaves[..k]), &root(&leaves[k..]))
+        }
+    }
+}
+
+/// Right edge of a tree: the roots of its perfect subtrees, largest first
+///
+/// Enough to append leaves and recompute the root without the earlier
+/// leaves, so a checkpoint can extend the previous one's tree.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frontier {
+    size: u64,
+    peaks: Vec<Hash>,
+}
+
+impl Frontier {
+    /// Rebuild a frontier of `size` leaves from its hex peaks
+    pub fn from_hex(size: u64, peaks: &[String]) -> Result<Self> {
+        if peaks.len() != size.count_ones() as usize {
+            return Err(LedgerError::InvalidProof(format!(
+                "{} peaks for a tree of {}",
+                peaks.len(),
+                size
+            )));
+        }
+        Ok(Self {
+            size,
+            peaks: decode_path(peaks)?,
+        })
+    }
+
+    /// Number of leaves covered
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Peaks as hex, largest subtree first
+    pub fn to_hex(&self) -> Vec<String> {
+        self.peaks.iter().map(to_hex).collect()
+    }
+
+    /// Append a leaf, merging the perfect subtrees it completes
+    pub fn push(&mut self, leaf: Hash) {
+        let mut carry = leaf;
+        let mut size = self.size;
+        while size & 1 == 1 {
+            let left = self.peaks.pop().expect("one peak per set bit");
+            carry = node(&left, &carry);
+            size >>= 1;
+        }
+        self.peaks.push(carry);
+        self.size += 1;
+    }
+
+    /// Root of the tree, equal to [`root`] over the same leaves
+    pub fn root(&self) -> Hash {
+        let mut peaks = self.peaks.iter().rev();
+        let Some(last) = peaks.next() else {
+            return [0u8; 32];
+        };
+        peaks.fold(*last, |acc, peak| node(peak, &acc))
+    }
+}
+
+/// Audit path for the leaf at `index` (0-indexed)
+pub fn inclusion_path(leaves: &[Hash], index: usize) -> Vec<Hash> {
+    let n = leaves.len();
+    if n <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(n);
+    if index < k {
+        let mut path = inclusion_path(&leaves[..k], index);
+        path.push(root(&leaves[k..]));
+        path
+    } else {
+        let mut path = inclusion_path(&leaves[k..], index - k);
+        path.push(root(&leaves[..k]));
+        path
+    }
+}
+
+/// Consistency path between the first `old_size` leaves and all of `leaves`
+///
+/// Requires `0 < old_size <= leaves.len()`.
+pub fn consistency_path(leaves: &[Hash], old_size: usize) -> Vec<Hash> {
+    subproof(old_size, leaves, true)
+}
+
+fn subproof(m: usize, leaves: &[Hash], complete: bool) -> Vec<Hash> {
+    let n = leaves.len();
+    if m == n {
+        return if complete { Vec::new() } else { vec![root(leaves)] };
+    }
+    let k = split_point(n);
+    if m <= k {
+        let mut path = subproof(m, &leaves[..k], complete);
+        path.push(root(&leaves[k..]));
+        path
+    } else {
+        let mut path = subproof(m - k, &leaves[k..], false);
+        path.push(root(&leaves[..k]));
+        path
+    }
+}
+
+/// Verify that `entry_hash` is the leaf at `proof.sequence` of the tree of
+/// `tree_size` entries with `root_hex`
+///
+/// `tree_size` comes from the same source as the root (e.g. a signed
+/// checkpoint), never from the proof.
+pub fn verify_inclusion(
+    entry_hash: &str,
+    proof: &InclusionProof,
+    tree_size: u64,
+    root_hex: &str,
+) -> Result<()> {
+    if proof.tree_size != tree_size {
+        return Err(LedgerError::InvalidProof(format!(
+            "proof is for a tree of {}, root covers {}",
+            proof.tree_size, tree_size
+        )));
+    }
+    if proof.sequence == 0 || proof.sequence > proof.tree_size {
+        return Err(LedgerError::InvalidProof(format!(
+            "sequence {} outside tree of size {}",
+            proof.sequence, proof.tree_size
+        )));
+    }
+    let expected = decode_hash(root_hex)?;
+    let path = decode_path(&proof.path)?;
+
+    let mut index = proof.sequence - 1;
+    let mut last = proof.tree_size - 1;
+    let mut acc = leaf_from_entry_hash(entry_hash);
+
+    for sibling in &path {
+        if last == 0 {
+            return Err(LedgerError::InvalidProof("path too long".to_string()));
+        }
+        if index & 1 == 1 || index == last {
+            acc = node(sibling, &acc);
+            while index & 1 == 0 && index != 0 {
+                index >>= 1;
+                last >>= 1;
+            }
+        } else {
+            acc = node(&acc, sibling);
+        }
+        index >>= 1;
+        last >>= 1;
+    }
+
+    if last != 0 {
+        return Err(LedgerError::InvalidProof("path too short".to_string()));
+    }
+    if acc != expected {
+        return Err(LedgerError::InvalidProof("root mismatch".to_string()));
+    }
+    Ok(())
+}
+
+/// Verify that the tree with `old_root_hex` is a prefix of the tree with `new_root_hex`
+pub fn verify_consistency(
+    old_root_hex: &str,
+    new_root_hex: &str,
+    proof: &ConsistencyProof,
+) -> Result<()> {
+    let old_root = decode_hash(old_root_hex)?;
+    let new_root = decode_hash(new_root_hex)?;
+    let mut path = decode_path(&proof.path)?;
+
+    if proof.old_size == 0 || proof.old_size > proof.new_size {
+        return Err(LedgerError::InvalidTreeSize {
+            old_size: proof.old_size,
+            new_size: proof.new_size,
+        });
+    }
+
+    if proof.old_size == proof.new_size {
+        if !path.is_empty() {
+            return Err(LedgerError::InvalidProof("path too long".to_string()));
+        }
+        if old_root != new_root {
+            return Err(LedgerError::InvalidProof("root mismatch".to_string()));
+        }
+        return Ok(());
+    }
+
+    // A power-of-two old tree is a complete subtree of the new one, so its
+    // root is the starting node and is left implicit in the proof.
+    if proof.old_size.is_power_of_two() {
+        path.insert(0, old_root);
+    }
+    let (first, rest) = path
+        .split_first()
+        .ok_or_else(|| LedgerError::InvalidProof("empty path".to_string()))?;
+
+    let mut index = proof.old_size - 1;
+    let mut last = proof.new_size - 1;
+    while index & 1 == 1 {
+        index >>= 1;
+        last >>= 1;
+    }
+
+    let mut old_acc = *first;
+    let mut new_acc = *first;
+
+    for sibling in rest {
+        if last == 0 {
+            return Err(LedgerError::InvalidProof("path too long".to_string()));
+        }
+        if index & 1 == 1 || index == last {
+            old_acc = node(sibling, &old_acc);
+            new_acc = node(sibling, &new_acc);
+            while index & 1 == 0 && index != 0 {
+                index >>= 1;
+                last >>= 1;
+            }
+        } else {
+            new_acc = node(&new_acc, sibling);
+        }
+        index >>= 1;
+        last >>= 1;
+    }
+
+    if last != 0 {
+        return Err(LedgerError::InvalidProof("path too short".to_string()));
+    }
+    if old_acc != old_root || new_acc != new_root {
+        return Err(LedgerError::InvalidProof("root mismatch".to_string()));
+    }
+    Ok(())
+}
+
+/// Hex encoding of a node, matching the rest of the ledger's hash strings
+pub fn to_hex(hash: &Hash) -> String {
+    hex::encode(hash)
+}
+
+fn node(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// Largest power of two strictly smaller than `n` (n > 1)
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+fn decode_hash(hex_str: &str) -> Result<Hash> {
+    let bytes = hex::decode(hex_str)
+        .map_err(|_| LedgerError::InvalidProof(format!("invalid hash: {}", hex_str)))?;
+    if bytes.len() != 32 {
+        return Err(LedgerError::InvalidProof(format!("invalid hash: {}", hex_str)));
+    }
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&bytes);
+    Ok(out)
+}
+
+fn decode_path(path: &[String]) -> Result<Vec<Hash>> {
+    path.iter().map(|h| decode_hash(h)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(n: usize) -> Vec<String> {
+        (0..n)
+            .map(|i| to_hex(blake3::hash(format!("entry{i}").as_bytes()).as_bytes()))
+            .collect()
+    }
+
+    fn leaves(n: usize) -> Vec<Hash> {
+        entries(n).iter().map(|e| leaf_from_entry_hash(e)).collect()
+    }
+
+    fn hex_path(path: &[Hash]) -> Vec<String> {
+        path.iter().map(to_hex).collect()
+    }
+
+    #[test]
+    fn test_root_shapes() {
+        let l = leaves(3);
+        assert_eq!(root(&[]), [0u8; 32]);
+        assert_eq!(root(&l[..1]), l[0]);
+        assert_eq!(root(&l), node(&node(&l[0], &l[1]), &l[2]));
+    }
+
+    #[test]
+    fn test_frontier_matches_root() {
+        let l = leaves(17);
+        let mut frontier = Frontier::default();
+        assert_eq!(frontier.root(), root(&[]));
+        for n in 1..=17 {
+            frontier.push(l[n - 1]);
+            assert_eq!(frontier.root(), root(&l[..n]));
+
+            let restored = Frontier::from_hex(n as u64, &frontier.to_hex()).unwrap();
+            assert_eq!(restored, frontier);
+        }
+        assert!(Frontier::from_hex(16, &frontier.to_hex()).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_all_sizes() {
+        for n in 1..=17 {
+            let (e, l) = (entries(n), leaves(n));
+            let r = to_hex(&root(&l));
+            for (i, entry) in e.iter().enumerate() {
+                let proof = InclusionProof {
+                    sequence: i as u64 + 1,
+                    tree_size: n as u64,
+                    path: hex_path(&inclusion_path(&l, i)),
+                };
+                verify_inclusion(entry, &proof, n as u64, &r).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_inclusion_rejects_wrong_leaf() {
+        let (e, l) = (entries(5), leaves(5));
+        let proof = InclusionProof {
+            sequence: 3,
+            tree_size: 5,
+            path: hex_path(&inclusion_path(&l, 2)),
+        };
+        let r = to_hex(&root(&l));
+        assert!(verify_inclusion(&e[1], &proof, 5, &r).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_rejects_wrong_size() {
+        let (e, l) = (entries(5), leaves(5));
+        let proof = InclusionProof {
+            sequence: 1,
+            tree_size: 4,
+            path: hex_path(&inclusion_path(&l, 0)),
+        };
+        let r = to_hex(&root(&l));
+        assert!(verify_inclusion(&e[0], &proof, 4, &r).is_err());
+        assert!(verify_inclusion(&e[0], &proof, 5, &r).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_rejects_interior_node_as_leaf() {
+        let l = leaves(4);
+        let r = to_hex(&root(&l));
+        // The left half's root, offered as the first leaf of a tree of two
+        let forged = InclusionProof {
+            sequence: 1,
+            tree_size: 2,
+            path: vec![to_hex(&node(&l[2], &l[3]))],
+        };
+        let interior = to_hex(&node(&l[0], &l[1]));
+        assert!(verify_inclusion(&interior, &forged, 4, &r).is_err());
+        // Even against a size taken from the proof, the leaf prefix breaks it
+        assert!(verify_inclusion(&interior, &forged, 2, &r).is_err());
+    }
+
+    #[test]
+    fn test_consistency_all_sizes() {
+        for n in 1..=17 {
+            let l = leaves(n);
+            let new_root = to_hex(&root(&l));
+            for m in 1..=n {
+                let proof = ConsistencyProof {
+                    old_size: m as u64,
+                    new_size: n as u64,
+                    path: hex_path(&consistency_path(&l, m)),
+                };
+                let old_root = to_hex(&root(&l[..m]));
+                verify_consistency(&old_root, &new_root, &proof).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_consistency_rejects_rewritten_history() {
+        let l = leaves(7);
+        let mut forked = l.clone();
+        forked[1] = *blake3::hash(b"forged").as_bytes();
+
+        let proof = ConsistencyProof {
+            old_size: 3,
+            new_size: 7,
+            path: hex_path(&consistency_path(&l, 3)),
+        };
+        let forked_old_root = to_hex(&root(&forked[..3]));
+        let new_root = to_hex(&root(&l));
+        assert!(verify_consistency(&forked_old_root, &new_root, &proof).is_err());
+    }
+}
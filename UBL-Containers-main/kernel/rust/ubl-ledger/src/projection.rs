@@ -0,0 +1,640 @@
+//! Projections over ledger entries (SPEC-UBL-LEDGER v1.0 §8.2)
+//!
+//! `State := fold(H)`. A [`Projection`] is one such fold, fed entries in
+//! sequence order. The [`ProjectionRunner`] keeps several named projections
+//! up to date, either by catching up from a [`LedgerStore`] or by being
+//! handed entries one at a time as they arrive on a tail (e.g. the server's
+//! SSE stream).
+//!
+//! Each projection has a [`Cursor`]: the last sequence and entry hash it has
+//! applied, plus an optional snapshot of its state. Cursors are persisted in
+//! a [`CursorStore`] every `snapshot_every` entries, at the end of a catch-up
+//! and on [`flush`](ProjectionRunner::flush), so a restarted runner resumes
+//! from its last snapshot and re-applies the few entries after it instead of
+//! replaying from genesis. The entry hash pins the cursor to a history: if
+//! the ledger no longer has that hash at that sequence, the runner refuses to
+//! continue rather than fold two histories together.
+//!
+//! Ready-made projections for office and messenger views live in
+//! [`views`](crate::views); [`tail`](crate::tail) reads the server's SSE stream.
+
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::store::LedgerStore;
+use crate::{LedgerEntry, LedgerError, Result, GENESIS_HASH};
+
+/// Entries read per page while catching up
+const CATCH_UP_PAGE: usize = 1024;
+
+/// Entries a projection applies between snapshots, unless configured
+pub const DEFAULT_SNAPSHOT_EVERY: u64 = 1024;
+
+/// A projection rejected an entry
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{0}")]
+pub struct ProjectionError(pub String);
+
+/// A fold over ledger entries
+pub trait Projection: Any + Snapshot {
+    /// Unique, file-safe name (`[A-Za-z0-9._-]+`) the cursor is stored under
+    fn name(&self) -> &str;
+
+    /// Fold the next entry into the state
+    fn apply(&mut self, entry: &LedgerEntry) -> std::result::Result<(), ProjectionError>;
+}
+
+/// Projection state persisted with its cursor
+///
+/// Every `Serialize + DeserializeOwned` type snapshots as its JSON. A
+/// projection that keeps its state elsewhere (e.g. in a database of its own)
+/// implements this by hand and returns `None`.
+pub trait Snapshot {
+    /// State to persist with the cursor
+    fn snapshot(&self) -> Option<Value>;
+
+    /// Reload state saved by [`snapshot`](Snapshot::snapshot)
+    fn restore(&mut self, snapshot: Value) -> std::result::Result<(), ProjectionError>;
+}
+
+impl<T: Serialize + DeserializeOwned> Snapshot for T {
+    fn snapshot(&self) -> Option<Value> {
+        serde_json::to_value(self).ok()
+    }
+
+    fn restore(&mut self, snapshot: Value) -> std::result::Result<(), ProjectionError> {
+        *self = serde_json::from_value(snapshot).map_err(|e| ProjectionError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// How far a projection has read
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Last applied sequence (0 before the first entry)
+    pub sequence: u64,
+    /// Hash of the entry at `sequence`
+    pub last_hash: String,
+    /// Projection state as of `sequence`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<Value>,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            sequence: 0,
+            last_hash: GENESIS_HASH.to_string(),
+            snapshot: None,
+        }
+    }
+}
+
+/// Where cursors are persisted
+pub trait CursorStore {
+    /// Cursor saved for `name`, if any
+    fn load(&self, name: &str) -> Result<Option<Cursor>>;
+
+    /// Durably replace the cursor for `name`
+    fn save(&mut self, name: &str, cursor: &Cursor) -> Result<()>;
+}
+
+/// Cursors kept in memory (tests, ephemeral nodes)
+#[derive(Debug, Clone, Default)]
+pub struct MemoryCursors {
+    cursors: HashMap<String, Cursor>,
+}
+
+impl MemoryCursors {
+    /// Empty cursor set
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CursorStore for MemoryCursors {
+    fn load(&self, name: &str) -> Result<Option<Cursor>> {
+        Ok(self.cursors.get(name).cloned())
+    }
+
+    fn save(&mut self, name: &str, cursor: &Cursor) -> Result<()> {
+        self.cursors.insert(name.to_string(), cursor.clone());
+        Ok(())
+    }
+}
+
+/// Cursors stored as `<dir>/<name>.cursor.json`, replaced atomically
+#[derive(Debug, Clone)]
+pub struct FileCursors {
+    dir: PathBuf,
+}
+
+impl FileCursors {
+    /// Store cursors in `dir`, creating it if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+        })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.cursor.json", name))
+    }
+}
+
+impl CursorStore for FileCursors {
+    fn load(&self, name: &str) -> Result<Option<Cursor>> {
+        match fs::read(self.path(name)) {
+            Ok(bytes) => {
+                serde_json::from_slice(&bytes)
+                    .map(Some)
+                    .map_err(|e| LedgerError::Corrupt {
+                        path: self.path(name).display().to_string(),
+                        offset: 0,
+                        reason: e.to_string(),
+                    })
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&mut self, name: &str, cursor: &Cursor) -> Result<()> {
+        // Write aside and rename, so a crash leaves the old cursor or the new one
+        let path = self.path(name);
+        let tmp = path.with_extension("json.tmp");
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(&serde_json::to_vec(cursor).expect("cursor serializes"))?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+        #[cfg(unix)]
+        fs::File::open(&self.dir)?.sync_all()?;
+        Ok(())
+    }
+}
+
+struct Slot {
+    projection: Box<dyn Projection>,
+    cursor: Cursor,
+    /// Sequence of the cursor last persisted
+    saved: u64,
+}
+
+/// Keeps named projections up to date and their cursors persisted
+pub struct ProjectionRunner<C: CursorStore> {
+    cursors: C,
+    slots: Vec<Slot>,
+    snapshot_every: u64,
+}
+
+impl<C: CursorStore> ProjectionRunner<C> {
+    /// Runner persisting cursors in `cursors`
+    pub fn new(cursors: C) -> Self {
+        Self {
+            cursors,
+            slots: Vec::new(),
+            snapshot_every: DEFAULT_SNAPSHOT_EVERY,
+        }
+    }
+
+    /// Persist a projection's cursor once it is `entries` past its last snapshot
+    pub fn with_snapshot_every(mut self, entries: u64) -> Self {
+        self.snapshot_every = entries.max(1);
+        self
+    }
+
+    /// Add a projection, restoring it from its saved cursor
+    pub fn register<P: Projection>(&mut self, mut projection: P) -> Result<()> {
+        let name = projection.name().to_string();
+        let file_safe = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+        if name.is_empty() || !file_safe || self.slot(&name).is_some() {
+            return Err(LedgerError::InvalidProjection(name));
+        }
+
+        let mut cursor = self.cursors.load(&name)?.unwrap_or_default();
+        if let Some(snapshot) = cursor.snapshot.take() {
+            projection
+                .restore(snapshot)
+                .map_err(|e| failed(&name, cursor.sequence, e))?;
+        }
+        self.slots.push(Slot {
+            projection: Box::new(projection),
+            saved: cursor.sequence,
+            cursor,
+        });
+        Ok(())
+    }
+
+    /// Apply every entry of `store` that some projection has not seen yet
+    ///
+    /// Returns the number of entries read.
+    pub fn catch_up<S: LedgerStore + ?Sized>(&mut self, store: &S) -> Result<u64> {
+        for slot in &self.slots {
+            check_anchor(slot, store)?;
+        }
+
+        let mut read = 0;
+        let mut from = match self.slots.iter().map(|s| s.cursor.sequence).min() {
+            Some(sequence) => sequence + 1,
+            None => return Ok(0),
+        };
+        loop {
+            let page = store.read_range(from, CATCH_UP_PAGE)?;
+            for entry in &page {
+                for slot in &mut self.slots {
+                    if slot.cursor.sequence < entry.sequence {
+                        advance(slot, entry)?;
+                    }
+                }
+            }
+            self.persist(false)?;
+
+            read += page.len() as u64;
+            if page.len() < CATCH_UP_PAGE {
+                self.flush()?;
+                return Ok(read);
+            }
+            from += page.len() as u64;
+        }
+    }
+
+    /// Apply one entry pushed from a tail
+    ///
+    /// Entries a projection has already applied are skipped, so a tail that
+    /// replays a few entries after a reconnect is harmless. An entry beyond
+    /// the next sequence is a [`LedgerError::ProjectionGap`]: catch up from
+    /// the ledger, then continue with the tail ([`follow`](Self::follow)
+    /// does both). A refused entry leaves every projection untouched.
+    pub fn apply(&mut self, entry: &LedgerEntry) -> Result<()> {
+        for slot in &self.slots {
+            let next = slot.cursor.sequence + 1;
+            if entry.sequence > next {
+                return Err(LedgerError::ProjectionGap {
+                    name: slot.projection.name().to_string(),
+                    expected: next,
+                    found: entry.sequence,
+                });
+            }
+            if entry.sequence == next && entry.link.previous_hash != slot.cursor.last_hash {
+                return Err(diverged(slot));
+            }
+        }
+        for slot in &mut self.slots {
+            if slot.cursor.sequence + 1 == entry.sequence {
+                advance(slot, entry)?;
+            }
+        }
+        self.persist(false)
+    }
+
+    /// Apply entries from a tail as they arrive, catching up from `store`
+    /// whenever the tail skips ahead (e.g. after a reconnect); flushes when
+    /// the tail ends
+    pub fn follow<S, T>(&mut self, store: &S, tail: T) -> Result<()>
+    where
+        S: LedgerStore + ?Sized,
+        T: IntoIterator<Item = Result<LedgerEntry>>,
+    {
+        for entry in tail {
+            let entry = entry?;
+            match self.apply(&entry) {
+                Err(LedgerError::ProjectionGap { .. }) => {
+                    self.catch_up(store)?;
+                    self.apply(&entry)?;
+                }
+                applied => applied?,
+            }
+        }
+        self.flush()
+    }
+
+    /// Persist every cursor that moved since its last snapshot
+    pub fn flush(&mut self) -> Result<()> {
+        self.persist(true)
+    }
+
+    /// Cursor of a registered projection
+    pub fn cursor(&self, name: &str) -> Option<&Cursor> {
+        self.slot(name).map(|s| &s.cursor)
+    }
+
+    /// A registered projection, by name and type
+    pub fn get<P: Projection>(&self, name: &str) -> Option<&P> {
+        let projection: &dyn Any = self.slot(name)?.projection.as_ref();
+        projection.downcast_ref()
+    }
+
+    fn slot(&self, name: &str) -> Option<&Slot> {
+        self.slots.iter().find(|s| s.projection.name() == name)
+    }
+
+    /// Save the cursors that are due a snapshot, or every moved one if `all`
+    fn persist(&mut self, all: bool) -> Result<()> {
+        for slot in &mut self.slots {
+            let behind = slot.cursor.sequence - slot.saved;
+            if behind == 0 || (!all && behind < self.snapshot_every) {
+                continue;
+            }
+            let cursor = Cursor {
+                snapshot: slot.projection.snapshot(),
+                ..slot.cursor.clone()
+            };
+            self.cursors.save(slot.projection.name(), &cursor)?;
+            slot.saved = slot.cursor.sequence;
+        }
+        Ok(())
+    }
+}
+
+/// The cursor must still point at an entry of `store`'s history
+fn check_anchor<S: LedgerStore + ?Sized>(slot: &Slot, store: &S) -> Result<()> {
+    let anchored = match slot.cursor.sequence {
+        0 => true,
+        sequence => store
+            .get_entry(sequence)?
+            .is_some_and(|e| e.entry_hash == slot.cursor.last_hash),
+    };
+    if anchored {
+        Ok(())
+    } else {
+        Err(diverged(slot))
+    }
+}
+
+fn advance(slot: &mut Slot, entry: &LedgerEntry) -> Result<()> {
+    let name = slot.projection.name().to_string();
+    slot.projection
+        .apply(entry)
+        .map_err(|e| failed(&name, entry.sequence, e))?;
+    slot.cursor.sequence = entry.sequence;
+    slot.cursor.last_hash = entry.entry_hash.clone();
+    Ok(())
+}
+
+fn failed(name: &str, sequence: u64, source: ProjectionError) -> LedgerError {
+    LedgerError::Projection {
+        name: name.to_string(),
+        sequence,
+        source,
+    }
+}
+
+fn diverged(slot: &Slot) -> LedgerError {
+    LedgerError::CursorDiverged {
+        name: slot.projection.name().to_string(),
+        sequence: slot.cursor.sequence,
+    }
+}
+
+/// Running tip state: sequence, last hash and physical balance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceProjection {
+    /// Last applied sequence
+    pub sequence: u64,
+    /// Hash of the last applied entry
+    pub last_hash: String,
+    /// Σ physics_delta
+    pub physical_balance: i128,
+}
+
+impl BalanceProjection {
+    /// Name the cursor is stored under
+    pub const NAME: &'static str = "balance";
+}
+
+impl Default for BalanceProjection {
+    fn default() -> Self {
+        Self {
+            sequence: 0,
+            last_hash: GENESIS_HASH.to_string(),
+            physical_balance: 0,
+        }
+    }
+}
+
+impl Projection for BalanceProjection {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply(&mut self, entry: &LedgerEntry) -> std::result::Result<(), ProjectionError> {
+        self.sequence = entry.sequence;
+        self.last_hash = entry.entry_hash.clone();
+        self.physical_balance += entry.link.physics_delta;
+        Ok(())
+    }
+}
+
+/// Where each atom was committed, and by whom
+///
+/// The lookup index behind message and document views: atom hash to the
+/// sequences committing it, and author to the sequences they signed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AtomIndex {
+    /// atom_hash → sequences
+    pub atoms: BTreeMap<String, Vec<u64>>,
+    /// author_pubkey → sequences
+    pub authors: BTreeMap<String, Vec<u64>>,
+}
+
+impl AtomIndex {
+    /// Name the cursor is stored under
+    pub const NAME: &'static str = "atoms";
+
+    /// Sequences that committed `atom_hash`
+    pub fn sequences_of(&self, atom_hash: &str) -> &[u64] {
+        self.atoms.get(atom_hash).map_or(&[], Vec::as_slice)
+    }
+
+    /// Sequences signed by `author_pubkey`
+    pub fn signed_by(&self, author_pubkey: &str) -> &[u64] {
+        self.authors.get(author_pubkey).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl Projection for AtomIndex {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply(&mut self, entry: &LedgerEntry) -> std::result::Result<(), ProjectionError> {
+        let link = &entry.link;
+        self.atoms
+            .entry(link.atom_hash.clone())
+            .or_default()
+            .push(entry.sequence);
+        self.authors
+            .entry(link.author_pubkey.clone())
+            .or_default()
+            .push(entry.sequence);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Author};
+    use crate::{Ledger, SegmentStore};
+    use ubl_link::IntentClass;
+
+    /// Commit `count` Entropy links of 3 by `author`
+    fn extend(store: &mut impl LedgerStore, author: &Author, count: u64) {
+        testing::extend(store, author, count, IntentClass::Entropy, 3);
+    }
+
+    fn runner<C: CursorStore>(cursors: C) -> ProjectionRunner<C> {
+        let mut runner = ProjectionRunner::new(cursors);
+        runner.register(BalanceProjection::default()).unwrap();
+        runner.register(AtomIndex::default()).unwrap();
+        runner
+    }
+
+    fn balance<C: CursorStore>(runner: &ProjectionRunner<C>) -> &BalanceProjection {
+        runner.get(BalanceProjection::NAME).unwrap()
+    }
+
+    #[test]
+    fn test_catch_up_and_tail() {
+        let author = Author::new();
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 5);
+
+        let mut runner = runner(MemoryCursors::new());
+        assert_eq!(runner.catch_up(&ledger).unwrap(), 5);
+        assert_eq!(balance(&runner).physical_balance, 15);
+        let atoms: &AtomIndex = runner.get(AtomIndex::NAME).unwrap();
+        assert_eq!(atoms.sequences_of(&format!("{:064x}", 1)), [1, 3, 5]);
+        assert_eq!(atoms.signed_by(&author.pubkey).len(), 5);
+
+        // Tail: replays are skipped, gaps are refused
+        extend(&mut ledger, &author, 2);
+        runner.apply(ledger.get_entry(5).unwrap()).unwrap();
+        runner.apply(ledger.get_entry(6).unwrap()).unwrap();
+        assert_eq!(runner.cursor(BalanceProjection::NAME).unwrap().sequence, 6);
+        runner.register(BalanceProjection::default()).unwrap_err();
+
+        extend(&mut ledger, &author, 1);
+        assert!(matches!(
+            runner.apply(ledger.get_entry(8).unwrap()),
+            Err(LedgerError::ProjectionGap {
+                expected: 7,
+                found: 8,
+                ..
+            })
+        ));
+        assert_eq!(runner.catch_up(&ledger).unwrap(), 2);
+        assert_eq!(balance(&runner).physical_balance, 24);
+        assert_eq!(balance(&runner).last_hash, ledger.last_hash());
+    }
+
+    #[test]
+    fn test_refused_entry_moves_no_projection() {
+        let author = Author::new();
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 3);
+        let mut runner = ProjectionRunner::new(MemoryCursors::new());
+        runner.register(BalanceProjection::default()).unwrap();
+        runner.catch_up(&ledger).unwrap();
+
+        // A projection registered late is behind: the tail entry is a gap for it
+        runner.register(AtomIndex::default()).unwrap();
+        extend(&mut ledger, &author, 1);
+        assert!(matches!(
+            runner.apply(ledger.get_entry(4).unwrap()),
+            Err(LedgerError::ProjectionGap { expected: 1, .. })
+        ));
+        assert_eq!(runner.cursor(BalanceProjection::NAME).unwrap().sequence, 3);
+
+        // follow catches the late one up, then takes the tail
+        extend(&mut ledger, &author, 1);
+        let tail = [4, 5].map(|seq| Ok(ledger.get_entry(seq).unwrap().clone()));
+        runner.follow(&ledger, tail).unwrap();
+        assert_eq!(runner.cursor(AtomIndex::NAME).unwrap().sequence, 5);
+        assert_eq!(balance(&runner).physical_balance, 15);
+    }
+
+    #[test]
+    fn test_snapshots_periodically() {
+        let author = Author::new();
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 6);
+        let mut runner = ProjectionRunner::new(MemoryCursors::new()).with_snapshot_every(3);
+        runner.register(BalanceProjection::default()).unwrap();
+        let saved = |runner: &ProjectionRunner<MemoryCursors>| {
+            runner
+                .cursors
+                .load(BalanceProjection::NAME)
+                .unwrap()
+                .map_or(0, |c| c.sequence)
+        };
+
+        for seq in 1..=5 {
+            runner.apply(ledger.get_entry(seq).unwrap()).unwrap();
+        }
+        assert_eq!(saved(&runner), 3);
+        runner.apply(ledger.get_entry(6).unwrap()).unwrap();
+        assert_eq!(saved(&runner), 6);
+
+        extend(&mut ledger, &author, 1);
+        runner.apply(ledger.get_entry(7).unwrap()).unwrap();
+        assert_eq!(saved(&runner), 6);
+        runner.flush().unwrap();
+        assert_eq!(saved(&runner), 7);
+    }
+
+    #[test]
+    fn test_resumes_from_persisted_cursors() {
+        let dir = tempfile::tempdir().unwrap();
+        let author = Author::new();
+        let mut store = SegmentStore::open(dir.path().join("chain"), "wallet").unwrap();
+        extend(&mut store, &author, 4);
+        {
+            let mut runner = runner(FileCursors::open(dir.path().join("cursors")).unwrap());
+            runner.catch_up(&store).unwrap();
+        }
+
+        extend(&mut store, &author, 2);
+        let mut runner = runner(FileCursors::open(dir.path().join("cursors")).unwrap());
+        assert_eq!(balance(&runner).physical_balance, 12);
+        assert_eq!(runner.catch_up(&store).unwrap(), 2);
+        assert_eq!(balance(&runner).physical_balance, 18);
+    }
+
+    #[test]
+    fn test_refuses_cursor_from_another_history() {
+        let author = Author::new();
+        let mut ledger = Ledger::new("wallet".to_string());
+        extend(&mut ledger, &author, 3);
+        let mut cursors = MemoryCursors::new();
+        let mut first = runner(cursors.clone());
+        first.catch_up(&ledger).unwrap();
+        cursors
+            .save(
+                BalanceProjection::NAME,
+                first.cursor(BalanceProjection::NAME).unwrap(),
+            )
+            .unwrap();
+
+        // Shorter history: the cursor's entry no longer exists
+        let mut other = Ledger::new("wallet".to_string());
+        extend(&mut other, &author, 2);
+        let mut runner = ProjectionRunner::new(cursors);
+        runner.register(BalanceProjection::default()).unwrap();
+        assert!(matches!(
+            runner.catch_up(&other),
+            Err(LedgerError::CursorDiverged { sequence: 3, .. })
+        ));
+    }
+}
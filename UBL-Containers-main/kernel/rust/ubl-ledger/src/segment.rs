@@ -0,0 +1,784 @@
+//! Append-only segment files (SPEC-UBL-LEDGER v1.0 §7.2)
+//!
+//! ```text
+//! <dir>/<first_sequence:020>.seg
+//!
+//! segment := "UBLSEG01" || len:u32 BE || container_id || record*
+//! record  := len:u32 BE || checksum:[u8; 32] || payload
+//! payload := sequence:u64 BE || timestamp:i64 BE || entry_hash:str || link wire frame
+//!
+//! str := len:u32 BE || UTF-8 bytes
+//! ```
+//!
+//! `checksum` is the BLAKE3 hash of `payload`. An append is fsynced before
+//! its receipt is returned, and a new segment starts once the active one
+//! passes `max_segment_bytes`.
+//!
+//! On open every record is re-checked. A record cut short by the end of the
+//! newest segment (an incomplete length prefix or body) is a torn write from
+//! a crash: it was never acknowledged, so the file is truncated back to the
+//! last whole record. A whole record failing its checksum, or a short record
+//! anywhere else, is corruption and fails the open. An append that fails to
+//! write or sync cuts the file back to where the append started.
+//!
+//! A rollback deletes the segments after the cut, newest first, then
+//! truncates the segment holding it, so a crash midway still leaves a
+//! contiguous prefix of the chain.
+//!
+//! Checkpoints live next to the segments in `checkpoints.log`, one record per
+//! checkpoint with its JSON as payload, under the same framing and recovery.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use ubl_link::{LinkCommit, LinkReceipt};
+
+use crate::checkpoint::Checkpoint;
+use crate::store::{check_rollback, LedgerStore};
+use crate::verify::entry_hash;
+use crate::{unix_now, LedgerEntry, LedgerError, Result, GENESIS_HASH};
+
+/// Magic bytes opening every segment file
+pub const SEGMENT_MAGIC: &[u8; 8] = b"UBLSEG01";
+
+/// Default size after which a new segment is started (64 MiB)
+pub const DEFAULT_MAX_SEGMENT_BYTES: u64 = 64 * 1024 * 1024;
+
+const SEGMENT_EXT: &str = "seg";
+const CHECKPOINT_LOG: &str = "checkpoints.log";
+const RECORD_HEADER_LEN: usize = 4 + 32;
+
+/// A durable ledger stored as append-only segment files in one directory
+#[derive(Debug)]
+pub struct SegmentStore {
+    dir: PathBuf,
+    container_id: String,
+    max_segment_bytes: u64,
+    segments: Vec<PathBuf>,
+    /// `(segment, offset, record length)` for each sequence, 1-indexed
+    index: Vec<(usize, u64, usize)>,
+    /// Append handle to the newest segment, opened on first append
+    active: Option<File>,
+    active_len: u64,
+    last_hash: String,
+    balance: i128,
+    checkpoints: Vec<Checkpoint>,
+    truncated: u64,
+    /// A failed append could not be rolled back; appends refuse until reopened
+    broken: bool,
+}
+
+impl SegmentStore {
+    /// Open (or create) the ledger for `container_id` stored in `dir`
+    pub fn open(dir: impl AsRef<Path>, container_id: impl Into<String>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let container_id = container_id.into();
+        fs::create_dir_all(&dir)?;
+
+        let mut paths: Vec<(u64, PathBuf)> = Vec::new();
+        for item in fs::read_dir(&dir)? {
+            let path = item?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(SEGMENT_EXT) {
+                continue;
+            }
+            let first = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse::<u64>().ok())
+                .ok_or_else(|| corrupt(&path, 0, "segment name is not a sequence"))?;
+            paths.push((first, path));
+        }
+        paths.sort();
+        if paths.is_empty() {
+            create_segment(&dir, &container_id, 1)?;
+            paths.push((1, segment_path(&dir, 1)));
+        }
+
+        let mut store = Self {
+            active: None,
+            dir,
+            container_id,
+            max_segment_bytes: DEFAULT_MAX_SEGMENT_BYTES,
+            segments: Vec::new(),
+            index: Vec::new(),
+            active_len: 0,
+            last_hash: GENESIS_HASH.to_string(),
+            balance: 0,
+            checkpoints: Vec::new(),
+            truncated: 0,
+            broken: false,
+        };
+        let count = paths.len();
+        for (i, (first, path)) in paths.into_iter().enumerate() {
+            store.recover_segment(first, path, i + 1 == count)?;
+        }
+        store.recover_checkpoints()?;
+        Ok(store)
+    }
+
+    /// Start a new segment once the active one reaches `bytes`
+    pub fn with_max_segment_bytes(mut self, bytes: u64) -> Self {
+        self.max_segment_bytes = bytes;
+        self
+    }
+
+    /// Directory holding the segment files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of segment files
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// Bytes of torn tail dropped while opening
+    pub fn truncated_bytes(&self) -> u64 {
+        self.truncated
+    }
+
+    fn recover_segment(&mut self, first: u64, path: PathBuf, is_last: bool) -> Result<()> {
+        let bytes = fs::read(&path)?;
+        let expected = header(&self.container_id);
+        let segment = self.segments.len();
+
+        if bytes.len() < expected.len() && is_last && expected.starts_with(&bytes) {
+            // Crashed while starting this segment; nothing in it was acknowledged
+            self.truncated += bytes.len() as u64;
+            create_segment(&self.dir, &self.container_id, first)?;
+            self.segments.push(path);
+            self.active_len = expected.len() as u64;
+            return Ok(());
+        }
+        if !bytes.starts_with(SEGMENT_MAGIC) {
+            return Err(corrupt(&path, 0, "bad segment magic"));
+        }
+        if !bytes.starts_with(&expected) {
+            return Err(LedgerError::ContainerMismatch {
+                expected: self.container_id.clone(),
+                actual: read_header_container(&bytes).unwrap_or_default(),
+            });
+        }
+        if first != self.next_sequence() {
+            return Err(corrupt(
+                &path,
+                0,
+                "segment does not start at the next sequence",
+            ));
+        }
+
+        let mut pos = expected.len();
+        while pos < bytes.len() {
+            let record = match check_record(&bytes[pos..]) {
+                Record::Whole(record) => record,
+                Record::Incomplete if is_last => {
+                    self.truncated += truncate(&path, pos, bytes.len())?;
+                    break;
+                }
+                Record::Incomplete => return Err(corrupt(&path, pos, "torn record")),
+                Record::BadChecksum => return Err(corrupt(&path, pos, "checksum mismatch")),
+            };
+            let entry =
+                decode_payload(&record[RECORD_HEADER_LEN..]).map_err(|r| corrupt(&path, pos, r))?;
+            if entry.sequence != self.next_sequence() {
+                return Err(corrupt(&path, pos, "sequence gap"));
+            }
+            self.index.push((segment, pos as u64, record.len()));
+            self.last_hash = entry.entry_hash;
+            self.balance += entry.link.physics_delta;
+            pos += record.len();
+        }
+
+        self.segments.push(path);
+        self.active_len = pos as u64;
+        Ok(())
+    }
+
+    fn recover_checkpoints(&mut self) -> Result<()> {
+        let path = self.dir.join(CHECKPOINT_LOG);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut pos = 0;
+        while pos < bytes.len() {
+            let record = match check_record(&bytes[pos..]) {
+                Record::Whole(record) => record,
+                Record::Incomplete => {
+                    self.truncated += truncate(&path, pos, bytes.len())?;
+                    break;
+                }
+                Record::BadChecksum => return Err(corrupt(&path, pos, "checksum mismatch")),
+            };
+            let checkpoint = serde_json::from_slice(&record[RECORD_HEADER_LEN..])
+                .map_err(|e| corrupt(&path, pos, e.to_string()))?;
+            self.checkpoints.push(checkpoint);
+            pos += record.len();
+        }
+        Ok(())
+    }
+
+    fn roll(&mut self) -> Result<()> {
+        let first = self.next_sequence();
+        self.active = Some(create_segment(&self.dir, &self.container_id, first)?);
+        self.segments.push(segment_path(&self.dir, first));
+        self.active_len = header(&self.container_id).len() as u64;
+        Ok(())
+    }
+
+    fn read_record(&self, file: &mut File, sequence: u64) -> Result<LedgerEntry> {
+        let (segment, offset, len) = self.index[(sequence - 1) as usize];
+        let mut record = vec![0u8; len];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut record)?;
+
+        let path = &self.segments[segment];
+        let offset = offset as usize;
+        if !matches!(check_record(&record), Record::Whole(_)) {
+            return Err(corrupt(path, offset, "checksum mismatch"));
+        }
+        decode_payload(&record[RECORD_HEADER_LEN..]).map_err(|r| corrupt(path, offset, r))
+    }
+}
+
+impl LedgerStore for SegmentStore {
+    fn container_id(&self) -> &str {
+        &self.container_id
+    }
+
+    fn current_sequence(&self) -> u64 {
+        self.index.len() as u64
+    }
+
+    fn last_hash(&self) -> String {
+        self.last_hash.clone()
+    }
+
+    fn physical_balance(&self) -> i128 {
+        self.balance
+    }
+
+    fn append(&mut self, link: LinkCommit) -> Result<LinkReceipt> {
+        if self.broken {
+            let newest = self.segments.last().expect("open creates a segment");
+            let offset = self.active_len as usize;
+            return Err(corrupt(
+                newest,
+                offset,
+                "failed append left a partial record",
+            ));
+        }
+        let empty = self.active_len == header(&self.container_id).len() as u64;
+        if self.active_len >= self.max_segment_bytes && !empty {
+            self.roll()?;
+        }
+
+        let entry = LedgerEntry {
+            sequence: self.next_sequence(),
+            entry_hash: entry_hash(&link)?,
+            link,
+            timestamp: unix_now(),
+        };
+        let record = encode_record(&entry);
+        let active = match &mut self.active {
+            Some(file) => file,
+            None => {
+                let newest = self.segments.last().expect("open creates a segment");
+                self.active
+                    .insert(OpenOptions::new().append(true).open(newest)?)
+            }
+        };
+        if let Err(failure) = append_durably(active, self.active_len, &record) {
+            if failure.rolled_back.is_err() {
+                self.broken = true;
+                self.active = None;
+            }
+            return Err(failure.error.into());
+        }
+
+        self.index
+            .push((self.segments.len() - 1, self.active_len, record.len()));
+        self.active_len += record.len() as u64;
+        self.balance += entry.link.physics_delta;
+        self.last_hash = entry.entry_hash.clone();
+
+        Ok(LinkReceipt {
+            entry_hash: entry.entry_hash,
+            sequence: entry.sequence,
+            timestamp: entry.timestamp,
+            container_id: self.container_id.clone(),
+        })
+    }
+
+    fn rollback(&mut self, sequence: u64) -> Result<()> {
+        check_rollback(self, sequence)?;
+        if sequence == self.current_sequence() {
+            return Ok(());
+        }
+        let dropped: i128 = self
+            .read_range(sequence + 1, usize::MAX)?
+            .iter()
+            .map(|entry| entry.link.physics_delta)
+            .sum();
+        let last_hash = match self.get_entry(sequence)? {
+            Some(entry) => entry.entry_hash,
+            None => GENESIS_HASH.to_string(),
+        };
+
+        let (segment, offset, _) = self.index[sequence as usize];
+        self.active = None;
+        for path in self.segments[segment + 1..].iter().rev() {
+            fs::remove_file(path)?;
+        }
+        let file = OpenOptions::new()
+            .write(true)
+            .open(&self.segments[segment])?;
+        file.set_len(offset)?;
+        file.sync_all()?;
+        sync_dir(&self.dir)?;
+
+        self.segments.truncate(segment + 1);
+        self.index.truncate(sequence as usize);
+        self.active_len = offset;
+        self.balance -= dropped;
+        self.last_hash = last_hash;
+        self.broken = false;
+        Ok(())
+    }
+
+    fn get_entry(&self, sequence: u64) -> Result<Option<LedgerEntry>> {
+        if sequence == 0 || sequence > self.current_sequence() {
+            return Ok(None);
+        }
+        let (segment, _, _) = self.index[(sequence - 1) as usize];
+        let mut file = File::open(&self.segments[segment])?;
+        self.read_record(&mut file, sequence).map(Some)
+    }
+
+    fn save_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        checkpoint.verify_against(self)?;
+        let payload = serde_json::to_vec(&checkpoint).expect("checkpoint serializes");
+
+        let path = self.dir.join(CHECKPOINT_LOG);
+        let fresh = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let start = file.metadata()?.len();
+        append_durably(&mut file, start, &frame(&payload)).map_err(|f| f.error)?;
+        if fresh {
+            sync_dir(&self.dir)?;
+        }
+        self.checkpoints.push(checkpoint);
+        Ok(())
+    }
+
+    fn latest_checkpoint(&self) -> Result<Option<Checkpoint>> {
+        Ok(self.checkpoints.last().cloned())
+    }
+
+    fn read_range(&self, from: u64, limit: usize) -> Result<Vec<LedgerEntry>> {
+        let mut out = Vec::new();
+        let mut open: Option<(usize, File)> = None;
+        let from = from.max(1);
+        let to = from.saturating_add(limit as u64).min(self.next_sequence());
+        for sequence in from..to {
+            let (segment, _, _) = self.index[(sequence - 1) as usize];
+            let file = match &mut open {
+                Some((current, file)) if *current == segment => file,
+                _ => {
+                    let file = File::open(&self.segments[segment])?;
+                    &mut open.insert((segment, file)).1
+                }
+            };
+            out.push(self.read_record(file, sequence)?);
+        }
+        Ok(out)
+    }
+}
+
+fn segment_path(dir: &Path, first_sequence: u64) -> PathBuf {
+    dir.join(format!("{:020}.{}", first_sequence, SEGMENT_EXT))
+}
+
+fn header(container_id: &str) -> Vec<u8> {
+    let mut out = SEGMENT_MAGIC.to_vec();
+    write_str(&mut out, container_id);
+    out
+}
+
+fn read_header_container(bytes: &[u8]) -> Option<String> {
+    let rest = bytes.get(SEGMENT_MAGIC.len()..)?;
+    let (container_id, _) = read_str(rest)?;
+    Some(container_id)
+}
+
+/// Create the segment starting at `first_sequence` and open it for appending
+fn create_segment(dir: &Path, container_id: &str, first_sequence: u64) -> Result<File> {
+    let path = segment_path(dir, first_sequence);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(&path)?;
+    file.write_all(&header(container_id))?;
+    file.sync_all()?;
+    sync_dir(dir)?;
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+/// Make a newly created file's directory entry durable
+pub(crate) fn sync_dir(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+    #[cfg(not(unix))]
+    let _ = dir;
+    Ok(())
+}
+
+fn encode_record(entry: &LedgerEntry) -> Vec<u8> {
+    let mut payload = Vec::new();
+    payload.extend_from_slice(&entry.sequence.to_be_bytes());
+    payload.extend_from_slice(&entry.timestamp.to_be_bytes());
+    write_str(&mut payload, &entry.entry_hash);
+    payload.extend_from_slice(&entry.link.to_wire());
+    frame(&payload)
+}
+
+/// Wrap a payload in a length- and checksum-prefixed record
+fn frame(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + payload.len());
+    write_len(&mut record, payload.len());
+    record.extend_from_slice(blake3::hash(payload).as_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+/// What starts at some offset of a record file
+enum Record<'a> {
+    /// A complete record whose checksum holds
+    Whole(&'a [u8]),
+    /// The bytes end inside the length prefix, checksum or payload
+    Incomplete,
+    /// A complete record whose payload does not match its checksum
+    BadChecksum,
+}
+
+fn check_record(bytes: &[u8]) -> Record<'_> {
+    let Some(prefix) = bytes.get(..4) else {
+        return Record::Incomplete;
+    };
+    let len = u32::from_be_bytes(prefix.try_into().unwrap()) as usize;
+    let Some(record) = bytes.get(..RECORD_HEADER_LEN + len) else {
+        return Record::Incomplete;
+    };
+    let payload = &record[RECORD_HEADER_LEN..];
+    if blake3::hash(payload).as_bytes()[..] == record[4..RECORD_HEADER_LEN] {
+        Record::Whole(record)
+    } else {
+        Record::BadChecksum
+    }
+}
+
+/// Cut the torn tail at `pos` off `path`, returning how many bytes went
+fn truncate(path: &Path, pos: usize, len: usize) -> Result<u64> {
+    let file = OpenOptions::new().write(true).open(path)?;
+    file.set_len(pos as u64)?;
+    file.sync_all()?;
+    Ok((len - pos) as u64)
+}
+
+/// An append that failed, and whether the file was cut back afterwards
+struct AppendFailure {
+    error: std::io::Error,
+    rolled_back: std::io::Result<()>,
+}
+
+/// Write `bytes` at `start`, the file's current end, and fsync; on failure
+/// truncate back to `start` so no partial record is left behind
+fn append_durably(
+    file: &mut File,
+    start: u64,
+    bytes: &[u8],
+) -> std::result::Result<(), AppendFailure> {
+    file.write_all(bytes)
+        .and_then(|()| file.sync_data())
+        .map_err(|error| AppendFailure {
+            error,
+            rolled_back: file.set_len(start).and_then(|()| file.sync_data()),
+        })
+}
+
+fn decode_payload(payload: &[u8]) -> std::result::Result<LedgerEntry, String> {
+    let truncated = || "truncated payload".to_string();
+    let sequence = u64::from_be_bytes(payload.get(..8).ok_or_else(truncated)?.try_into().unwrap());
+    let timestamp = i64::from_be_bytes(
+        payload
+            .get(8..16)
+            .ok_or_else(truncated)?
+            .try_into()
+            .unwrap(),
+    );
+    let (entry_hash, used) = read_str(&payload[16..]).ok_or_else(truncated)?;
+    let link = LinkCommit::from_wire(&payload[16 + used..]).map_err(|e| e.to_string())?;
+    Ok(LedgerEntry {
+        sequence,
+        entry_hash,
+        link,
+        timestamp,
+    })
+}
+
+fn write_str(out: &mut Vec<u8>, value: &str) {
+    write_len(out, value.len());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).expect("ledger records are shorter than 4 GiB");
+    out.extend_from_slice(&len.to_be_bytes());
+}
+
+/// Read a length-prefixed string, returning it and the bytes consumed
+fn read_str(bytes: &[u8]) -> Option<(String, usize)> {
+    let len = u32::from_be_bytes(bytes.get(..4)?.try_into().ok()?) as usize;
+    let raw = bytes.get(4..4usize.checked_add(len)?)?;
+    Some((String::from_utf8(raw.to_vec()).ok()?, 4 + len))
+}
+
+fn corrupt(path: &Path, offset: usize, reason: impl Into<String>) -> LedgerError {
+    LedgerError::Corrupt {
+        path: path.display().to_string(),
+        offset: offset as u64,
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ledger;
+    use ubl_link::IntentClass;
+
+    fn commit(seq: u64, prev_hash: &str, delta: i128) -> LinkCommit {
+        LinkCommit {
+            version: 1,
+            container_id: "wallet".to_string(),
+            expected_sequence: seq,
+            previous_hash: prev_hash.to_string(),
+            atom_hash: "atom".to_string(),
+            intent_class: IntentClass::Conservation,
+            physics_delta: delta,
+            constraints: Vec::new(),
+            pact: None,
+            author_pubkey: "pk".to_string(),
+            signature: "sig".to_string(),
+        }
+    }
+
+    fn fill(store: &mut impl LedgerStore, count: u64) {
+        for _ in 0..count {
+            let seq = store.next_sequence();
+            let prev = store.last_hash();
+            store.append(commit(seq, &prev, 10)).unwrap();
+        }
+    }
+
+    fn last_segment(dir: &Path) -> PathBuf {
+        let mut paths: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        paths.sort();
+        paths.pop().unwrap()
+    }
+
+    #[test]
+    fn test_matches_in_memory_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut disk = SegmentStore::open(dir.path(), "wallet").unwrap();
+        let mut memory = Ledger::new("wallet".to_string());
+        fill(&mut disk, 5);
+        fill(&mut memory, 5);
+
+        assert_eq!(disk.current_sequence(), 5);
+        assert_eq!(LedgerStore::last_hash(&disk), memory.last_hash());
+        assert_eq!(LedgerStore::physical_balance(&disk), 50);
+        let from_disk = disk.read_range(2, 3).unwrap();
+        let from_memory = memory.read_range(2, 3).unwrap();
+        assert_eq!(from_disk.len(), 3);
+        for (a, b) in from_disk.iter().zip(&from_memory) {
+            assert_eq!(
+                (a.sequence, &a.entry_hash, &a.link),
+                (b.sequence, &b.entry_hash, &b.link)
+            );
+        }
+        assert!(disk.get_entry(6).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_reopen_restores_tip() {
+        let dir = tempfile::tempdir().unwrap();
+        let tip = {
+            let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+            fill(&mut store, 3);
+            store.last_hash()
+        };
+        let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        assert_eq!(store.current_sequence(), 3);
+        assert_eq!(store.last_hash(), tip);
+        assert_eq!(store.physical_balance(), 30);
+        assert_eq!(store.truncated_bytes(), 0);
+
+        fill(&mut store, 1);
+        assert_eq!(
+            store.get_entry(4).unwrap().unwrap().link.expected_sequence,
+            4
+        );
+    }
+
+    #[test]
+    fn test_rolls_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), "wallet")
+                .unwrap()
+                .with_max_segment_bytes(256);
+            fill(&mut store, 10);
+            assert!(store.segment_count() > 1);
+        }
+        let store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        let entries = store.read_range(1, 100).unwrap();
+        assert_eq!(entries.len(), 10);
+        assert!(entries.iter().zip(1..).all(|(e, seq)| e.sequence == seq));
+    }
+
+    #[test]
+    fn test_rollback_drops_tail_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let tip = {
+            let mut store = SegmentStore::open(dir.path(), "wallet")
+                .unwrap()
+                .with_max_segment_bytes(256);
+            fill(&mut store, 4);
+            let tip = store.last_hash();
+            fill(&mut store, 6);
+            let segments = store.segment_count();
+
+            store.rollback(4).unwrap();
+            assert!(store.segment_count() < segments);
+            assert_eq!(
+                (store.current_sequence(), store.physical_balance()),
+                (4, 40)
+            );
+            assert_eq!(store.last_hash(), tip);
+            fill(&mut store, 1);
+            tip
+        };
+        let store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        assert_eq!(
+            (store.current_sequence(), store.physical_balance()),
+            (5, 50)
+        );
+        assert_eq!(store.get_entry(5).unwrap().unwrap().link.previous_hash, tip);
+        assert_eq!(store.truncated_bytes(), 0);
+    }
+
+    #[test]
+    fn test_truncates_torn_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+            fill(&mut store, 3);
+        }
+        // Simulate a crash halfway through writing a fourth record
+        let path = last_segment(dir.path());
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0, 0, 1, 0, 7, 7, 7]).unwrap();
+        drop(file);
+
+        let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        assert_eq!(store.current_sequence(), 3);
+        assert_eq!(store.truncated_bytes(), 7);
+
+        fill(&mut store, 1);
+        drop(store);
+        let store = SegmentStore::open(dir.path(), "wallet").unwrap();
+        assert_eq!(store.current_sequence(), 4);
+        assert_eq!(store.truncated_bytes(), 0);
+    }
+
+    #[test]
+    fn test_rejects_checksum_failure_in_newest_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), "wallet").unwrap();
+            fill(&mut store, 3);
+        }
+        let path = last_segment(dir.path());
+        let pristine = fs::read(&path).unwrap();
+
+        // A whole record was acknowledged, wherever it sits: never truncate it
+        let first_payload = header("wallet").len() + RECORD_HEADER_LEN;
+        for pos in [first_payload, pristine.len() - 1] {
+            let mut bytes = pristine.clone();
+            bytes[pos] ^= 0xff;
+            fs::write(&path, &bytes).unwrap();
+
+            assert!(matches!(
+                SegmentStore::open(dir.path(), "wallet"),
+                Err(LedgerError::Corrupt { .. })
+            ));
+            assert_eq!(fs::read(&path).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_failed_append_rolls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("log");
+        fs::write(&path, b"acked").unwrap();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        assert!(append_durably(&mut file, 5, b"+next").is_ok());
+        assert_eq!(fs::read(&path).unwrap(), b"acked+next");
+
+        // A handle that cannot write leaves the file where it was
+        let mut file = File::open(&path).unwrap();
+        assert!(append_durably(&mut file, 10, b"+lost").is_err());
+        assert_eq!(fs::read(&path).unwrap(), b"acked+next");
+    }
+
+    #[test]
+    fn test_rejects_damage_in_sealed_segment() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), "wallet")
+                .unwrap()
+                .with_max_segment_bytes(256);
+            fill(&mut store, 10);
+        }
+        let first = segment_path(dir.path(), 1);
+        let mut bytes = fs::read(&first).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&first, &bytes).unwrap();
+
+        assert!(matches!(
+            SegmentStore::open(dir.path(), "wallet"),
+            Err(LedgerError::Corrupt { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_other_container() {
+        let dir = tempfile::tempdir().unwrap();
+        drop(SegmentStore::open(dir.path(), "wallet").unwrap());
+
+        assert!(matches!(
+            SegmentStore::open(dir.path(), "other"),
+            Err(LedgerError::ContainerMismatch { .. })
+        ));
+    }
+}
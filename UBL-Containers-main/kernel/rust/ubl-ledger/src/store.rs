@@ -0,0 +1,207 @@
+//! Storage backends for the ledger (SPEC-UBL-LEDGER v1.0 §4)
+//!
+//! [`LedgerStore`] is the append-only contract every backend honours: entries
+//! are only ever added at the tip, never rewritten. The in-memory [`Ledger`]
+//! implements it directly; [`SegmentStore`](crate::segment::SegmentStore)
+//! persists the same entries to disk. The one exception is
+//! [`rollback`](LedgerStore::rollback), which undoes the staged legs of a
+//! transfer that did not complete, before anything else builds on them.
+//!
+//! Stores also keep signed [`Checkpoint`]s alongside the chain, so state can
+//! be resumed from the latest one instead of folded from genesis.
+
+use ubl_kernel::SigningKey;
+use ubl_link::{LinkCommit, LinkReceipt};
+
+use crate::checkpoint::Checkpoint;
+use crate::verify::{ChainSummary, ChainVerifier};
+use crate::{merkle, Ledger, LedgerEntry, LedgerError, LedgerState, Result};
+
+/// Entries read per page while verifying
+const VERIFY_PAGE: usize = 1024;
+
+/// Append-only storage for one container's chain
+pub trait LedgerStore {
+    /// Container this store belongs to
+    fn container_id(&self) -> &str;
+
+    /// Sequence of the last entry (0 if empty)
+    fn current_sequence(&self) -> u64;
+
+    /// Hash of the last entry (or the genesis hash)
+    fn last_hash(&self) -> String;
+
+    /// Sum of all physics deltas
+    fn physical_balance(&self) -> i128;
+
+    /// Append a validated commit at the tip, deriving its entry hash from
+    /// the link (`hash_link`, then `hash_entry`)
+    /// NOTE: Validation should be done by the membrane before calling this
+    fn append(&mut self, link: LinkCommit) -> Result<LinkReceipt>;
+
+    /// Drop every entry after `sequence`; only for undoing a transfer leg
+    /// whose pair failed. Refused below the latest checkpoint
+    fn rollback(&mut self, sequence: u64) -> Result<()>;
+
+    /// Read the entry at `sequence`, if present
+    fn get_entry(&self, sequence: u64) -> Result<Option<LedgerEntry>>;
+
+    /// Keep a checkpoint of this chain (rejected unless it is anchored in it)
+    fn save_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()>;
+
+    /// Most recently saved checkpoint
+    fn latest_checkpoint(&self) -> Result<Option<Checkpoint>>;
+
+    /// Next expected sequence number
+    fn next_sequence(&self) -> u64 {
+        self.current_sequence() + 1
+    }
+
+    /// Re-derive every entry and report the first broken sequence
+    fn verify_chain(&self) -> Result<ChainSummary> {
+        verify_from(self, ChainVerifier::new(self.container_id()))
+    }
+
+    /// Sign and save a checkpoint of the current tip
+    fn checkpoint(&mut self, key: &SigningKey) -> Result<Checkpoint> {
+        let mut leaves = Vec::new();
+        loop {
+            let page = self.read_range(leaves.len() as u64 + 1, VERIFY_PAGE)?;
+            leaves.extend(
+                page.iter()
+                    .map(|e| merkle::leaf_from_entry_hash(&e.entry_hash)),
+            );
+            if page.len() < VERIFY_PAGE {
+                break;
+            }
+        }
+        let state = LedgerState {
+            container_id: self.container_id().to_string(),
+            sequence: self.current_sequence(),
+            last_hash: self.last_hash(),
+            physical_balance: self.physical_balance(),
+            merkle_root: merkle::to_hex(&merkle::root(&leaves)),
+        };
+        let checkpoint = Checkpoint::sign(state, key);
+        self.save_checkpoint(checkpoint.clone())?;
+        Ok(checkpoint)
+    }
+
+    /// Tip state resumed from the latest checkpoint signed by `signer`
+    ///
+    /// Only the entries after the checkpoint are re-verified and folded; with
+    /// no checkpoint this is [`verify_chain`](LedgerStore::verify_chain).
+    fn resume(&self, signer: &str) -> Result<ChainSummary> {
+        let verifier = match self.latest_checkpoint()? {
+            Some(checkpoint) => {
+                checkpoint.verify(signer)?;
+                checkpoint.verify_against(self)?;
+                ChainVerifier::resume(&checkpoint)
+            }
+            None => ChainVerifier::new(self.container_id()),
+        };
+        verify_from(self, verifier)
+    }
+
+    /// Read up to `limit` entries starting at sequence `from`
+    fn read_range(&self, from: u64, limit: usize) -> Result<Vec<LedgerEntry>> {
+        let mut out = Vec::new();
+        let mut sequence = from.max(1);
+        while out.len() < limit {
+            match self.get_entry(sequence)? {
+                Some(entry) => out.push(entry),
+                None => break,
+            }
+            sequence += 1;
+        }
+        Ok(out)
+    }
+}
+
+impl LedgerStore for Ledger {
+    fn container_id(&self) -> &str {
+        Ledger::container_id(self)
+    }
+
+    fn current_sequence(&self) -> u64 {
+        Ledger::current_sequence(self)
+    }
+
+    fn last_hash(&self) -> String {
+        Ledger::last_hash(self)
+    }
+
+    fn physical_balance(&self) -> i128 {
+        Ledger::physical_balance(self)
+    }
+
+    fn append(&mut self, link: LinkCommit) -> Result<LinkReceipt> {
+        Ledger::append(self, link)
+    }
+
+    fn rollback(&mut self, sequence: u64) -> Result<()> {
+        check_rollback(self, sequence)?;
+        for entry in self.chain.drain(sequence as usize..) {
+            self.balance -= entry.link.physics_delta;
+        }
+        Ok(())
+    }
+
+    fn get_entry(&self, sequence: u64) -> Result<Option<LedgerEntry>> {
+        Ok(Ledger::get_entry(self, sequence).cloned())
+    }
+
+    fn save_checkpoint(&mut self, checkpoint: Checkpoint) -> Result<()> {
+        checkpoint.verify_against(self)?;
+        self.checkpoints.push(checkpoint);
+        Ok(())
+    }
+
+    fn latest_checkpoint(&self) -> Result<Option<Checkpoint>> {
+        Ok(self.checkpoints.last().cloned())
+    }
+
+    fn read_range(&self, from: u64, limit: usize) -> Result<Vec<LedgerEntry>> {
+        let start = from.max(1).min(self.next_sequence()) as usize - 1;
+        Ok(self
+            .entries()
+            .iter()
+            .skip(start)
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+}
+
+/// Check that `store` can roll back to `sequence`: an existing entry no
+/// older than the latest checkpoint
+pub(crate) fn check_rollback<S: LedgerStore + ?Sized>(store: &S, sequence: u64) -> Result<()> {
+    if sequence > store.current_sequence() {
+        return Err(LedgerError::EntryNotFound(sequence));
+    }
+    match store.latest_checkpoint()? {
+        Some(checkpoint) if checkpoint.sequence > sequence => {
+            Err(LedgerError::RollbackPastCheckpoint {
+                sequence,
+                checkpoint: checkpoint.sequence,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Feed every entry from the verifier's next sequence to the tip
+fn verify_from<S: LedgerStore + ?Sized>(
+    store: &S,
+    mut verifier: ChainVerifier,
+) -> Result<ChainSummary> {
+    loop {
+        let page = store.read_range(verifier.next_sequence(), VERIFY_PAGE)?;
+        for entry in &page {
+            verifier.check(entry)?;
+        }
+        if page.len() < VERIFY_PAGE {
+            return Ok(verifier.finish());
+        }
+    }
+}
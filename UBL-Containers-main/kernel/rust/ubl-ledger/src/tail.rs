@@ -0,0 +1,201 @@
+//! Reading the server's SSE tail (`GET /ledger/:container_id/tail`)
+//!
+//! The server streams every appended `ledger_entry` row as JSON in an SSE
+//! event named `ledger_entry`, with the signed link draft in `metadata`
+//! (`physics_delta` as a decimal string). [`SseTail`] reads that stream from
+//! any [`BufRead`] and yields [`LedgerEntry`]s for
+//! [`ProjectionRunner::follow`](crate::ProjectionRunner::follow).
+//!
+//! Keep-alive comments and other events are skipped. The tail only carries
+//! what the server accepted; it is not verified here, so projections that
+//! must not trust the server catch up from a verified store instead.
+
+use std::io::BufRead;
+
+use serde::Deserialize;
+use ubl_link::{Constraint, IntentClass, LinkCommit, PactProof};
+
+use crate::{LedgerEntry, LedgerError, Result};
+
+/// SSE event name the server gives appended entries
+pub const ENTRY_EVENT: &str = "ledger_entry";
+
+/// `ledger_entry` events read from an SSE stream
+#[derive(Debug)]
+pub struct SseTail<R> {
+    reader: R,
+}
+
+impl<R: BufRead> SseTail<R> {
+    /// Read events from `reader` (e.g. the body of a tail response)
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Next event as `(name, data)`, or `None` at the end of the stream
+    fn next_event(&mut self) -> Result<Option<(String, String)>> {
+        let mut event = String::from("message");
+        let mut data: Option<String> = None;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(data.map(|data| (event, data)));
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.is_empty() {
+                match data.take() {
+                    Some(data) => return Ok(Some((event, data))),
+                    None => continue,
+                }
+            }
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => event = value.to_string(),
+                "data" => match &mut data {
+                    Some(data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => data = Some(value.to_string()),
+                },
+                // Comments (keep-alives), id and retry
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SseTail<R> {
+    type Item = Result<LedgerEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_event() {
+                Ok(Some((event, data))) if event == ENTRY_EVENT => {
+                    return Some(entry_from_row(&data))
+                }
+                Ok(Some(_)) => continue,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// A `ledger_entry` row as the server serializes it
+#[derive(Deserialize)]
+struct TailRow {
+    sequence: u64,
+    entry_hash: String,
+    ts_unix_ms: i64,
+    metadata: TailLink,
+}
+
+/// The link draft kept in a row's `metadata`
+#[derive(Deserialize)]
+struct TailLink {
+    version: u8,
+    container_id: String,
+    expected_sequence: u64,
+    previous_hash: String,
+    atom_hash: String,
+    intent_class: IntentClass,
+    physics_delta: String,
+    #[serde(default)]
+    constraints: Vec<Constraint>,
+    #[serde(default)]
+    pact: Option<PactProof>,
+    author_pubkey: String,
+    signature: String,
+}
+
+/// Decode the JSON of one `ledger_entry` event
+pub fn entry_from_row(data: &str) -> Result<LedgerEntry> {
+    let malformed = |reason: String| LedgerError::MalformedTail(reason);
+    let row: TailRow = serde_json::from_str(data).map_err(|e| malformed(e.to_string()))?;
+    let link = row.metadata;
+    let physics_delta = link
+        .physics_delta
+        .parse()
+        .map_err(|_| malformed(format!("invalid physics_delta: {}", link.physics_delta)))?;
+    Ok(LedgerEntry {
+        sequence: row.sequence,
+        entry_hash: row.entry_hash,
+        link: LinkCommit {
+            version: link.version,
+            container_id: link.container_id,
+            expected_sequence: link.expected_sequence,
+            previous_hash: link.previous_hash,
+            atom_hash: link.atom_hash,
+            intent_class: link.intent_class,
+            physics_delta,
+            constraints: link.constraints,
+            pact: link.pact,
+            author_pubkey: link.author_pubkey,
+            signature: link.signature,
+        },
+        timestamp: row.ts_unix_ms / 1000,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(sequence: u64, delta: &str) -> String {
+        serde_json::json!({
+            "container_id": "wallet",
+            "sequence": sequence,
+            "link_hash": "ab",
+            "previous_hash": "0x00",
+            "entry_hash": format!("hash{}", sequence),
+            "ts_unix_ms": 1_700_000_000_123i64,
+            "hash_scheme": 1,
+            "metadata": {
+                "version": 2,
+                "container_id": "wallet",
+                "expected_sequence": sequence,
+                "previous_hash": "0x00",
+                "atom_hash": "cd",
+                "intent_class": "Conservation",
+                "physics_delta": delta,
+                "author_pubkey": "alice",
+                "signature": "sig"
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_reads_entry_events() {
+        let stream = format!(
+            ": keep-alive\n\nevent: ledger_entry\ndata: {}\n\nevent: other\ndata: x\n\nevent: ledger_entry\r\ndata: {}\r\n\r\n",
+            row(1, "-5"),
+            row(2, "170141183460469231731687303715884105727"),
+        );
+        let entries: Vec<_> = SseTail::new(stream.as_bytes())
+            .collect::<Result<_>>()
+            .unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].sequence, 1);
+        assert_eq!(entries[0].entry_hash, "hash1");
+        assert_eq!(entries[0].timestamp, 1_700_000_000);
+        assert_eq!(entries[0].link.physics_delta, -5);
+        assert_eq!(entries[0].link.intent_class, IntentClass::Conservation);
+        assert_eq!(entries[1].link.physics_delta, i128::MAX);
+    }
+
+    #[test]
+    fn test_rejects_malformed_rows() {
+        let stream = format!("event: ledger_entry\ndata: {}\n\n", row(1, "lots"));
+        let mut tail = SseTail::new(stream.as_bytes());
+        assert!(matches!(
+            tail.next(),
+            Some(Err(LedgerError::MalformedTail(_)))
+        ));
+        assert!(tail.next().is_none());
+    }
+}
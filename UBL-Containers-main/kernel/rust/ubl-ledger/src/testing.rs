@@ -0,0 +1,31 @@
+//! Signed chains for tests, built with [`ubl_membrane::testing`]
+
+use ubl_link::{IntentClass, LinkCommit};
+pub use ubl_membrane::testing::{link, Author};
+
+use crate::LedgerStore;
+
+/// Commit `count` links of `class` and `delta` signed by `author`; their atoms
+/// alternate between two hashes, so indexes see repeats
+pub fn extend(
+    store: &mut impl LedgerStore,
+    author: &Author,
+    count: u64,
+    class: IntentClass,
+    delta: i128,
+) {
+    for _ in 0..count {
+        let sequence = store.next_sequence();
+        let next = LinkCommit {
+            atom_hash: format!("{:064x}", sequence % 2),
+            ..link(
+                store.container_id(),
+                sequence,
+                &store.last_hash(),
+                class,
+                delta,
+            )
+        };
+        store.append(author.sign(next)).unwrap();
+    }
+}
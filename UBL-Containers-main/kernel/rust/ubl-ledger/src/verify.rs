@@ -0,0 +1,364 @@
+//! Chain verification (SPEC-UBL-LEDGER v1.0 §10)
+//!
+//! Re-derives every entry from its link alone: the link hash and entry hash
+//! are recomputed with the kernel's domain-separated hashes, the author
+//! signature is checked, and each entry must sit at the next sequence and
+//! point at its predecessor. Nothing stored next to the link is trusted.
+//!
+//! Verification stops at the first broken sequence: everything after it
+//! hangs off a link that can no longer be trusted.
+
+use serde::Serialize;
+use thiserror::Error;
+use ubl_link::{LinkCommit, LinkError};
+
+use crate::checkpoint::Checkpoint;
+use crate::{LedgerEntry, GENESIS_HASH};
+
+/// What is wrong with an entry
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChainFault {
+    /// Entry or its link is not at the next sequence
+    #[error("sequence gap: expected {expected}, found {found}")]
+    SequenceGap {
+        /// Sequence the chain expected
+        expected: u64,
+        /// Sequence recorded in the entry or its link
+        found: u64,
+    },
+
+    /// Link targets another container
+    #[error("link targets container {found}")]
+    ContainerMismatch {
+        /// Container named by the link
+        found: String,
+    },
+
+    /// Link does not point at its predecessor
+    #[error("previous_hash {found} does not match predecessor {expected}")]
+    PreviousHash {
+        /// Hash of the predecessor (or genesis)
+        expected: String,
+        /// Hash the link points at
+        found: String,
+    },
+
+    /// Stored link could not be decoded
+    #[error("malformed link: {0}")]
+    MalformedLink(String),
+
+    /// Link uses an unknown signing format
+    #[error("unsupported link version {0}")]
+    UnsupportedVersion(u8),
+
+    /// Author signature does not verify
+    #[error("invalid author signature")]
+    Signature,
+
+    /// Stored entry hash differs from the recomputed one
+    #[error("entry_hash {found} does not match recomputed {expected}")]
+    EntryHash {
+        /// Recomputed `hash_entry`
+        expected: String,
+        /// Hash stored with the entry
+        found: String,
+    },
+}
+
+/// First point where a chain stops verifying
+#[derive(Error, Debug, Clone, PartialEq, Eq, Serialize)]
+#[error("chain broken at sequence {sequence}: {fault}")]
+pub struct ChainBreak {
+    /// First sequence that fails
+    pub sequence: u64,
+    /// Why it fails
+    pub fault: ChainFault,
+}
+
+/// Summary of a chain that verified end to end
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainSummary {
+    /// Container verified
+    pub container_id: String,
+    /// Number of entries checked
+    pub entries: u64,
+    /// Hash of the last entry (or the genesis hash)
+    pub last_hash: String,
+    /// Sum of all physics deltas
+    pub physical_balance: i128,
+}
+
+/// Incremental verifier, fed entries in sequence order
+#[derive(Debug, Clone)]
+pub struct ChainVerifier {
+    container_id: String,
+    last_hash: String,
+    next_sequence: u64,
+    balance: i128,
+}
+
+impl ChainVerifier {
+    /// Verify a chain starting from the genesis hash
+    pub fn new(container_id: impl Into<String>) -> Self {
+        Self {
+            container_id: container_id.into(),
+            last_hash: GENESIS_HASH.to_string(),
+            next_sequence: 1,
+            balance: 0,
+        }
+    }
+
+    /// Continue a chain from a checkpoint (its signature is not checked here)
+    pub fn resume(checkpoint: &Checkpoint) -> Self {
+        Self {
+            container_id: checkpoint.container_id.clone(),
+            last_hash: checkpoint.last_hash.clone(),
+            next_sequence: checkpoint.sequence + 1,
+            balance: checkpoint.physical_balance,
+        }
+    }
+
+    /// Use another value as the first entry's `previous_hash`
+    pub fn with_genesis(mut self, hash: impl Into<String>) -> Self {
+        self.last_hash = hash.into();
+        self
+    }
+
+    /// Check the next entry and advance past it
+    pub fn check(&mut self, entry: &LedgerEntry) -> Result<(), ChainBreak> {
+        let sequence = self.next_sequence;
+        let fault = |fault| ChainBreak { sequence, fault };
+
+        self.check_sequence(entry.sequence).map_err(fault)?;
+        self.check_sequence(entry.link.expected_sequence)
+            .map_err(fault)?;
+        self.check_link(&entry.link).map_err(fault)?;
+        let expected = entry_hash(&entry.link)
+            .map_err(|_| fault(ChainFault::UnsupportedVersion(entry.link.version)))?;
+        self.advance(expected, &entry.entry_hash, entry.link.physics_delta)
+    }
+
+    /// Check the next entry of a chain written before links were stored,
+    /// from the entry's own columns
+    ///
+    /// Only its place in the chain and its hash can be checked: `expected` is
+    /// the entry hash the caller recomputed from the same columns under the
+    /// scheme the entry was written with. Such entries carry no delta.
+    pub fn check_unlinked(
+        &mut self,
+        sequence: u64,
+        previous_hash: &str,
+        entry_hash: &str,
+        expected: String,
+    ) -> Result<(), ChainBreak> {
+        let fault = |fault| ChainBreak {
+            sequence: self.next_sequence,
+            fault,
+        };
+        self.check_sequence(sequence).map_err(fault)?;
+        if previous_hash != self.last_hash {
+            return Err(fault(ChainFault::PreviousHash {
+                expected: self.last_hash.clone(),
+                found: previous_hash.to_string(),
+            }));
+        }
+        self.advance(expected, entry_hash, 0)
+    }
+
+    /// Sequence the next entry must have
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence
+    }
+
+    /// Summary of everything checked so far
+    pub fn finish(self) -> ChainSummary {
+        ChainSummary {
+            container_id: self.container_id,
+            entries: self.next_sequence - 1,
+            last_hash: self.last_hash,
+            physical_balance: self.balance,
+        }
+    }
+
+    fn check_sequence(&self, found: u64) -> Result<(), ChainFault> {
+        if found != self.next_sequence {
+            return Err(ChainFault::SequenceGap {
+                expected: self.next_sequence,
+                found,
+            });
+        }
+        Ok(())
+    }
+
+    /// Accept an entry whose hash must be `expected`
+    fn advance(&mut self, expected: String, found: &str, delta: i128) -> Result<(), ChainBreak> {
+        if found != expected {
+            return Err(ChainBreak {
+                sequence: self.next_sequence,
+                fault: ChainFault::EntryHash {
+                    expected,
+                    found: found.to_string(),
+                },
+            });
+        }
+        self.last_hash = expected;
+        self.next_sequence += 1;
+        self.balance += delta;
+        Ok(())
+    }
+
+    /// Check the link belongs at the current tip and is signed by its author
+    fn check_link(&self, link: &LinkCommit) -> Result<(), ChainFault> {
+        if link.container_id != self.container_id {
+            return Err(ChainFault::ContainerMismatch {
+                found: link.container_id.clone(),
+            });
+        }
+        if link.previous_hash != self.last_hash {
+            return Err(ChainFault::PreviousHash {
+                expected: self.last_hash.clone(),
+                found: link.previous_hash.clone(),
+            });
+        }
+        if !ubl_link::is_supported_version(link.version) {
+            return Err(ChainFault::UnsupportedVersion(link.version));
+        }
+        let signing_bytes = link
+            .signing_bytes()
+            .map_err(|_| ChainFault::UnsupportedVersion(link.version))?;
+        ubl_kernel::verify(&link.author_pubkey, &signing_bytes, &link.signature)
+            .map_err(|_| ChainFault::Signature)
+    }
+}
+
+/// Entry hash a link gets when appended at the sequence and tip it names
+pub fn entry_hash(link: &LinkCommit) -> Result<String, LinkError> {
+    let link_hash = ubl_kernel::hash_link(&link.signing_bytes()?);
+    Ok(ubl_kernel::hash_entry(
+        &link.container_id,
+        link.expected_sequence,
+        &link_hash,
+        &link.previous_hash,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Author};
+    use crate::{Ledger, LedgerError, LedgerStore};
+    use ubl_link::IntentClass;
+
+    fn signed_ledger(count: u64) -> Ledger {
+        let mut ledger = Ledger::new("wallet".to_string());
+        testing::extend(
+            &mut ledger,
+            &Author::new(),
+            count,
+            IntentClass::Observation,
+            0,
+        );
+        ledger
+    }
+
+    fn tampered(ledger: &Ledger, sequence: u64, edit: impl Fn(&mut LedgerEntry)) -> Ledger {
+        let mut copy = Ledger::new(ledger.container_id().to_string());
+        for entry in ledger.entries() {
+            let mut entry = entry.clone();
+            if entry.sequence == sequence {
+                edit(&mut entry);
+            }
+            copy.push(entry.link, entry.entry_hash);
+        }
+        copy
+    }
+
+    fn broken_at(ledger: &Ledger) -> (u64, ChainFault) {
+        match ledger.verify_chain() {
+            Err(LedgerError::ChainBroken(ChainBreak { sequence, fault })) => (sequence, fault),
+            other => panic!("expected a broken chain, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let ledger = signed_ledger(5);
+        let summary = ledger.verify_chain().unwrap();
+        assert_eq!(summary.entries, 5);
+        assert_eq!(summary.last_hash, ledger.last_hash());
+        assert_eq!(
+            Ledger::new("empty".to_string())
+                .verify_chain()
+                .unwrap()
+                .last_hash,
+            GENESIS_HASH
+        );
+    }
+
+    #[test]
+    fn test_detects_tampered_link() {
+        let ledger = tampered(&signed_ledger(5), 3, |e| e.link.physics_delta = 1_000);
+        assert_eq!(broken_at(&ledger), (3, ChainFault::Signature));
+    }
+
+    #[test]
+    fn test_detects_rewritten_entry_hash() {
+        let ledger = tampered(&signed_ledger(5), 4, |e| e.entry_hash = "f".repeat(64));
+        assert!(matches!(
+            broken_at(&ledger),
+            (4, ChainFault::EntryHash { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_unlinked_entries() {
+        let mut verifier = ChainVerifier::new("wallet");
+        let legacy = |n: u64| format!("legacy{}", n);
+        verifier
+            .check_unlinked(1, GENESIS_HASH, "legacy1", legacy(1))
+            .unwrap();
+        verifier
+            .check_unlinked(2, "legacy1", "legacy2", legacy(2))
+            .unwrap();
+
+        for (sequence, previous, stored, fault) in [
+            (4, "legacy2", "legacy3", "sequence_gap"),
+            (3, "legacy1", "legacy3", "previous_hash"),
+            (3, "legacy2", "forged", "entry_hash"),
+        ] {
+            let broken = verifier
+                .clone()
+                .check_unlinked(sequence, previous, stored, legacy(3))
+                .unwrap_err();
+            assert_eq!(broken.sequence, 3);
+            assert_eq!(serde_json::to_value(&broken.fault).unwrap()["kind"], fault);
+        }
+        assert_eq!(verifier.finish().last_hash, "legacy2");
+    }
+
+    #[test]
+    fn test_detects_broken_links() {
+        let original = signed_ledger(5);
+        let relinked = tampered(&original, 2, |e| e.link.previous_hash = GENESIS_HASH.into());
+        assert!(matches!(
+            broken_at(&relinked),
+            (2, ChainFault::PreviousHash { .. })
+        ));
+
+        let mut gap = Ledger::new("wallet".to_string());
+        for entry in original.entries().iter().filter(|e| e.sequence != 3) {
+            gap.push(entry.link.clone(), entry.entry_hash.clone());
+        }
+        assert_eq!(
+            broken_at(&gap),
+            (
+                3,
+                ChainFault::SequenceGap {
+                    expected: 3,
+                    found: 4
+                }
+            )
+        );
+    }
+}
@@ -0,0 +1,394 @@
+//! Projections behind office and messenger views (SPEC-UBL-LEDGER v1.0 §8.2)
+//!
+//! The clients ask the server for affordances, obligations and messages; these
+//! folds derive them from a container's chain instead. Each is a
+//! [`Projection`](crate::Projection) with a JSON snapshot, meant for a
+//! [`ProjectionRunner`](crate::ProjectionRunner). They see links only: atom
+//! bodies live elsewhere and are looked up by `atom_hash`.
+//!
+//! - [`Affordances`]: the intent classes each entity has exercised, and the
+//!   pacts that authorized them
+//! - [`Obligations`]: commits bound by an `obligation` constraint, open until
+//!   the obligor commits one that `fulfils` them
+//! - [`MessageIndex`]: the container's Observations in order, for paging a
+//!   conversation
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use ubl_link::IntentClass;
+use ubl_membrane::constraints::{parse_obligation, FULFILS, OBLIGATION};
+
+use crate::projection::{Projection, ProjectionError};
+use crate::LedgerEntry;
+
+/// One intent class an entity has exercised
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affordance {
+    /// Intent class exercised
+    pub intent_class: IntentClass,
+    /// Commits of this class the entity authored or co-signed
+    pub count: u64,
+    /// Latest such commit
+    pub last_sequence: u64,
+    /// Pacts those commits were authorized under
+    pub pacts: BTreeSet<String>,
+}
+
+/// What each entity has been able to do in this container
+///
+/// An entity affords an intent class once it has authored a commit of that
+/// class, or co-signed the pact proof that authorized one. This is evidence
+/// from history: authority granted by a pact but never used does not show up.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affordances {
+    /// public key → classes exercised
+    pub entities: BTreeMap<String, Vec<Affordance>>,
+}
+
+impl Affordances {
+    /// Name the cursor is stored under
+    pub const NAME: &'static str = "affordances";
+
+    /// Classes `entity` has exercised
+    pub fn of(&self, entity: &str) -> &[Affordance] {
+        self.entities.get(entity).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether `entity` has exercised `intent_class`
+    pub fn affords(&self, entity: &str, intent_class: IntentClass) -> bool {
+        self.of(entity)
+            .iter()
+            .any(|a| a.intent_class == intent_class)
+    }
+}
+
+impl Projection for Affordances {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply(&mut self, entry: &LedgerEntry) -> Result<(), ProjectionError> {
+        let link = &entry.link;
+        let pact = link.pact.as_ref();
+        let mut entities = BTreeSet::from([link.author_pubkey.as_str()]);
+        entities.extend(
+            pact.iter()
+                .flat_map(|p| &p.signatures)
+                .map(|s| s.pubkey.as_str()),
+        );
+
+        for entity in entities {
+            let classes = self.entities.entry(entity.to_string()).or_default();
+            let index = match classes
+                .iter()
+                .position(|a| a.intent_class == link.intent_class)
+            {
+                Some(index) => index,
+                None => {
+                    classes.push(Affordance {
+                        intent_class: link.intent_class,
+                        count: 0,
+                        last_sequence: 0,
+                        pacts: BTreeSet::new(),
+                    });
+                    classes.len() - 1
+                }
+            };
+            let affordance = &mut classes[index];
+            affordance.count += 1;
+            affordance.last_sequence = entry.sequence;
+            affordance.pacts.extend(pact.map(|p| p.pact_id.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// A commit some entity owes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Obligation {
+    /// Hash of the entry that opened it
+    pub entry_hash: String,
+    /// Sequence of that entry
+    pub sequence: u64,
+    /// Atom describing what is owed
+    pub atom_hash: String,
+    /// Author of the opening commit
+    pub creditor: String,
+    /// Public key that owes it
+    pub obligor: String,
+    /// Deadline (Unix seconds)
+    pub due_at: i64,
+    /// Sequence of the commit that fulfilled it
+    pub fulfilled_at: Option<u64>,
+}
+
+/// Obligations opened in this container, keyed by the opening entry hash
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Obligations {
+    /// entry_hash → obligation
+    pub obligations: BTreeMap<String, Obligation>,
+}
+
+impl Obligations {
+    /// Name the cursor is stored under
+    pub const NAME: &'static str = "obligations";
+
+    /// Open obligations of `obligor`, oldest first
+    pub fn pending_for<'a>(&'a self, obligor: &'a str) -> impl Iterator<Item = &'a Obligation> {
+        self.open().filter(move |o| o.obligor == obligor)
+    }
+
+    /// Open obligations past their deadline at `now`
+    pub fn overdue(&self, now: i64) -> impl Iterator<Item = &Obligation> {
+        self.open().filter(move |o| o.due_at < now)
+    }
+
+    fn open(&self) -> impl Iterator<Item = &Obligation> {
+        let mut open: Vec<_> = self
+            .obligations
+            .values()
+            .filter(|o| o.fulfilled_at.is_none())
+            .collect();
+        open.sort_by_key(|o| o.sequence);
+        open.into_iter()
+    }
+}
+
+impl Projection for Obligations {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply(&mut self, entry: &LedgerEntry) -> Result<(), ProjectionError> {
+        let link = &entry.link;
+        for constraint in &link.constraints {
+            match constraint.kind.as_str() {
+                OBLIGATION => {
+                    let (obligor, due_at) =
+                        parse_obligation(&constraint.value).ok_or_else(|| {
+                            ProjectionError(format!("malformed obligation {:?}", constraint.value))
+                        })?;
+                    self.obligations.insert(
+                        entry.entry_hash.clone(),
+                        Obligation {
+                            entry_hash: entry.entry_hash.clone(),
+                            sequence: entry.sequence,
+                            atom_hash: link.atom_hash.clone(),
+                            creditor: link.author_pubkey.clone(),
+                            obligor: obligor.to_string(),
+                            due_at,
+                            fulfilled_at: None,
+                        },
+                    );
+                }
+                // Only the obligor can discharge an obligation, once
+                FULFILS => {
+                    if let Some(obligation) = self.obligations.get_mut(&constraint.value) {
+                        if obligation.obligor == link.author_pubkey
+                            && obligation.fulfilled_at.is_none()
+                        {
+                            obligation.fulfilled_at = Some(entry.sequence);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A message: an Observation whose atom is the body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Message {
+    /// Sequence of the entry
+    pub sequence: u64,
+    /// Atom holding the body
+    pub atom_hash: String,
+    /// Sender's public key
+    pub author: String,
+    /// Entry timestamp (Unix seconds)
+    pub timestamp: i64,
+}
+
+/// A conversation container's messages, in sequence order
+///
+/// Every Observation counts as a message; commits that move value do not.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageIndex {
+    /// Messages, oldest first
+    pub messages: Vec<Message>,
+}
+
+impl MessageIndex {
+    /// Name the cursor is stored under
+    pub const NAME: &'static str = "messages";
+
+    /// Up to `limit` messages before sequence `before` (or the latest ones),
+    /// oldest first
+    pub fn page(&self, before: Option<u64>, limit: usize) -> &[Message] {
+        let end = match before {
+            Some(sequence) => self.messages.partition_point(|m| m.sequence < sequence),
+            None => self.messages.len(),
+        };
+        &self.messages[end.saturating_sub(limit)..end]
+    }
+
+    /// Messages sent by `author`, oldest first
+    pub fn from_author<'a>(&'a self, author: &'a str) -> impl Iterator<Item = &'a Message> {
+        self.messages.iter().filter(move |m| m.author == author)
+    }
+}
+
+impl Projection for MessageIndex {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn apply(&mut self, entry: &LedgerEntry) -> Result<(), ProjectionError> {
+        let link = &entry.link;
+        if link.intent_class == IntentClass::Observation {
+            self.messages.push(Message {
+                sequence: entry.sequence,
+                atom_hash: link.atom_hash.clone(),
+                author: link.author_pubkey.clone(),
+                timestamp: entry.timestamp,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ubl_link::{Constraint, LinkCommit, PactProof, PactSignature};
+
+    fn entry(
+        sequence: u64,
+        intent_class: IntentClass,
+        author: &str,
+        constraints: &[(&str, &str)],
+    ) -> LedgerEntry {
+        LedgerEntry {
+            sequence,
+            entry_hash: format!("hash{}", sequence),
+            link: LinkCommit {
+                version: 3,
+                container_id: "office".to_string(),
+                expected_sequence: sequence,
+                previous_hash: format!("hash{}", sequence - 1),
+                atom_hash: format!("atom{}", sequence),
+                intent_class,
+                physics_delta: 0,
+                constraints: constraints
+                    .iter()
+                    .map(|(kind, value)| Constraint {
+                        kind: kind.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+                pact: None,
+                author_pubkey: author.to_string(),
+                signature: String::new(),
+            },
+            timestamp: 1_700_000_000 + sequence as i64,
+        }
+    }
+
+    /// Fold `entries`, then round-trip the state through its snapshot
+    fn fold<P: Projection + Default>(entries: &[LedgerEntry]) -> P {
+        let mut projection = P::default();
+        for entry in entries {
+            projection.apply(entry).unwrap();
+        }
+        let mut restored = P::default();
+        restored.restore(projection.snapshot().unwrap()).unwrap();
+        restored
+    }
+
+    #[test]
+    fn test_affordances_from_authors_and_pact_signers() {
+        let mut minted = entry(2, IntentClass::Entropy, "alice", &[]);
+        minted.link.pact = Some(PactProof {
+            pact_id: "mint".to_string(),
+            signatures: vec![PactSignature {
+                pubkey: "board".to_string(),
+                signature: String::new(),
+            }],
+        });
+        let entries = [
+            entry(1, IntentClass::Observation, "alice", &[]),
+            minted,
+            entry(3, IntentClass::Observation, "alice", &[]),
+        ];
+        let affordances = fold::<Affordances>(&entries);
+
+        assert!(affordances.affords("alice", IntentClass::Observation));
+        assert!(affordances.affords("board", IntentClass::Entropy));
+        assert!(!affordances.affords("board", IntentClass::Observation));
+        let observed = &affordances.of("alice")[0];
+        assert_eq!((observed.count, observed.last_sequence), (2, 3));
+        assert_eq!(
+            affordances.of("alice")[1].pacts,
+            BTreeSet::from(["mint".to_string()])
+        );
+        assert!(affordances.of("carol").is_empty());
+    }
+
+    #[test]
+    fn test_obligations_open_until_the_obligor_fulfils() {
+        let entries = [
+            entry(
+                1,
+                IntentClass::Observation,
+                "alice",
+                &[(OBLIGATION, "bob@100")],
+            ),
+            entry(
+                2,
+                IntentClass::Observation,
+                "alice",
+                &[(OBLIGATION, "bob@300")],
+            ),
+            // Only bob can discharge what bob owes
+            entry(3, IntentClass::Observation, "carol", &[(FULFILS, "hash1")]),
+            entry(4, IntentClass::Observation, "bob", &[(FULFILS, "hash2")]),
+        ];
+        let obligations = fold::<Obligations>(&entries);
+
+        let pending: Vec<_> = obligations.pending_for("bob").map(|o| o.sequence).collect();
+        assert_eq!(pending, [1]);
+        assert_eq!(obligations.overdue(200).count(), 1);
+        assert_eq!(obligations.overdue(50).count(), 0);
+        assert_eq!(obligations.obligations["hash2"].fulfilled_at, Some(4));
+        assert_eq!(obligations.obligations["hash1"].creditor, "alice");
+
+        let malformed = entry(5, IntentClass::Observation, "alice", &[(OBLIGATION, "bob")]);
+        assert!(Obligations::default().apply(&malformed).is_err());
+    }
+
+    #[test]
+    fn test_message_index_pages() {
+        let entries: Vec<_> = (1..=6)
+            .map(|seq| match seq {
+                3 => entry(seq, IntentClass::Conservation, "alice", &[]),
+                _ => entry(
+                    seq,
+                    IntentClass::Observation,
+                    ["alice", "bob"][seq as usize % 2],
+                    &[],
+                ),
+            })
+            .collect();
+        let index = fold::<MessageIndex>(&entries);
+        let sequences = |page: &[Message]| page.iter().map(|m| m.sequence).collect::<Vec<_>>();
+
+        assert_eq!(sequences(index.page(None, 2)), [5, 6]);
+        assert_eq!(sequences(index.page(Some(5), 2)), [2, 4]);
+        assert_eq!(sequences(index.page(Some(2), 10)), [1]);
+        assert_eq!(index.from_author("bob").count(), 2);
+        assert_eq!(index.messages[0].timestamp, 1_700_000_001);
+    }
+}
@@ -19,11 +19,63 @@
 //! - Physical class (Observation, Conservation, Entropy, Evolution)
 //! - Physics delta (the physical change)
 //! - Authority (signature)
+//!
+//! ## Versions
+//! - v1: fields concatenated as-is (kept so historical entries still verify)
+//! - v2: every variable-length field is length-prefixed, so distinct commits
+//!   can never share signing bytes; [`wire`] carries the same layout on the wire
+//! - v3: v2 plus the policy [`Constraint`]s the commit was allowed under, signed
+//!   with the rest so the membrane can enforce them
 
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod wire;
+
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Original link format (unprefixed signing bytes)
+pub const LINK_V1: u8 = 1;
+
+/// Length-prefixed link format
+pub const LINK_V2: u8 = 2;
+
+/// Length-prefixed link format with bound policy constraints
+pub const LINK_V3: u8 = 3;
+
+/// Whether a link `version` byte is understood by this crate
+pub fn is_supported_version(version: u8) -> bool {
+    matches!(version, LINK_V1 | LINK_V2 | LINK_V3)
+}
+
+/// Errors from decoding link commits
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    /// The version byte names a format this crate does not know
+    #[error("Unsupported link version: {0}")]
+    UnsupportedVersion(u8),
+
+    /// Input ended before the commit was complete
+    #[error("Truncated link encoding")]
+    Truncated,
+
+    /// Bytes left over after a complete commit
+    #[error("{0} trailing bytes after link encoding")]
+    TrailingBytes(usize),
+
+    /// Unknown intent class byte
+    #[error("Invalid intent class: {0}")]
+    InvalidIntentClass(u8),
+
+    /// Invalid pact marker (must be 0 or 1)
+    #[error("Invalid pact marker: {0}")]
+    InvalidPactMarker(u8),
+
+    /// A string field is not valid UTF-8
+    #[error("Invalid UTF-8 in field {0}")]
+    InvalidUtf8(&'static str),
+}
 
 /// SPEC 4: Intent Class
 /// The physical classification of an intent.
@@ -45,23 +97,56 @@ impl IntentClass {
     pub fn as_byte(&self) -> u8 {
         *self as u8
     }
+
+    /// Parse the byte representation
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x00 => Some(IntentClass::Observation),
+            0x01 => Some(IntentClass::Conservation),
+            0x02 => Some(IntentClass::Entropy),
+            0x03 => Some(IntentClass::Evolution),
+            _ => None,
+        }
+    }
 }
 
 /// Pact proof structure (SPEC-UBL-PACT v1.0 §8)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Shared with ubl-pact, which validates it against a registered pact
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PactProof {
     /// Pact identifier
     pub pact_id: String,
     /// Signatures from authorized signers
-    pub signatures: Vec<String>,
+    pub signatures: Vec<PactSignature>,
+}
+
+/// A single signature in a pact proof
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PactSignature {
+    /// Signer's public key (hex)
+    pub pubkey: String,
+    /// Signature (hex)
+    pub signature: String,
+}
+
+/// A policy constraint bound into a v3 link (SPEC-UBL-LINK v1.0 §3.3)
+///
+/// Same shape as the policy VM's decision constraints, so an allow decision's
+/// constraints can be copied into the link as they are.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Constraint {
+    /// Constraint kind (e.g. "max_delta", "time_window")
+    pub kind: String,
+    /// Constraint value
+    pub value: String,
 }
 
 /// SPEC 3: The Link Commit Structure
 /// This is what crosses the boundary Mind → Body.
 /// SPEC-UBL-LINK v1.0 §3
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LinkCommit {
-    /// SPEC 3.2: Protocol version (must be 1)
+    /// SPEC 3.2: Protocol version ([`LINK_V1`] or [`LINK_V2`])
     pub version: u8,
     
     /// SPEC 3.2: Container ID (Hash32 hex)
@@ -81,7 +166,11 @@ pub struct LinkCommit {
     
     /// SPEC 3.2: Physical delta (change in value) - i128 per spec
     pub physics_delta: i128,
-    
+
+    /// SPEC 3.3: Policy constraints, signed in [`LINK_V3`] (must be empty before)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<Constraint>,
+
     /// SPEC 3.2: Pact proof (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pact: Option<PactProof>,
@@ -96,7 +185,33 @@ pub struct LinkCommit {
 impl LinkCommit {
     /// Generate the bytes that must be signed (SPEC-UBL-LINK v1.0 §5)
     /// CRITICAL: Does NOT include pact, author_pubkey, or signature
-    pub fn signing_bytes(&self) -> Vec<u8> {
+    ///
+    /// The layout is chosen by `version`: [`LINK_V1`] is the original
+    /// concatenation, [`LINK_V2`] uses length-prefixed fields (§5.1) and
+    /// [`LINK_V3`] adds the constraints. Other versions have no layout.
+    pub fn signing_bytes(&self) -> Result<Vec<u8>, LinkError> {
+        match self.version {
+            LINK_V1 => Ok(self.signing_bytes_v1()),
+            LINK_V2 | LINK_V3 => {
+                let mut bytes = Vec::new();
+                wire::write_signed_fields(self, &mut bytes);
+                Ok(bytes)
+            }
+            other => Err(LinkError::UnsupportedVersion(other)),
+        }
+    }
+
+    /// Encode the full commit in the binary wire format (see [`wire`])
+    pub fn to_wire(&self) -> Vec<u8> {
+        wire::encode(self)
+    }
+
+    /// Decode a commit from the binary wire format (see [`wire`])
+    pub fn from_wire(bytes: &[u8]) -> Result<Self, LinkError> {
+        wire::decode(bytes)
+    }
+
+    fn signing_bytes_v1(&self) -> Vec<u8> {
         let mut bytes = Vec::new();
         
         // Version (1 byte)
@@ -163,17 +278,116 @@ mod tests {
             atom_hash: "def456".to_string(),
             intent_class: IntentClass::Conservation,
             physics_delta: -100,
+            constraints: Vec::new(),
             author_pubkey: "pubkey".to_string(),
             signature: "sig".to_string(),
             pact: None,
         };
 
-        let bytes1 = commit.signing_bytes();
-        let bytes2 = commit.signing_bytes();
+        let bytes1 = commit.signing_bytes().unwrap();
+        let bytes2 = commit.signing_bytes().unwrap();
         
         assert_eq!(bytes1, bytes2);
     }
 
+    #[test]
+    fn test_unknown_version_has_no_signing_bytes() {
+        let commit = LinkCommit {
+            version: 4,
+            container_id: "container_a".to_string(),
+            expected_sequence: 1,
+            previous_hash: "0x00".to_string(),
+            atom_hash: "def456".to_string(),
+            intent_class: IntentClass::Observation,
+            physics_delta: 0,
+            constraints: Vec::new(),
+            author_pubkey: "pubkey".to_string(),
+            signature: "sig".to_string(),
+            pact: None,
+        };
+        assert_eq!(commit.signing_bytes(), Err(LinkError::UnsupportedVersion(4)));
+        let v0 = LinkCommit { version: 0, ..commit };
+        assert_eq!(v0.signing_bytes(), Err(LinkError::UnsupportedVersion(0)));
+    }
+
+    #[test]
+    fn test_v2_signing_bytes_are_unambiguous() {
+        let commit = |container: &str, previous: &str| LinkCommit {
+            version: LINK_V1,
+            container_id: container.to_string(),
+            expected_sequence: 7,
+            previous_hash: previous.to_string(),
+            atom_hash: "beef".to_string(),
+            intent_class: IntentClass::Observation,
+            physics_delta: 0,
+            constraints: Vec::new(),
+            author_pubkey: "pk".to_string(),
+            signature: "sig".to_string(),
+            pact: None,
+        };
+
+        // v1: shifting bytes between adjacent fields collides
+        let a = commit("wallet", "ab");
+        let b = commit("wallet", "a");
+        let b = LinkCommit { atom_hash: "bbeef".to_string(), ..b };
+        assert_eq!(a.signing_bytes().unwrap(), b.signing_bytes().unwrap());
+
+        let a2 = LinkCommit { version: LINK_V2, ..a };
+        let b2 = LinkCommit { version: LINK_V2, ..b };
+        assert_ne!(a2.signing_bytes().unwrap(), b2.signing_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_v3_signs_constraints() {
+        let link = LinkCommit {
+            version: LINK_V3,
+            container_id: "wallet".to_string(),
+            expected_sequence: 1,
+            previous_hash: "0x00".to_string(),
+            atom_hash: "beef".to_string(),
+            intent_class: IntentClass::Conservation,
+            physics_delta: -10,
+            constraints: Vec::new(),
+            author_pubkey: "pk".to_string(),
+            signature: "sig".to_string(),
+            pact: None,
+        };
+        let bound = LinkCommit {
+            constraints: vec![Constraint {
+                kind: "max_delta".to_string(),
+                value: "100".to_string(),
+            }],
+            ..link.clone()
+        };
+        assert_ne!(link.signing_bytes().unwrap(), bound.signing_bytes().unwrap());
+        let loosened = LinkCommit {
+            constraints: vec![Constraint {
+                kind: "max_delta".to_string(),
+                value: "1000".to_string(),
+            }],
+            ..link.clone()
+        };
+        assert_ne!(bound.signing_bytes().unwrap(), loosened.signing_bytes().unwrap());
+
+        // v2 carries no constraints, so they cannot change what it signs
+        let v2 = LinkCommit { version: LINK_V2, ..link };
+        let v2_bound = LinkCommit { version: LINK_V2, ..bound };
+        assert_eq!(v2.signing_bytes().unwrap(), v2_bound.signing_bytes().unwrap());
+    }
+
+    #[test]
+    fn test_intent_class_from_byte() {
+        for class in [
+            IntentClass::Observation,
+            IntentClass::Conservation,
+            IntentClass::Entropy,
+            IntentClass::Evolution,
+        ] {
+            assert_eq!(IntentClass::from_byte(class.as_byte()), Some(class));
+        }
+        assert_eq!(IntentClass::from_byte(0x04), None);
+    }
+
     #[test]
     fn test_serialization() {
         let commit = LinkCommit {
@@ -184,6 +398,7 @@ mod tests {
             atom_hash: "abcd".to_string(),
             intent_class: IntentClass::Conservation,
             physics_delta: -50,
+            constraints: Vec::new(),
             author_pubkey: "pk".to_string(),
             signature: "sig".to_string(),
             pact: None,
@@ -0,0 +1,267 @@
+//! Binary wire codec for link commits (SPEC-UBL-LINK §5.1)
+//!
+//! ```text
+//! frame :=
+//!   version           u8
+//!   container_id      str
+//!   expected_sequence u64 BE
+//!   previous_hash     str
+//!   atom_hash         str
+//!   intent_class      u8
+//!   physics_delta     i128 BE
+//!   constraints       count:u32 BE (kind:str value:str)*      -- v3 only
+//!   author_pubkey     str
+//!   signature         str
+//!   pact              0x00 | 0x01 pact_id:str count:u32 BE (pubkey:str signature:str)*
+//!
+//! str := len:u32 BE || UTF-8 bytes
+//! ```
+//!
+//! The fields before `author_pubkey` are exactly the v2/v3 signing bytes, so a
+//! verifier can check such a frame without re-encoding anything. The version
+//! byte selects how the signature is checked; the frame layout is the same for
+//! v1 and v2, and v3 only adds the constraints.
+
+use crate::{
+    is_supported_version, Constraint, IntentClass, LinkCommit, LinkError, PactProof,
+    PactSignature, LINK_V3,
+};
+
+/// Encode a commit into a wire frame
+pub fn encode(link: &LinkCommit) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_signed_fields(link, &mut out);
+    write_str(&mut out, &link.author_pubkey);
+    write_str(&mut out, &link.signature);
+    match &link.pact {
+        None => out.push(0),
+        Some(pact) => {
+            out.push(1);
+            write_str(&mut out, &pact.pact_id);
+            write_len(&mut out, pact.signatures.len());
+            for sig in &pact.signatures {
+                write_str(&mut out, &sig.pubkey);
+                write_str(&mut out, &sig.signature);
+            }
+        }
+    }
+    out
+}
+
+/// Decode a wire frame, rejecting unknown versions and trailing bytes
+pub fn decode(bytes: &[u8]) -> Result<LinkCommit, LinkError> {
+    let mut r = Reader { bytes, pos: 0 };
+
+    let version = r.u8()?;
+    if !is_supported_version(version) {
+        return Err(LinkError::UnsupportedVersion(version));
+    }
+    let container_id = r.str("container_id")?;
+    let expected_sequence = u64::from_be_bytes(r.array()?);
+    let previous_hash = r.str("previous_hash")?;
+    let atom_hash = r.str("atom_hash")?;
+    let class = r.u8()?;
+    let intent_class = IntentClass::from_byte(class).ok_or(LinkError::InvalidIntentClass(class))?;
+    let physics_delta = i128::from_be_bytes(r.array()?);
+    let mut constraints = Vec::new();
+    if version == LINK_V3 {
+        for _ in 0..u32::from_be_bytes(r.array()?) {
+            constraints.push(Constraint {
+                kind: r.str("constraint.kind")?,
+                value: r.str("constraint.value")?,
+            });
+        }
+    }
+    let author_pubkey = r.str("author_pubkey")?;
+    let signature = r.str("signature")?;
+    let pact = match r.u8()? {
+        0 => None,
+        1 => {
+            let pact_id = r.str("pact_id")?;
+            let count = u32::from_be_bytes(r.array()?);
+            let mut signatures = Vec::new();
+            for _ in 0..count {
+                signatures.push(PactSignature {
+                    pubkey: r.str("pact.pubkey")?,
+                    signature: r.str("pact.signature")?,
+                });
+            }
+            Some(PactProof {
+                pact_id,
+                signatures,
+            })
+        }
+        other => return Err(LinkError::InvalidPactMarker(other)),
+    };
+
+    let rest = bytes.len() - r.pos;
+    if rest != 0 {
+        return Err(LinkError::TrailingBytes(rest));
+    }
+
+    Ok(LinkCommit {
+        version,
+        container_id,
+        expected_sequence,
+        previous_hash,
+        atom_hash,
+        intent_class,
+        physics_delta,
+        constraints,
+        pact,
+        author_pubkey,
+        signature,
+    })
+}
+
+/// Write the signed prefix of a frame (the v2/v3 signing bytes)
+pub(crate) fn write_signed_fields(link: &LinkCommit, out: &mut Vec<u8>) {
+    out.push(link.version);
+    write_str(out, &link.container_id);
+    out.extend_from_slice(&link.expected_sequence.to_be_bytes());
+    write_str(out, &link.previous_hash);
+    write_str(out, &link.atom_hash);
+    out.push(link.intent_class.as_byte());
+    out.extend_from_slice(&link.physics_delta.to_be_bytes());
+    if link.version == LINK_V3 {
+        write_len(out, link.constraints.len());
+        for constraint in &link.constraints {
+            write_str(out, &constraint.kind);
+            write_str(out, &constraint.value);
+        }
+    }
+}
+
+fn write_str(out: &mut Vec<u8>, value: &str) {
+    write_len(out, value.len());
+    out.extend_from_slice(value.as_bytes());
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).expect("link fields are shorter than 4 GiB");
+    out.extend_from_slice(&len.to_be_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], LinkError> {
+        let end = self.pos.checked_add(n).ok_or(LinkError::Truncated)?;
+        let slice = self.bytes.get(self.pos..end).ok_or(LinkError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], LinkError> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, LinkError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn str(&mut self, field: &'static str) -> Result<String, LinkError> {
+        let len = u32::from_be_bytes(self.array()?) as usize;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| LinkError::InvalidUtf8(field))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LINK_V1, LINK_V2};
+
+    fn commit(version: u8) -> LinkCommit {
+        LinkCommit {
+            version,
+            container_id: "wallet_alice".to_string(),
+            expected_sequence: 42,
+            previous_hash: "0".repeat(64),
+            atom_hash: "ab".repeat(32),
+            intent_class: IntentClass::Entropy,
+            physics_delta: -1_000_000_000_000_000_000_000,
+            constraints: Vec::new(),
+            pact: Some(PactProof {
+                pact_id: "pact_mint".to_string(),
+                signatures: vec![
+                    PactSignature {
+                        pubkey: "k1".to_string(),
+                        signature: "s1".to_string(),
+                    },
+                    PactSignature {
+                        pubkey: "k2".to_string(),
+                        signature: "s2".to_string(),
+                    },
+                ],
+            }),
+            author_pubkey: "cd".repeat(32),
+            signature: "ef".repeat(64),
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_both_versions() {
+        for version in [LINK_V1, LINK_V2] {
+            let link = commit(version);
+            assert_eq!(decode(&encode(&link)).unwrap(), link);
+
+            let bare = LinkCommit { pact: None, ..link };
+            assert_eq!(decode(&encode(&bare)).unwrap(), bare);
+        }
+
+        let constrained = LinkCommit {
+            constraints: vec![Constraint {
+                kind: "time_window".to_string(),
+                value: "0..2000".to_string(),
+            }],
+            ..commit(LINK_V3)
+        };
+        let frame = encode(&constrained);
+        assert!(frame.starts_with(&constrained.signing_bytes().unwrap()));
+        assert_eq!(decode(&frame).unwrap(), constrained);
+    }
+
+    #[test]
+    fn test_v2_signing_bytes_prefix_the_frame() {
+        let link = commit(LINK_V2);
+        let frame = encode(&link);
+        assert!(frame.starts_with(&link.signing_bytes().unwrap()));
+    }
+
+    #[test]
+    fn test_rejects_every_truncation() {
+        let frame = encode(&commit(LINK_V2));
+        for len in 0..frame.len() {
+            assert_eq!(
+                decode(&frame[..len]),
+                Err(LinkError::Truncated),
+                "len {}",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_malformed_frames() {
+        let mut frame = encode(&commit(LINK_V2));
+        frame.push(0);
+        assert_eq!(decode(&frame), Err(LinkError::TrailingBytes(1)));
+
+        let mut frame = encode(&commit(LINK_V2));
+        frame[0] = 9;
+        assert_eq!(decode(&frame), Err(LinkError::UnsupportedVersion(9)));
+
+        // intent_class sits after version, container, sequence and both hashes
+        let link = commit(LINK_V2);
+        let class_at = 1 + 4 + link.container_id.len() + 8 + 4 + 64 + 4 + 64;
+        let mut frame = encode(&link);
+        frame[class_at] = 7;
+        assert_eq!(decode(&frame), Err(LinkError::InvalidIntentClass(7)));
+    }
+}
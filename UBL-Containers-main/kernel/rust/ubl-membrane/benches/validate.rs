@@ -0,0 +1,63 @@
+//! Membrane validation against the <1ms per commit target.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ubl_link::{IntentClass, LinkCommit};
+use ubl_membrane::LedgerState;
+
+fn genesis() -> LedgerState {
+    LedgerState {
+        container_id: "wallet".to_string(),
+        last_hash: "0".repeat(64),
+        next_sequence: 1,
+        physical_balance: 1_000_000,
+    }
+}
+
+/// `len` sequential commits, each extending the previous one
+fn chain(len: u64) -> Vec<LinkCommit> {
+    let (pubkey, key) = ubl_kernel::generate_keypair();
+    let mut prev = genesis().last_hash;
+    let mut links = Vec::new();
+    for seq in 1..=len {
+        let mut link = LinkCommit {
+            version: 1,
+            container_id: "wallet".to_string(),
+            expected_sequence: seq,
+            previous_hash: prev.clone(),
+            atom_hash: "a".repeat(64),
+            intent_class: IntentClass::Conservation,
+            physics_delta: -1,
+            constraints: Vec::new(),
+            pact: None,
+            author_pubkey: pubkey.clone(),
+            signature: String::new(),
+        };
+        link.signature = ubl_kernel::sign(&key, &link.signing_bytes().unwrap());
+        let link_hash = ubl_kernel::hash_link(&link.signing_bytes().unwrap());
+        prev = ubl_kernel::hash_entry("wallet", seq, &link_hash, &prev);
+        links.push(link);
+    }
+    links
+}
+
+fn bench_single(c: &mut Criterion) {
+    let state = genesis();
+    let link = chain(1).remove(0);
+    c.bench_function("validate", |b| {
+        b.iter(|| ubl_membrane::validate(black_box(&link), black_box(&state)))
+    });
+    c.bench_function("validate_signed", |b| {
+        b.iter(|| ubl_membrane::validate_signed(black_box(&link), black_box(&state)))
+    });
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let state = genesis();
+    let links = chain(64);
+    c.bench_function("validate_batch/64", |b| {
+        b.iter(|| ubl_membrane::validate_batch(black_box(&links), black_box(&state)))
+    });
+}
+
+criterion_group!(benches, bench_single, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+//! Policy constraints bound into links (SPEC-UBL-MEMBRANE v1.0 §6.3)
+//!
+//! A v3 link carries the constraints of the policy decision that allowed it,
+//! signed with the rest of the commit. The membrane enforces the standard
+//! vocabulary, so a Mind that ignores a constraint it was given fails here:
+//!
+//! | kind            | value                    | holds when                              |
+//! |-----------------|--------------------------|-----------------------------------------|
+//! | `max_delta`     | integer                  | `abs(physics_delta) <= value`           |
+//! | `time_window`   | `not_before..not_after`  | the membrane's time is in the window    |
+//! | `actor`         | public key (hex)         | `author_pubkey == value`                |
+//! | `required_pact` | pact id                  | the link carries a proof of that pact   |
+//! | `risk_level`    | `L0`…`L5`                | always (read by policy chains)          |
+//! | `obligation`    | `obligor@due_at`         | always (recorded for projections)       |
+//! | `fulfils`       | entry hash               | always (recorded for projections)       |
+//!
+//! `obligation` makes the committed atom something `obligor` (a public key)
+//! owes by `due_at` (Unix seconds); a later commit by the obligor carrying
+//! `fulfils` with that entry's hash discharges it. Only the value's shape is
+//! checked here; the ledger's obligation view folds them.
+//!
+//! Broken or malformed constraints are `PhysicsViolation`s, except a missing
+//! pact, which fails like any other missing authority. Every constraint must
+//! hold, and a kind outside the table is rejected rather than carried
+//! unenforced. [`require`] checks that a link binds what its policy decided.
+
+use ubl_link::{Constraint, IntentClass, LinkCommit};
+
+use crate::{MembraneError, Result};
+
+/// `abs(physics_delta)` may not exceed the value
+pub const MAX_DELTA: &str = "max_delta";
+
+/// `not_before..not_after` (Unix seconds, inclusive)
+pub const TIME_WINDOW: &str = "time_window";
+
+/// Only this author may commit
+pub const ACTOR: &str = "actor";
+
+/// The link must carry a proof of this pact
+pub const REQUIRED_PACT: &str = "required_pact";
+
+/// Trust level the deciding policy demanded (`L0`…`L5`)
+pub const RISK_LEVEL: &str = "risk_level";
+
+/// The committed atom is owed by `obligor` by `due_at` (`obligor@due_at`)
+pub const OBLIGATION: &str = "obligation";
+
+/// This commit discharges the obligation opened by the entry with this hash
+pub const FULFILS: &str = "fulfils";
+
+/// Kinds the membrane checks
+const ENFORCED: &[&str] = &[MAX_DELTA, TIME_WINDOW, ACTOR, REQUIRED_PACT];
+
+/// Kinds bound by the signature and accepted as they are
+pub const PASS_THROUGH: &[&str] = &[RISK_LEVEL, OBLIGATION, FULFILS];
+
+/// Split an `obligation` value into `(obligor, due_at)`
+pub fn parse_obligation(value: &str) -> Option<(&str, i64)> {
+    let (obligor, due_at) = value.rsplit_once('@')?;
+    if obligor.is_empty() {
+        return None;
+    }
+    Some((obligor, due_at.parse().ok()?))
+}
+
+/// Check every constraint bound into `link` at time `now`
+pub fn check(link: &LinkCommit, now: i64) -> Result<()> {
+    if !link.constraints.is_empty() && link.version != ubl_link::LINK_V3 {
+        // Only v3 signs constraints; anywhere else they could be stripped
+        return Err(MembraneError::InvalidVersion);
+    }
+    for constraint in &link.constraints {
+        check_one(link, constraint, now)?;
+    }
+    Ok(())
+}
+
+/// Check that `link` binds every constraint in `decided`, the policy
+/// decision that allowed it (extra constraints only narrow it further)
+pub fn require(link: &LinkCommit, decided: &[Constraint]) -> Result<()> {
+    match decided.iter().find(|c| !link.constraints.contains(c)) {
+        Some(missing) => Err(MembraneError::PhysicsViolation {
+            reason: format!(
+                "policy constraint {} = {} is not bound into the link",
+                missing.kind, missing.value
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
+fn check_one(link: &LinkCommit, constraint: &Constraint, now: i64) -> Result<()> {
+    let broken = |why: String| MembraneError::PhysicsViolation {
+        reason: format!(
+            "constraint {} = {}: {why}",
+            constraint.kind, constraint.value
+        ),
+    };
+    let malformed = || broken("malformed value".to_string());
+
+    match constraint.kind.as_str() {
+        MAX_DELTA => {
+            let max: u128 = constraint.value.parse().map_err(|_| malformed())?;
+            let delta = link.physics_delta.unsigned_abs();
+            if delta > max {
+                return Err(broken(format!("|delta| is {delta}")));
+            }
+        }
+        TIME_WINDOW => {
+            let (not_before, not_after) = constraint
+                .value
+                .split_once("..")
+                .and_then(|(a, b)| Some((a.parse::<i64>().ok()?, b.parse::<i64>().ok()?)))
+                .ok_or_else(malformed)?;
+            if now < not_before || now > not_after {
+                return Err(broken(format!("now is {now}")));
+            }
+        }
+        ACTOR if link.author_pubkey != constraint.value => {
+            return Err(broken(format!("author is {}", link.author_pubkey)));
+        }
+        OBLIGATION if parse_obligation(&constraint.value).is_none() => return Err(malformed()),
+        REQUIRED_PACT => {
+            let carried = link.pact.as_ref().map(|proof| proof.pact_id.as_str());
+            if carried != Some(constraint.value.as_str()) {
+                return Err(match link.intent_class {
+                    IntentClass::Evolution => MembraneError::UnauthorizedEvolution,
+                    _ => MembraneError::PactViolation,
+                });
+            }
+        }
+        kind if !ENFORCED.contains(&kind) && !PASS_THROUGH.contains(&kind) => {
+            return Err(broken("unknown kind".to_string()));
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ubl_link::{PactProof, LINK_V2, LINK_V3};
+
+    fn link(constraints: &[(&str, &str)]) -> LinkCommit {
+        LinkCommit {
+            version: LINK_V3,
+            container_id: "wallet".to_string(),
+            expected_sequence: 1,
+            previous_hash: "genesis".to_string(),
+            atom_hash: "a".repeat(64),
+            intent_class: IntentClass::Conservation,
+            physics_delta: -250,
+            constraints: constraints
+                .iter()
+                .map(|(kind, value)| Constraint {
+                    kind: kind.to_string(),
+                    value: value.to_string(),
+                })
+                .collect(),
+            pact: None,
+            author_pubkey: "alice".to_string(),
+            signature: "mock".to_string(),
+        }
+    }
+
+    fn violation(result: Result<()>) -> bool {
+        matches!(result, Err(MembraneError::PhysicsViolation { .. }))
+    }
+
+    #[test]
+    fn test_standard_vocabulary() {
+        let ok = link(&[
+            (MAX_DELTA, "250"),
+            (TIME_WINDOW, "1000..2000"),
+            (ACTOR, "alice"),
+            ("risk_level", "L3"),
+        ]);
+        assert!(check(&ok, 1000).is_ok());
+        assert!(check(&ok, 2000).is_ok());
+        assert!(violation(check(&ok, 2001)));
+
+        assert!(violation(check(&link(&[(MAX_DELTA, "249")]), 0)));
+        assert!(violation(check(&link(&[(MAX_DELTA, "lots")]), 0)));
+        assert!(violation(check(&link(&[(TIME_WINDOW, "1000")]), 0)));
+        assert!(violation(check(&link(&[(ACTOR, "bob")]), 0)));
+        assert!(check(&link(&[(OBLIGATION, "bob@1700000000"), (FULFILS, "ab")]), 0).is_ok());
+        assert!(violation(check(&link(&[(OBLIGATION, "bob")]), 0)));
+        assert!(violation(check(&link(&[(OBLIGATION, "@1700000000")]), 0)));
+        // Unknown kinds would pass as enforced without being so
+        assert!(violation(check(&link(&[("max_amount", "10")]), 0)));
+        // Conjunctive: a looser duplicate does not help
+        assert!(violation(check(
+            &link(&[(MAX_DELTA, "1000"), (MAX_DELTA, "100")]),
+            0
+        )));
+    }
+
+    #[test]
+    fn test_link_binds_the_decision() {
+        let decided = link(&[(MAX_DELTA, "250"), (RISK_LEVEL, "L3")]).constraints;
+        let bound = link(&[(RISK_LEVEL, "L3"), (MAX_DELTA, "250"), (ACTOR, "alice")]);
+        assert!(require(&bound, &decided).is_ok());
+        assert!(require(&bound, &[]).is_ok());
+        assert!(violation(require(&link(&[(MAX_DELTA, "250")]), &decided)));
+        assert!(violation(require(
+            &link(&[(MAX_DELTA, "100"), (RISK_LEVEL, "L3")]),
+            &decided
+        )));
+    }
+
+    #[test]
+    fn test_required_pact_and_version() {
+        let mut bound = link(&[(REQUIRED_PACT, "treasury")]);
+        assert!(matches!(
+            check(&bound, 0),
+            Err(MembraneError::PactViolation)
+        ));
+
+        bound.pact = Some(PactProof {
+            pact_id: "treasury".to_string(),
+            signatures: Vec::new(),
+        });
+        assert!(check(&bound, 0).is_ok());
+        bound.pact.as_mut().unwrap().pact_id = "petty_cash".to_string();
+        assert!(matches!(
+            check(&bound, 0),
+            Err(MembraneError::PactViolation)
+        ));
+
+        bound.version = LINK_V2;
+        assert!(matches!(
+            check(&bound, 0),
+            Err(MembraneError::InvalidVersion)
+        ));
+    }
+}
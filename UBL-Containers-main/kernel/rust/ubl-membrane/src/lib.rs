@@ -13,11 +13,15 @@
 //! ## Validations
 //! - V1: Version check
 //! - V2: Container ID match
-//! - V3: Signature verification
+//! - V3: Signature verification (`validate_signed`)
 //! - V4: Reality drift (previous hash)
 //! - V5: Sequence continuity
 //! - V6: Atom hash format
 //! - V7: Physics invariants (conservation, entropy)
+//! - V7–V9: Pact authority for Entropy and Evolution ([`Membrane::with_pacts`])
+//! - Per-container physics ([`Membrane::with_rules`], [`rules`])
+//! - Policy constraints bound into v3 links ([`Membrane`], [`constraints`])
+//! - Paired cross-container Conservation ([`Membrane::validate_transfer`], [`transfer`])
 //!
 //! ## Performance Target
 //! All validations must complete in < 1ms
@@ -25,8 +29,19 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod constraints;
+pub mod rules;
+pub mod transfer;
+
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+
 use thiserror::Error;
 use ubl_link::{IntentClass, LinkCommit};
+use ubl_pact::{PactRegistry, PactSubject};
+
+pub use rules::{ContainerRules, PhysicsRules};
+pub use transfer::{validate_transfer, TransferLeg};
 
 /// Errors that can occur during membrane validation
 /// SPEC-UBL-MEMBRANE v1.0: Canonical error names (8 total)
@@ -54,7 +69,10 @@ pub enum MembraneError {
 
     /// V6: Physics violation (includes conservation, observation, etc.)
     #[error("V6: Physics violation: {reason}")]
-    PhysicsViolation { reason: String },
+    PhysicsViolation {
+        /// Which invariant was broken
+        reason: String,
+    },
 
     /// V7: Pact violation
     #[error("V7: Pact violation")]
@@ -65,6 +83,22 @@ pub enum MembraneError {
     UnauthorizedEvolution,
 }
 
+impl MembraneError {
+    /// Canonical error name, as surfaced by the HTTP API and SDKs
+    pub fn code(&self) -> &'static str {
+        match self {
+            MembraneError::InvalidVersion => "InvalidVersion",
+            MembraneError::InvalidSignature => "InvalidSignature",
+            MembraneError::InvalidTarget => "InvalidTarget",
+            MembraneError::RealityDrift => "RealityDrift",
+            MembraneError::SequenceMismatch => "SequenceMismatch",
+            MembraneError::PhysicsViolation { .. } => "PhysicsViolation",
+            MembraneError::PactViolation => "PactViolation",
+            MembraneError::UnauthorizedEvolution => "UnauthorizedEvolution",
+        }
+    }
+}
+
 /// Result type for membrane validation
 pub type Result<T> = std::result::Result<T, MembraneError>;
 
@@ -84,7 +118,18 @@ impl Decision {
     }
 }
 
+/// A batch was rejected at the first commit that failed
+#[derive(Error, Debug, Clone)]
+#[error("Commit {index} rejected: {error}")]
+pub struct BatchRejection {
+    /// Position of the failing commit in the batch
+    pub index: usize,
+    /// Why it failed
+    pub error: MembraneError,
+}
+
 /// Ledger state needed for validation
+#[derive(Debug, Clone)]
 pub struct LedgerState {
     /// Container ID
     pub container_id: String,
@@ -96,11 +141,220 @@ pub struct LedgerState {
     pub physical_balance: i128,
 }
 
+/// V2: Verify the author's Ed25519 signature over `signing_bytes()`
+pub fn verify_signature(link: &LinkCommit) -> Result<()> {
+    let signing_bytes = link
+        .signing_bytes()
+        .map_err(|_| MembraneError::InvalidVersion)?;
+    ubl_kernel::verify(&link.author_pubkey, &signing_bytes, &link.signature)
+        .map_err(|_| MembraneError::InvalidSignature)
+}
+
+/// Validate a link commit including signature verification (SPEC-UBL-MEMBRANE v1.0 §6)
+/// This is the entry point for anything that accepts commits from outside
+pub fn validate_signed(link: &LinkCommit, state: &LedgerState) -> Result<()> {
+    validate_signed_leg(link, state, false)
+}
+
+/// [`validate_signed`] for a link that may be one leg of a transfer
+fn validate_signed_leg(link: &LinkCommit, state: &LedgerState, paired: bool) -> Result<()> {
+    // V1 first, so a foreign version is reported as such rather than as a bad signature
+    if !ubl_link::is_supported_version(link.version) {
+        return Err(MembraneError::InvalidVersion);
+    }
+    verify_signature(link)?;
+    validate_leg(link, state, paired)
+}
+
+/// Validate a run of sequential commits, chaining state from one to the next
+///
+/// Commit `i + 1` must extend commit `i`: its previous hash is the entry hash
+/// of commit `i` (`ubl_kernel::hash_entry`), its sequence is one higher, and it
+/// sees the balance after commit `i`. Signatures are checked with a single
+/// Ed25519 batch verification. Returns the state after the last commit.
+pub fn validate_batch(
+    links: &[LinkCommit],
+    state: &LedgerState,
+) -> std::result::Result<LedgerState, BatchRejection> {
+    Membrane::new().validate_batch(links, state)
+}
+
+/// Membrane configuration: which authority checks apply on top of V1–V6
+///
+/// The free functions ([`validate`], [`validate_signed`], [`validate_batch`])
+/// are a membrane without authority. Once pacts or rules are configured:
+/// - the container's [`PhysicsRules`] must allow the intent class (V6)
+/// - Entropy must carry a proof of a pact at or above the container's
+///   `entropy_risk`, else `PactViolation` (V7)
+/// - Evolution must carry a proof of an L5 pact, else `UnauthorizedEvolution` (V8)
+/// - a proof attached to any other commit must be valid too (V9)
+///
+/// Without a registry no proof can be verified, so Entropy and Evolution fail.
+/// Constraints bound into a link ([`constraints`]) are always enforced, with
+/// time windows read against [`Membrane::at`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Membrane<'a> {
+    pacts: Option<&'a PactRegistry>,
+    rules: Option<&'a ContainerRules>,
+    now: i64,
+}
+
+impl<'a> Membrane<'a> {
+    /// A membrane without pact enforcement
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enforce pacts from `pacts` (V7 Entropy, V8 Evolution, V9 attached proofs)
+    pub fn with_pacts(mut self, pacts: &'a PactRegistry) -> Self {
+        self.pacts = Some(pacts);
+        self
+    }
+
+    /// Govern containers by `rules` instead of the default physics
+    pub fn with_rules(mut self, rules: &'a ContainerRules) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    /// Unix time used for pact time windows (the membrane never reads a clock)
+    pub fn at(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
+    /// V1–V9 and bound constraints, without signature verification
+    pub fn validate(&self, link: &LinkCommit, state: &LedgerState) -> Result<()> {
+        validate(link, state)?;
+        constraints::check(link, self.now)?;
+        self.check_authority(link)
+    }
+
+    /// V1–V9 and bound constraints, including the author's signature
+    pub fn validate_signed(&self, link: &LinkCommit, state: &LedgerState) -> Result<()> {
+        self.validate_signed_leg(link, state, false)
+    }
+
+    /// [`Membrane::validate_signed`], admitting a Conservation credit when
+    /// `paired` (the transfer path has already matched it with a debit)
+    fn validate_signed_leg(
+        &self,
+        link: &LinkCommit,
+        state: &LedgerState,
+        paired: bool,
+    ) -> Result<()> {
+        validate_signed_leg(link, state, paired)?;
+        constraints::check(link, self.now)?;
+        self.check_authority(link)
+    }
+
+    /// Decide including signature verification
+    pub fn decide_signed(&self, link: &LinkCommit, state: &LedgerState) -> Decision {
+        match self.validate_signed(link, state) {
+            Ok(()) => Decision::Accept,
+            Err(e) => Decision::Reject(e),
+        }
+    }
+
+    /// Validate a run of sequential commits (see [`validate_batch`])
+    pub fn validate_batch(
+        &self,
+        links: &[LinkCommit],
+        state: &LedgerState,
+    ) -> std::result::Result<LedgerState, BatchRejection> {
+        // A link of unknown version signs nothing; the loop rejects it as InvalidVersion
+        let signing_bytes: Vec<Vec<u8>> = links
+            .iter()
+            .map(|l| l.signing_bytes().unwrap_or_default())
+            .collect();
+        let items: Vec<(&str, &[u8], &str)> = links
+            .iter()
+            .zip(&signing_bytes)
+            .map(|(l, bytes)| (l.author_pubkey.as_str(), bytes.as_slice(), l.signature.as_str()))
+            .collect();
+
+        // Only pay for per-signature checks when the batch as a whole fails
+        let first_bad_signature = match ubl_kernel::verify_batch(&items) {
+            Ok(()) => None,
+            Err(_) => items
+                .iter()
+                .position(|(pk, msg, sig)| ubl_kernel::verify(pk, msg, sig).is_err()),
+        };
+
+        let mut state = state.clone();
+        for (index, link) in links.iter().enumerate() {
+            let reject = |error| BatchRejection { index, error };
+
+            if !ubl_link::is_supported_version(link.version) {
+                return Err(reject(MembraneError::InvalidVersion));
+            }
+            if first_bad_signature == Some(index) {
+                return Err(reject(MembraneError::InvalidSignature));
+            }
+            self.validate(link, &state).map_err(reject)?;
+
+            let link_hash = ubl_kernel::hash_link(&signing_bytes[index]);
+            state.last_hash = ubl_kernel::hash_entry(
+                &link.container_id,
+                link.expected_sequence,
+                &link_hash,
+                &link.previous_hash,
+            );
+            state.next_sequence += 1;
+            state.physical_balance += link.physics_delta;
+        }
+
+        Ok(state)
+    }
+
+    fn check_authority(&self, link: &LinkCommit) -> Result<()> {
+        if self.pacts.is_none() && self.rules.is_none() {
+            return Ok(());
+        }
+        let default_rules = PhysicsRules::default();
+        let rules = self
+            .rules
+            .map_or(&default_rules, |r| r.for_container(&link.container_id));
+
+        // V6 - the container's physics must admit this class at all
+        if !rules.allows(link.intent_class) {
+            return Err(MembraneError::PhysicsViolation {
+                reason: format!(
+                    "container {} does not accept {:?}",
+                    link.container_id, link.intent_class
+                ),
+            });
+        }
+
+        // V7/V8/V9 - authority
+        let failure = match link.intent_class {
+            IntentClass::Evolution => MembraneError::UnauthorizedEvolution,
+            _ => MembraneError::PactViolation,
+        };
+        let required = rules.required_risk(link.intent_class);
+        match (&link.pact, self.pacts) {
+            (Some(proof), Some(pacts)) => pacts
+                .validate_at_risk(proof, &PactSubject::of(link), required, self.now)
+                .map_err(|_| failure),
+            (Some(_), None) => Err(failure),
+            (None, _) if matches!(link.intent_class, IntentClass::Entropy | IntentClass::Evolution) => {
+                Err(failure)
+            }
+            (None, _) => Ok(()),
+        }
+    }
+}
+
 /// Validate a link commit (SPEC-UBL-MEMBRANE v1.0 §6)
-/// This version does not perform signature validation - that must be done separately
+/// This version does not perform signature validation - use `validate_signed` for untrusted input
 pub fn validate(link: &LinkCommit, state: &LedgerState) -> Result<()> {
-    // V1 - Version check
-    if link.version != 1 {
+    validate_leg(link, state, false)
+}
+
+/// [`validate`]; a Conservation credit is only admitted when `paired`
+fn validate_leg(link: &LinkCommit, state: &LedgerState, paired: bool) -> Result<()> {
+    // V1 - Version check (v1 and v2 signing layouts)
+    if !ubl_link::is_supported_version(link.version) {
         return Err(MembraneError::InvalidVersion);
     }
 
@@ -138,6 +392,15 @@ pub fn validate(link: &LinkCommit, state: &LedgerState) -> Result<()> {
             }
         }
         IntentClass::Conservation => {
+            // ∑Δ = 0: value only enters a container paired with a debit elsewhere
+            if link.physics_delta > 0 && !paired {
+                return Err(MembraneError::PhysicsViolation {
+                    reason: format!(
+                        "unpaired Conservation credit of {}; use a transfer",
+                        link.physics_delta
+                    ),
+                });
+            }
             // Conservation: balance must remain >= 0
             let resulting_balance = state.physical_balance + link.physics_delta;
             if resulting_balance < 0 {
@@ -146,12 +409,9 @@ pub fn validate(link: &LinkCommit, state: &LedgerState) -> Result<()> {
                 });
             }
         }
-        IntentClass::Entropy => {
-            // Entropy allows creation/destruction - no additional checks
-        }
-        IntentClass::Evolution => {
-            // Evolution is for rule changes - would need additional policy checks
-            // For now, allow it
+        IntentClass::Entropy | IntentClass::Evolution => {
+            // V7/V8 - authority comes from pacts, which only a configured
+            // `Membrane` (`with_pacts` / `with_rules`) can check
         }
     }
 
@@ -166,6 +426,14 @@ pub fn decide(link: &LinkCommit, state: &LedgerState) -> Decision {
     }
 }
 
+/// Decide including signature verification
+pub fn decide_signed(link: &LinkCommit, state: &LedgerState) -> Decision {
+    match validate_signed(link, state) {
+        Ok(()) => Decision::Accept,
+        Err(e) => Decision::Reject(e),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +447,7 @@ mod tests {
             atom_hash: "a".repeat(64),
             intent_class: class,
             physics_delta: delta,
+            constraints: Vec::new(),
             pact: None,
             author_pubkey: "pk".to_string(),
             signature: "mock".to_string(),
@@ -207,7 +476,7 @@ mod tests {
     fn test_invalid_version() {
         let state = make_state(1, "genesis", 0);
         let mut commit = make_commit(1, "genesis", 0, IntentClass::Observation);
-        commit.version = 2;
+        commit.version = 4;
 
         let result = validate(&commit, &state);
         assert!(matches!(result, Err(MembraneError::InvalidVersion)));
@@ -254,6 +523,19 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_unpaired_conservation_credit() {
+        let state = make_state(1, "genesis", 0);
+        let commit = make_commit(1, "genesis", 100, IntentClass::Conservation);
+
+        let result = validate(&commit, &state);
+        assert!(matches!(
+            result,
+            Err(MembraneError::PhysicsViolation { .. })
+        ));
+        assert!(validate_leg(&commit, &state, true).is_ok());
+    }
+
     #[test]
     fn test_observation_with_delta() {
         let state = make_state(1, "genesis", 0);
@@ -275,6 +557,292 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn signed(commit: LinkCommit) -> LinkCommit {
+        testing::Author::new().sign(commit)
+    }
+
+    #[test]
+    fn test_signed_commit_accepted() {
+        let state = make_state(1, "genesis", 0);
+        let commit = signed(make_commit(1, "genesis", 100, IntentClass::Entropy));
+
+        assert!(validate_signed(&commit, &state).is_ok());
+        assert!(decide_signed(&commit, &state).is_accept());
+    }
+
+    #[test]
+    fn test_v2_commit_accepted() {
+        let state = make_state(1, "genesis", 0);
+        let commit = signed(LinkCommit {
+            version: ubl_link::LINK_V2,
+            ..make_commit(1, "genesis", 100, IntentClass::Entropy)
+        });
+        assert!(validate_signed(&commit, &state).is_ok());
+
+        // A v2 signature does not verify under the v1 layout
+        let mut downgraded = commit.clone();
+        downgraded.version = ubl_link::LINK_V1;
+        let result = validate_signed(&downgraded, &state);
+        assert!(matches!(result, Err(MembraneError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_unsigned_commit_rejected() {
+        let state = make_state(1, "genesis", 0);
+        let commit = make_commit(1, "genesis", 100, IntentClass::Entropy);
+
+        let result = validate_signed(&commit, &state);
+        assert!(matches!(result, Err(MembraneError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_tampered_commit_rejected() {
+        let state = make_state(1, "genesis", 0);
+        let mut commit = signed(make_commit(1, "genesis", 100, IntentClass::Entropy));
+        commit.physics_delta = 1_000_000;
+
+        let result = validate_signed(&commit, &state);
+        assert!(matches!(result, Err(MembraneError::InvalidSignature)));
+    }
+
+    #[test]
+    fn test_foreign_key_rejected() {
+        let state = make_state(1, "genesis", 0);
+        let mut commit = signed(make_commit(1, "genesis", 100, IntentClass::Entropy));
+        commit.author_pubkey = ubl_kernel::generate_keypair().0;
+
+        let result = validate_signed(&commit, &state);
+        assert!(matches!(result, Err(MembraneError::InvalidSignature)));
+    }
+
+    fn make_batch(
+        len: u64,
+        delta: i128,
+        class: IntentClass,
+        balance: i128,
+    ) -> (Vec<LinkCommit>, LedgerState) {
+        let state = make_state(1, "genesis", balance);
+        let author = testing::Author::new();
+        let mut prev = state.last_hash.clone();
+        let mut links = Vec::new();
+        for seq in 1..=len {
+            let commit = author.sign(make_commit(seq, &prev, delta, class));
+            let link_hash = ubl_kernel::hash_link(&commit.signing_bytes().unwrap());
+            prev = ubl_kernel::hash_entry("wallet", seq, &link_hash, &prev);
+            links.push(commit);
+        }
+        (links, state)
+    }
+
+    #[test]
+    fn test_batch_chains_state() {
+        let (links, state) = make_batch(5, 10, IntentClass::Entropy, 0);
+
+        let after = validate_batch(&links, &state).unwrap();
+        assert_eq!(after.next_sequence, 6);
+        assert_eq!(after.physical_balance, 50);
+    }
+
+    #[test]
+    fn test_batch_reports_first_bad_signature() {
+        let (mut links, state) = make_batch(5, 10, IntentClass::Entropy, 0);
+        links[3].signature = links[2].signature.clone();
+
+        let err = validate_batch(&links, &state).unwrap_err();
+        assert_eq!(err.index, 3);
+        assert!(matches!(err.error, MembraneError::InvalidSignature));
+    }
+
+    #[test]
+    fn test_batch_detects_broken_chain() {
+        let (mut links, state) = make_batch(4, 10, IntentClass::Entropy, 0);
+        links.swap(1, 2);
+
+        let err = validate_batch(&links, &state).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert!(matches!(err.error, MembraneError::RealityDrift));
+    }
+
+    #[test]
+    fn test_batch_conservation_sees_running_balance() {
+        let (links, state) = make_batch(2, -10, IntentClass::Conservation, 15);
+
+        let err = validate_batch(&links, &state).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert!(matches!(err.error, MembraneError::PhysicsViolation { .. }));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(MembraneError::RealityDrift.code(), "RealityDrift");
+        let err = MembraneError::PhysicsViolation { reason: "x".to_string() };
+        assert_eq!(err.code(), "PhysicsViolation");
+    }
+
+    /// Register a one-signer pact at `risk` and attach a valid proof to `commit`
+    fn attach_pact(commit: &mut LinkCommit, risk: ubl_pact::RiskLevel) -> PactRegistry {
+        let (pubkey, key) = ubl_kernel::generate_keypair();
+        let mut registry = PactRegistry::new();
+        registry.register(ubl_pact::Pact {
+            pact_id: "pact_test".to_string(),
+            version: 1,
+            scope: ubl_pact::PactScope::Container,
+            threshold: 1,
+            signers: [pubkey.clone()].into_iter().collect(),
+            window: ubl_pact::TimeWindow {
+                not_before: 0,
+                not_after: 10_000,
+            },
+            risk_level: risk,
+            container_id: Some("wallet".to_string()),
+            quorum: None,
+        });
+        let message = PactSubject::of(commit).signing_bytes("pact_test");
+        commit.pact = Some(ubl_link::PactProof {
+            pact_id: "pact_test".to_string(),
+            signatures: vec![ubl_link::PactSignature {
+                pubkey,
+                signature: ubl_kernel::sign(&key, &message),
+            }],
+        });
+        registry
+    }
+
+    #[test]
+    fn test_entropy_requires_pact() {
+        let state = make_state(1, "genesis", 0);
+        let mut commit = make_commit(1, "genesis", 1000, IntentClass::Entropy);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L4);
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+
+        assert!(membrane.validate(&commit, &state).is_ok());
+
+        let bare = LinkCommit { pact: None, ..commit.clone() };
+        assert!(matches!(membrane.validate(&bare, &state), Err(MembraneError::PactViolation)));
+
+        // Proof signed for 1000 does not cover 2000
+        let inflated = LinkCommit { physics_delta: 2000, ..commit.clone() };
+        assert!(matches!(membrane.validate(&inflated, &state), Err(MembraneError::PactViolation)));
+
+        // Outside the pact's time window
+        let late = Membrane::new().with_pacts(&registry).at(20_000);
+        assert!(matches!(late.validate(&commit, &state), Err(MembraneError::PactViolation)));
+    }
+
+    #[test]
+    fn test_entropy_pact_risk_too_low() {
+        let state = make_state(1, "genesis", 0);
+        let mut commit = make_commit(1, "genesis", 1000, IntentClass::Entropy);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L2);
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+
+        assert!(matches!(membrane.validate(&commit, &state), Err(MembraneError::PactViolation)));
+    }
+
+    #[test]
+    fn test_evolution_requires_l5_pact() {
+        let state = make_state(1, "genesis", 0);
+
+        let bare = make_commit(1, "genesis", 0, IntentClass::Evolution);
+        let registry = PactRegistry::new();
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+        assert!(matches!(membrane.validate(&bare, &state), Err(MembraneError::UnauthorizedEvolution)));
+
+        let mut commit = make_commit(1, "genesis", 0, IntentClass::Evolution);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L4);
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+        assert!(matches!(membrane.validate(&commit, &state), Err(MembraneError::UnauthorizedEvolution)));
+
+        let mut commit = make_commit(1, "genesis", 0, IntentClass::Evolution);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L5);
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+        assert!(membrane.validate(&commit, &state).is_ok());
+    }
+
+    #[test]
+    fn test_attached_pact_must_be_valid() {
+        let state = make_state(1, "genesis", 500);
+        let mut commit = make_commit(1, "genesis", -100, IntentClass::Conservation);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L2);
+        let membrane = Membrane::new().with_pacts(&registry).at(1000);
+        assert!(membrane.validate(&commit, &state).is_ok());
+
+        commit.pact.as_mut().unwrap().pact_id = "pact_unknown".to_string();
+        assert!(matches!(membrane.validate(&commit, &state), Err(MembraneError::PactViolation)));
+
+        // Without a pact, plain conservation stays pact-free
+        commit.pact = None;
+        assert!(membrane.validate(&commit, &state).is_ok());
+    }
+
+    #[test]
+    fn test_container_entropy_risk() {
+        let state = make_state(1, "genesis", 0);
+        let mut commit = make_commit(1, "genesis", 1000, IntentClass::Entropy);
+        let registry = attach_pact(&mut commit, ubl_pact::RiskLevel::L3);
+
+        // Default physics want L4 for Entropy
+        let strict = ContainerRules::standard();
+        let membrane = Membrane::new().with_rules(&strict).with_pacts(&registry).at(1000);
+        assert!(matches!(membrane.validate(&commit, &state), Err(MembraneError::PactViolation)));
+
+        let lax = ContainerRules::standard().with_prefix(
+            "wallet",
+            PhysicsRules::default().with_entropy_risk(ubl_pact::RiskLevel::L3),
+        );
+        let membrane = Membrane::new().with_rules(&lax).with_pacts(&registry).at(1000);
+        assert!(membrane.validate(&commit, &state).is_ok());
+
+        // Rules without a registry cannot verify any proof
+        let membrane = Membrane::new().with_rules(&lax).at(1000);
+        assert!(matches!(membrane.validate(&commit, &state), Err(MembraneError::PactViolation)));
+    }
+
+    #[test]
+    fn test_membrane_enforces_bound_constraints() {
+        let state = make_state(1, "genesis", 1000);
+        let author = testing::Author::new();
+        let signed = |delta: i128, max: &str| {
+            author.sign(LinkCommit {
+                version: ubl_link::LINK_V3,
+                constraints: vec![ubl_link::Constraint {
+                    kind: constraints::MAX_DELTA.to_string(),
+                    value: max.to_string(),
+                }],
+                ..make_commit(1, "genesis", delta, IntentClass::Conservation)
+            })
+        };
+        let membrane = Membrane::new().at(1000);
+
+        assert!(membrane.validate_signed(&signed(-100, "100"), &state).is_ok());
+        // The Mind ignored the policy's limit
+        assert!(matches!(
+            membrane.validate_signed(&signed(-500, "100"), &state),
+            Err(MembraneError::PhysicsViolation { .. })
+        ));
+        // ...or loosened it after signing
+        let mut loosened = signed(-500, "100");
+        loosened.constraints[0].value = "500".to_string();
+        assert!(matches!(
+            membrane.validate_signed(&loosened, &state),
+            Err(MembraneError::InvalidSignature)
+        ));
+        let batch = membrane.validate_batch(&[signed(-500, "100")], &state);
+        assert_eq!(batch.unwrap_err().index, 0);
+    }
+
+    #[test]
+    fn test_batch_enforces_pacts() {
+        let (links, state) = make_batch(2, 10, IntentClass::Entropy, 0);
+        let registry = PactRegistry::new();
+        let err = Membrane::new()
+            .with_pacts(&registry)
+            .validate_batch(&links, &state)
+            .unwrap_err();
+        assert_eq!(err.index, 0);
+        assert!(matches!(err.error, MembraneError::PactViolation));
+    }
+
     #[test]
     fn test_decide_accept() {
         let state = make_state(1, "genesis", 0);
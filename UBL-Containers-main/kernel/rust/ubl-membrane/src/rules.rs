@@ -0,0 +1,175 @@
+//! Per-container physics rules (SPEC-UBL-MEMBRANE v1.0 §5, V6–V8)
+//!
+//! Each container is governed by one [`PhysicsRules`]: which intent classes
+//! it accepts, how much authority an Entropy commit must carry, and which
+//! policies (evaluated by the server) govern its commits. Rules are
+//! looked up by the longest matching container-id prefix, so a whole family
+//! such as `repo://` can be declared observation-only at once.
+//!
+//! Evolution is not configurable: it always needs an L5 pact.
+
+use serde::{Deserialize, Serialize};
+use ubl_link::IntentClass;
+use ubl_pact::RiskLevel;
+
+/// Prefix of static repository containers (Δ = 0, observation only)
+pub const REPO_PREFIX: &str = "repo://";
+
+/// Physics a single container obeys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhysicsRules {
+    /// Intent classes the container accepts
+    pub allowed_classes: Vec<IntentClass>,
+    /// Minimum risk level of the pact authorizing an Entropy commit
+    pub entropy_risk: RiskLevel,
+    /// Policies governing the container, most specific first; each commit
+    /// must bind the constraints they decide (none: ungoverned)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policies: Vec<String>,
+}
+
+impl Default for PhysicsRules {
+    /// All four classes; Entropy needs an L4 pact (SPEC-UBL-PACT §6)
+    fn default() -> Self {
+        Self {
+            allowed_classes: vec![
+                IntentClass::Observation,
+                IntentClass::Conservation,
+                IntentClass::Entropy,
+                IntentClass::Evolution,
+            ],
+            entropy_risk: RiskLevel::L4,
+            policies: Vec::new(),
+        }
+    }
+}
+
+impl PhysicsRules {
+    /// Accept Observation (Δ = 0) and nothing else
+    pub fn observation_only() -> Self {
+        Self {
+            allowed_classes: vec![IntentClass::Observation],
+            ..Self::default()
+        }
+    }
+
+    /// Require Entropy pacts at or above `level`
+    pub fn with_entropy_risk(mut self, level: RiskLevel) -> Self {
+        self.entropy_risk = level;
+        self
+    }
+
+    /// Govern the container by `policies`, most specific first
+    pub fn with_policies(mut self, policies: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.policies = policies.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether the container accepts commits of `class`
+    pub fn allows(&self, class: IntentClass) -> bool {
+        self.allowed_classes.contains(&class)
+    }
+
+    /// Minimum pact risk level for a commit of `class`
+    pub fn required_risk(&self, class: IntentClass) -> RiskLevel {
+        match class {
+            IntentClass::Entropy => self.entropy_risk,
+            IntentClass::Evolution => RiskLevel::L5,
+            other => RiskLevel::from_intent_class(other.as_byte()),
+        }
+    }
+}
+
+/// Physics rules for every container, selected by container-id prefix
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContainerRules {
+    /// Rules for containers no prefix matches
+    pub default: PhysicsRules,
+    /// `(prefix, rules)` pairs; the longest matching prefix wins
+    pub prefixes: Vec<(String, PhysicsRules)>,
+}
+
+impl ContainerRules {
+    /// Every container obeys `default`
+    pub fn new(default: PhysicsRules) -> Self {
+        Self {
+            default,
+            prefixes: Vec::new(),
+        }
+    }
+
+    /// Default physics with `repo://` containers observation-only
+    pub fn standard() -> Self {
+        Self::default().with_prefix(REPO_PREFIX, PhysicsRules::observation_only())
+    }
+
+    /// Apply `rules` to containers whose id starts with `prefix`
+    pub fn with_prefix(mut self, prefix: impl Into<String>, rules: PhysicsRules) -> Self {
+        let prefix = prefix.into();
+        self.prefixes.retain(|(p, _)| *p != prefix);
+        self.prefixes.push((prefix, rules));
+        self
+    }
+
+    /// Rules governing `container_id`
+    pub fn for_container(&self, container_id: &str) -> &PhysicsRules {
+        self.prefixes
+            .iter()
+            .filter(|(prefix, _)| container_id.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, rules)| rules)
+            .unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let rules = ContainerRules::standard()
+            .with_prefix(
+                "wallet/",
+                PhysicsRules::default().with_entropy_risk(RiskLevel::L3),
+            )
+            .with_prefix(
+                "wallet/treasury",
+                PhysicsRules::default().with_entropy_risk(RiskLevel::L5),
+            );
+
+        assert_eq!(
+            rules.for_container("wallet/alice").entropy_risk,
+            RiskLevel::L3
+        );
+        assert_eq!(
+            rules.for_container("wallet/treasury/main").entropy_risk,
+            RiskLevel::L5
+        );
+        assert_eq!(rules.for_container("C.Jobs").entropy_risk, RiskLevel::L4);
+        assert!(!rules
+            .for_container("repo://acme/core")
+            .allows(IntentClass::Entropy));
+    }
+
+    #[test]
+    fn test_evolution_always_needs_l5() {
+        let lax = PhysicsRules::default().with_entropy_risk(RiskLevel::L1);
+        assert_eq!(lax.required_risk(IntentClass::Entropy), RiskLevel::L1);
+        assert_eq!(lax.required_risk(IntentClass::Evolution), RiskLevel::L5);
+    }
+
+    #[test]
+    fn test_policies_are_optional_in_json() {
+        let governed = PhysicsRules::default().with_policies(["wallet", "global"]);
+        let json = serde_json::to_value(&governed).unwrap();
+        assert_eq!(json["policies"], serde_json::json!(["wallet", "global"]));
+
+        let plain = serde_json::to_value(PhysicsRules::default()).unwrap();
+        assert!(plain.get("policies").is_none());
+        assert_eq!(
+            serde_json::from_value::<PhysicsRules>(plain).unwrap(),
+            PhysicsRules::default()
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Signed links for tests
+//!
+//! Compiled for this crate's tests and, through the `test-util` feature, for
+//! the tests of crates that build on the membrane (ubl-ledger).
+
+use ubl_kernel::SigningKey;
+use ubl_link::{IntentClass, LinkCommit};
+
+/// An unsigned v2 link to `container_id` at `(expected_sequence, previous_hash)`
+pub fn link(
+    container_id: &str,
+    expected_sequence: u64,
+    previous_hash: &str,
+    class: IntentClass,
+    delta: i128,
+) -> LinkCommit {
+    LinkCommit {
+        version: 2,
+        container_id: container_id.to_string(),
+        expected_sequence,
+        previous_hash: previous_hash.to_string(),
+        atom_hash: "ab".repeat(32),
+        intent_class: class,
+        physics_delta: delta,
+        constraints: Vec::new(),
+        pact: None,
+        author_pubkey: String::new(),
+        signature: String::new(),
+    }
+}
+
+/// A fresh Ed25519 identity that signs links
+pub struct Author {
+    /// Public key (hex)
+    pub pubkey: String,
+    /// Signing key
+    pub key: SigningKey,
+}
+
+impl Author {
+    /// Generate a new author
+    pub fn new() -> Self {
+        let (pubkey, key) = ubl_kernel::generate_keypair();
+        Self { pubkey, key }
+    }
+
+    /// `link` authored and signed by this author
+    pub fn sign(&self, mut link: LinkCommit) -> LinkCommit {
+        link.author_pubkey = self.pubkey.clone();
+        let bytes = link.signing_bytes().expect("test link encodes");
+        link.signature = ubl_kernel::sign(&self.key, &bytes);
+        link
+    }
+}
+
+impl Default for Author {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,203 @@
+//! Cross-container transfers (SPEC-UBL-MEMBRANE v1.0 §6, V6 Conservation)
+//!
+//! Conservation is `∑Δ = 0` with paired changes: value leaves one container
+//! only if the same value enters another. A transfer is therefore a pair of
+//! Conservation links, a debit and a credit, that
+//! - target two different containers,
+//! - commit the same transfer atom (`atom_hash`), which ties the legs together,
+//! - carry deltas that are non-zero and sum to zero,
+//! - each pass V1–V9 against their own container's state (the debit leg's
+//!   balance must stay ≥ 0).
+//!
+//! The membrane only decides; appending both legs atomically is the ledger's
+//! job (`ubl_ledger::LedgerManager`, `PgLedger::transfer`).
+
+use ubl_link::{IntentClass, LinkCommit};
+
+use crate::{BatchRejection, LedgerState, Membrane, MembraneError};
+
+/// Position of the debit leg in a [`BatchRejection`]
+pub const DEBIT: usize = 0;
+
+/// Position of the credit leg in a [`BatchRejection`]
+pub const CREDIT: usize = 1;
+
+/// One side of a transfer and the state of the container it targets
+#[derive(Debug, Clone, Copy)]
+pub struct TransferLeg<'a> {
+    /// Signed Conservation link
+    pub link: &'a LinkCommit,
+    /// State of `link.container_id`
+    pub state: &'a LedgerState,
+}
+
+impl<'a> TransferLeg<'a> {
+    /// Pair a link with its container's state
+    pub fn new(link: &'a LinkCommit, state: &'a LedgerState) -> Self {
+        Self { link, state }
+    }
+}
+
+impl Membrane<'_> {
+    /// Validate both legs of a transfer, signatures included
+    ///
+    /// The rejection's `index` is [`DEBIT`] or [`CREDIT`]; pairing violations
+    /// (same container, different atoms, non-zero sum) are reported on the
+    /// credit leg.
+    pub fn validate_transfer(
+        &self,
+        debit: TransferLeg<'_>,
+        credit: TransferLeg<'_>,
+    ) -> std::result::Result<(), BatchRejection> {
+        for (index, leg) in [(DEBIT, debit), (CREDIT, credit)] {
+            if leg.link.intent_class != IntentClass::Conservation {
+                return Err(violation(index, "transfer legs must be Conservation"));
+            }
+        }
+        if debit.link.physics_delta >= 0 {
+            return Err(violation(DEBIT, "debit must have delta < 0"));
+        }
+        if debit.link.container_id == credit.link.container_id {
+            return Err(violation(CREDIT, "transfer legs target the same container"));
+        }
+        if debit.link.atom_hash != credit.link.atom_hash {
+            return Err(violation(
+                CREDIT,
+                "transfer legs must share the transfer atom",
+            ));
+        }
+        if debit
+            .link
+            .physics_delta
+            .checked_add(credit.link.physics_delta)
+            != Some(0)
+        {
+            return Err(violation(
+                CREDIT,
+                format!(
+                    "transfer deltas must sum to 0, got {} + {}",
+                    debit.link.physics_delta, credit.link.physics_delta
+                ),
+            ));
+        }
+
+        for (index, leg) in [(DEBIT, debit), (CREDIT, credit)] {
+            self.validate_signed_leg(leg.link, leg.state, true)
+                .map_err(|error| BatchRejection { index, error })?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate a transfer with a membrane without authority (see [`Membrane::validate_transfer`])
+pub fn validate_transfer(
+    debit: TransferLeg<'_>,
+    credit: TransferLeg<'_>,
+) -> std::result::Result<(), BatchRejection> {
+    Membrane::new().validate_transfer(debit, credit)
+}
+
+fn violation(index: usize, reason: impl Into<String>) -> BatchRejection {
+    BatchRejection {
+        index,
+        error: MembraneError::PhysicsViolation {
+            reason: reason.into(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Author};
+
+    fn state(container: &str, balance: i128) -> LedgerState {
+        LedgerState {
+            container_id: container.to_string(),
+            last_hash: "genesis".to_string(),
+            next_sequence: 1,
+            physical_balance: balance,
+        }
+    }
+
+    fn leg(container: &str, delta: i128, atom: &str) -> LinkCommit {
+        Author::new().sign(LinkCommit {
+            atom_hash: atom.repeat(64),
+            ..testing::link(container, 1, "genesis", IntentClass::Conservation, delta)
+        })
+    }
+
+    fn check(debit: &LinkCommit, credit: &LinkCommit, funds: i128) -> Result<(), BatchRejection> {
+        let (from, to) = (
+            state(&debit.container_id, funds),
+            state(&credit.container_id, 0),
+        );
+        validate_transfer(
+            TransferLeg::new(debit, &from),
+            TransferLeg::new(credit, &to),
+        )
+    }
+
+    fn rejected_leg(result: Result<(), BatchRejection>) -> usize {
+        result.expect_err("transfer should be rejected").index
+    }
+
+    #[test]
+    fn test_paired_transfer_accepted() {
+        let debit = leg("wallet/alice", -40, "a");
+        let credit = leg("wallet/bob", 40, "a");
+        check(&debit, &credit, 100).unwrap();
+    }
+
+    #[test]
+    fn test_unbalanced_or_unpaired_rejected() {
+        let debit = leg("wallet/alice", -40, "a");
+        assert_eq!(
+            rejected_leg(check(&debit, &leg("wallet/bob", 39, "a"), 100)),
+            CREDIT
+        );
+        assert_eq!(
+            rejected_leg(check(&debit, &leg("wallet/bob", 40, "b"), 100)),
+            CREDIT
+        );
+        assert_eq!(
+            rejected_leg(check(&debit, &leg("wallet/alice", 40, "a"), 100)),
+            CREDIT
+        );
+        assert_eq!(
+            rejected_leg(check(
+                &leg("wallet/alice", 40, "a"),
+                &leg("wallet/bob", -40, "a"),
+                100
+            )),
+            DEBIT
+        );
+
+        let mut entropy = leg("wallet/bob", 40, "a");
+        entropy.intent_class = IntentClass::Entropy;
+        assert_eq!(rejected_leg(check(&debit, &entropy, 100)), CREDIT);
+    }
+
+    #[test]
+    fn test_each_leg_is_validated() {
+        let debit = leg("wallet/alice", -40, "a");
+        let credit = leg("wallet/bob", 40, "a");
+        assert!(matches!(
+            check(&debit, &credit, 10),
+            Err(BatchRejection {
+                index: DEBIT,
+                error: MembraneError::PhysicsViolation { .. }
+            })
+        ));
+
+        let mut forged = credit.clone();
+        forged.author_pubkey = ubl_kernel::generate_keypair().0;
+        assert!(matches!(
+            check(&debit, &forged, 100),
+            Err(BatchRejection {
+                index: CREDIT,
+                error: MembraneError::InvalidSignature
+            })
+        ));
+    }
+}
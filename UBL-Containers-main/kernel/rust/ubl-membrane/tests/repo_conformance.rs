@@ -0,0 +1,132 @@
+//! Conformance: static repo container MUST enforce Δ=0 and reject non-Observation.
+
+use ubl_link::{IntentClass, LinkCommit, PactProof, PactSignature};
+use ubl_membrane::{ContainerRules, LedgerState, Membrane, MembraneError};
+use ubl_pact::{Pact, PactRegistry, PactScope, PactSubject, RiskLevel, TimeWindow};
+
+const REPO: &str = "repo://acme/core";
+
+fn state() -> LedgerState {
+    LedgerState {
+        container_id: REPO.to_string(),
+        last_hash: "genesis".to_string(),
+        next_sequence: 1,
+        physical_balance: 1_000,
+    }
+}
+
+fn commit(class: IntentClass, delta: i128) -> LinkCommit {
+    LinkCommit {
+        version: 1,
+        container_id: REPO.to_string(),
+        expected_sequence: 1,
+        previous_hash: "genesis".to_string(),
+        atom_hash: "a".repeat(64),
+        intent_class: class,
+        physics_delta: delta,
+        constraints: Vec::new(),
+        pact: None,
+        author_pubkey: "pk".to_string(),
+        signature: "sig".to_string(),
+    }
+}
+
+/// Attach a valid L5 proof, so rejections below come from the container's physics
+fn with_sovereign_pact(mut link: LinkCommit, registry: &mut PactRegistry) -> LinkCommit {
+    let (pubkey, key) = ubl_kernel::generate_keypair();
+    registry.register(Pact {
+        pact_id: "pact_root".to_string(),
+        version: 1,
+        scope: PactScope::Global,
+        threshold: 1,
+        signers: [pubkey.clone()].into_iter().collect(),
+        window: TimeWindow {
+            not_before: 0,
+            not_after: i64::MAX,
+        },
+        risk_level: RiskLevel::L5,
+        container_id: None,
+        quorum: None,
+    });
+    let message = PactSubject::of(&link).signing_bytes("pact_root");
+    link.pact = Some(PactProof {
+        pact_id: "pact_root".to_string(),
+        signatures: vec![PactSignature {
+            pubkey,
+            signature: ubl_kernel::sign(&key, &message),
+        }],
+    });
+    link
+}
+
+#[test]
+fn repo_accepts_observation() {
+    let rules = ContainerRules::standard();
+    let membrane = Membrane::new().with_rules(&rules);
+
+    assert!(membrane
+        .validate(&commit(IntentClass::Observation, 0), &state())
+        .is_ok());
+}
+
+#[test]
+fn repo_delta_must_be_zero() {
+    let rules = ContainerRules::standard();
+    let membrane = Membrane::new().with_rules(&rules);
+
+    let result = membrane.validate(&commit(IntentClass::Observation, 5), &state());
+    assert!(matches!(
+        result,
+        Err(MembraneError::PhysicsViolation { .. })
+    ));
+}
+
+#[test]
+fn repo_rejects_non_observation_even_with_authority() {
+    let rules = ContainerRules::standard();
+    for (class, delta) in [
+        (IntentClass::Conservation, -10),
+        (IntentClass::Entropy, 10),
+        (IntentClass::Evolution, 0),
+    ] {
+        let mut registry = PactRegistry::new();
+        let link = with_sovereign_pact(commit(class, delta), &mut registry);
+        let membrane = Membrane::new()
+            .with_rules(&rules)
+            .with_pacts(&registry)
+            .at(1);
+
+        let result = membrane.validate(&link, &state());
+        assert!(
+            matches!(result, Err(MembraneError::PhysicsViolation { .. })),
+            "{:?} accepted in {}",
+            class,
+            REPO
+        );
+    }
+}
+
+#[test]
+fn same_commit_outside_repo_is_governed_by_pacts() {
+    let rules = ContainerRules::standard();
+    let mut registry = PactRegistry::new();
+    let mut link = commit(IntentClass::Entropy, 10);
+    link.container_id = "wallet/acme".to_string();
+    let link = with_sovereign_pact(link, &mut registry);
+    let st = LedgerState {
+        container_id: "wallet/acme".to_string(),
+        ..state()
+    };
+
+    let membrane = Membrane::new()
+        .with_rules(&rules)
+        .with_pacts(&registry)
+        .at(1);
+    assert!(membrane.validate(&link, &st).is_ok());
+
+    let bare = LinkCommit { pact: None, ..link };
+    assert!(matches!(
+        membrane.validate(&bare, &st),
+        Err(MembraneError::PactViolation)
+    ));
+}
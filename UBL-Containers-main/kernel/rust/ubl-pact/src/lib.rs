@@ -9,13 +9,28 @@
 //!
 //! Authority, Consensus and Risk Specification
 //! Determines if a link can cross the boundary based on collective authority
+//!
+//! Pacts are created, amended and revoked by Evolution commits to `C.Pacts`;
+//! the [`PactRegistry`] is the projection of that history ([`lifecycle`]).
+//! A pact may also carry a [`Quorum`] expression for weighted, mandatory and
+//! nested signer requirements ([`quorum`]).
 
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod lifecycle;
+pub mod quorum;
+
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 use thiserror::Error;
+use ubl_link::LinkCommit;
+
+/// Pact proofs travel inside the link, so the link crate owns their shape
+pub use ubl_link::{PactProof, PactSignature};
+
+pub use lifecycle::{PactEvent, PactVersion, PACTS_CONTAINER};
+pub use quorum::{Quorum, UnmetClause};
 
 /// Errors from pact validation
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
@@ -30,15 +45,80 @@ pub enum PactError {
 
     /// Insufficient signatures
     #[error("Insufficient signatures: got {got}, need {need}")]
-    InsufficientSignatures { got: usize, need: usize },
+    InsufficientSignatures {
+        /// Valid signatures present
+        got: usize,
+        /// Threshold required by the pact
+        need: usize,
+    },
 
     /// Unauthorized signer
     #[error("Unauthorized signer: {0}")]
     UnauthorizedSigner(String),
 
+    /// Signature does not verify over the pact signing payload
+    #[error("Invalid signature from: {0}")]
+    InvalidSignature(String),
+
+    /// Pact was revoked before the time of use
+    #[error("Pact revoked: {0}")]
+    PactRevoked(String),
+
+    /// Create for a pact id that already has a history
+    #[error("Pact already exists: {0}")]
+    PactExists(String),
+
+    /// Pact definition cannot be satisfied
+    #[error("Invalid pact: {0}")]
+    InvalidPact(String),
+
+    /// Lifecycle event takes effect before the pact's latest version
+    #[error("Pact {pact_id} event at {effective_at} precedes version at {latest}")]
+    OutOfOrder {
+        /// Pact the event is about
+        pact_id: String,
+        /// When the event would take effect
+        effective_at: i64,
+        /// When the latest version took effect
+        latest: i64,
+    },
+
+    /// Amendment does not raise the pact's version
+    #[error("Pact {pact_id} v{version} is not newer than v{latest}")]
+    StaleVersion {
+        /// Pact being amended
+        pact_id: String,
+        /// Version the amendment carries
+        version: u8,
+        /// Version in force
+        latest: u8,
+    },
+
+    /// Atom is not a pact lifecycle event for its link
+    #[error("Malformed pact event: {0}")]
+    MalformedEvent(String),
+
+    /// Signatures meet the threshold but not the pact's quorum expression
+    #[error("Quorum not met: {}", format_unmet(.0))]
+    QuorumNotMet(Vec<UnmetClause>),
+
+    /// Pact scope does not cover the target container
+    #[error("Pact {pact_id} does not cover container {container_id}")]
+    ScopeMismatch {
+        /// Pact that was presented
+        pact_id: String,
+        /// Container the link targets
+        container_id: String,
+    },
+
     /// Risk level mismatch
     #[error("Risk mismatch: intent={intent:?}, pact={pact:?}")]
-    RiskMismatch { intent: RiskLevel, pact: RiskLevel },
+    RiskMismatch {
+        /// Risk level the intent requires
+        intent: RiskLevel,
+        /// Risk level the pact authorizes
+        pact: RiskLevel,
+    },
 }
 
 /// Result type for pact operations
@@ -86,7 +166,7 @@ impl RiskLevel {
 }
 
 /// Time window for pact validity (SPEC-UBL-PACT v1.0 §7)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct TimeWindow {
     /// Unix timestamp - not valid before this
     pub not_before: i64,
@@ -102,12 +182,12 @@ impl TimeWindow {
 }
 
 /// Pact definition (SPEC-UBL-PACT v1.0 §4)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Pact {
     /// Pact identifier (Hash32)
     pub pact_id: String,
     
-    /// Protocol version
+    /// Protocol version; each amendment must raise it
     pub version: u8,
     
     /// Scope of application
@@ -116,8 +196,8 @@ pub struct Pact {
     /// Minimum threshold of signatures required
     pub threshold: usize,
     
-    /// Authorized signers (public keys in hex)
-    pub signers: HashSet<String>,
+    /// Authorized signers (public keys in hex), ordered so pact atoms hash stably
+    pub signers: BTreeSet<String>,
     
     /// Time window
     pub window: TimeWindow,
@@ -125,64 +205,201 @@ pub struct Pact {
     /// Risk level this pact authorizes
     pub risk_level: RiskLevel,
     
-    /// Optional: container ID if scope is Container
+    /// Container ID (Container scope) or container-id prefix such as
+    /// `wallet/` (Namespace scope); unused for Global
     pub container_id: Option<String>,
+
+    /// Quorum expression that must also hold over the valid signers (§4.2)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<Quorum>,
 }
 
-/// Pact proof attached to a link (SPEC-UBL-PACT v1.0 §8)
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PactProof {
-    /// Reference to the pact
-    pub pact_id: String,
-    
-    /// Signatures from authorized signers
-    pub signatures: Vec<PactSignature>,
+impl Pact {
+    /// Whether the pact's scope covers `container_id` (SPEC-UBL-PACT v1.0 §5)
+    pub fn applies_to(&self, container_id: &str) -> bool {
+        match (self.scope, &self.container_id) {
+            (PactScope::Container, Some(id)) => id == container_id,
+            (PactScope::Namespace, Some(prefix)) => container_id.starts_with(prefix.as_str()),
+            (PactScope::Global, _) => true,
+            (_, None) => false,
+        }
+    }
+
+    /// How narrowly the pact is scoped (higher is narrower): container, then
+    /// longer namespace prefixes, then global
+    fn specificity(&self) -> (u8, usize) {
+        let rank = match self.scope {
+            PactScope::Container => 2,
+            PactScope::Namespace => 1,
+            PactScope::Global => 0,
+        };
+        (rank, self.container_id.as_ref().map_or(0, String::len))
+    }
 }
 
-/// A single signature in a pact proof
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PactSignature {
-    /// Signer's public key (hex)
-    pub pubkey: String,
-    
-    /// Signature (hex)
-    pub signature: String,
+/// The link fields a pact signature covers (SPEC-UBL-PACT v1.0 §8.1)
+///
+/// Everything that places the link in a chain is signed, so a quorum
+/// collected for one link cannot be replayed onto another container or a
+/// later position with the same atom and delta.
+#[derive(Debug, Clone, Copy)]
+pub struct PactSubject<'a> {
+    /// Container the link targets (also checked against the pact's scope)
+    pub container_id: &'a str,
+    /// Sequence the link expects to occupy
+    pub expected_sequence: u64,
+    /// Entry hash the link chains from
+    pub previous_hash: &'a str,
+    /// Atom hash of the link (hex)
+    pub atom_hash: &'a str,
+    /// Intent class byte of the link
+    pub intent_class: u8,
+    /// Physics delta of the link
+    pub physics_delta: i128,
+}
+
+impl<'a> PactSubject<'a> {
+    /// The subject a link's pact proof must cover
+    pub fn of(link: &'a LinkCommit) -> Self {
+        Self {
+            container_id: &link.container_id,
+            expected_sequence: link.expected_sequence,
+            previous_hash: &link.previous_hash,
+            atom_hash: &link.atom_hash,
+            intent_class: link.intent_class.as_byte(),
+            physics_delta: link.physics_delta,
+        }
+    }
+
+    /// Message each signer signs for `pact_id`, strings prefixed with their
+    /// u32 big-endian length:
+    /// `BLAKE3("ubl:pact\n" || pact_id || container_id || expected_sequence ||
+    /// previous_hash || atom_hash || intent_class || physics_delta)`
+    pub fn signing_bytes(&self, pact_id: &str) -> Vec<u8> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(ubl_kernel::domains::PACT);
+        hash_str(&mut hasher, pact_id);
+        hash_str(&mut hasher, self.container_id);
+        hasher.update(&self.expected_sequence.to_be_bytes());
+        hash_str(&mut hasher, self.previous_hash);
+        hash_str(&mut hasher, self.atom_hash);
+        hasher.update(&[self.intent_class]);
+        hasher.update(&self.physics_delta.to_be_bytes());
+        hasher.finalize().as_bytes().to_vec()
+    }
+}
+
+fn hash_str(hasher: &mut blake3::Hasher, value: &str) {
+    let len = u32::try_from(value.len()).expect("pact fields are shorter than 4 GiB");
+    hasher.update(&len.to_be_bytes());
+    hasher.update(value.as_bytes());
 }
 
-/// Pact registry for validation
+/// Pact registry for validation: every version of every pact
+#[derive(Debug, Clone)]
 pub struct PactRegistry {
-    pacts: std::collections::HashMap<String, Pact>,
+    pacts: HashMap<String, Vec<PactVersion>>,
 }
 
 impl PactRegistry {
     /// Create a new empty registry
     pub fn new() -> Self {
         Self {
-            pacts: std::collections::HashMap::new(),
+            pacts: HashMap::new(),
         }
     }
 
-    /// Register a pact
+    /// Register a genesis pact, in force at all times (replaces its history)
     pub fn register(&mut self, pact: Pact) {
-        self.pacts.insert(pact.pact_id.clone(), pact);
+        let pact_id = pact.pact_id.clone();
+        let version = PactVersion {
+            effective_from: i64::MIN,
+            pact: Some(pact),
+        };
+        self.pacts.insert(pact_id, vec![version]);
     }
 
-    /// Get a pact by ID
+    /// Current version of a pact (`None` if unknown or revoked)
     pub fn get(&self, pact_id: &str) -> Option<&Pact> {
-        self.pacts.get(pact_id)
+        self.history(pact_id).last()?.pact.as_ref()
+    }
+
+    /// Version of a pact in force at `at`
+    pub fn get_at(&self, pact_id: &str, at: i64) -> Result<&Pact> {
+        let version = self
+            .history(pact_id)
+            .iter()
+            .rev()
+            .find(|v| v.effective_from <= at)
+            .ok_or_else(|| PactError::UnknownPact(pact_id.to_string()))?;
+        version
+            .pact
+            .as_ref()
+            .ok_or_else(|| PactError::PactRevoked(pact_id.to_string()))
+    }
+
+    /// Pacts able to authorize a commit on `container_id` at `at`: in force,
+    /// inside their time window, covering the container and at or above
+    /// `required` (the risk the container's physics demand for the intent, as
+    /// the membrane's rules resolve it). Most specific first (container,
+    /// longest namespace prefix, global); ties by pact id.
+    pub fn applicable(&self, container_id: &str, required: RiskLevel, at: i64) -> Vec<&Pact> {
+        let mut pacts: Vec<&Pact> = self
+            .pacts
+            .keys()
+            .filter_map(|id| self.get_at(id, at).ok())
+            .filter(|p| p.window.is_valid(at) && p.applies_to(container_id))
+            .filter(|p| p.risk_level >= required)
+            .collect();
+        pacts.sort_by(|a, b| {
+            b.specificity()
+                .cmp(&a.specificity())
+                .then_with(|| a.pact_id.cmp(&b.pact_id))
+        });
+        pacts
+    }
+
+    /// Every version of a pact, oldest first
+    pub fn history(&self, pact_id: &str) -> &[PactVersion] {
+        self.pacts.get(pact_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether no pact is in force at `at` (nothing can authorize Entropy or Evolution)
+    pub fn is_empty_at(&self, at: i64) -> bool {
+        self.pacts.keys().all(|id| self.get_at(id, at).is_err())
     }
 
     /// Validate a pact proof (SPEC-UBL-PACT v1.0 §9)
+    /// Every counted signature is verified over `subject.signing_bytes(pact_id)`
     pub fn validate(
         &self,
         proof: &PactProof,
-        intent_class: u8,
+        subject: &PactSubject,
+        now: i64,
+    ) -> Result<()> {
+        let required = RiskLevel::from_intent_class(subject.intent_class);
+        self.validate_at_risk(proof, subject, required, now)
+    }
+
+    /// Validate a pact proof, requiring the pact to authorize at least `required`
+    /// (containers may demand more than the intent class minimum)
+    pub fn validate_at_risk(
+        &self,
+        proof: &PactProof,
+        subject: &PactSubject,
+        required: RiskLevel,
         now: i64,
     ) -> Result<()> {
-        // Get the pact
-        let pact = self
-            .get(&proof.pact_id)
-            .ok_or_else(|| PactError::UnknownPact(proof.pact_id.clone()))?;
+        // Get the pact version in force at `now`
+        let pact = self.get_at(&proof.pact_id, now)?;
+
+        // Check scope (SPEC-UBL-PACT v1.0 §5)
+        if !pact.applies_to(subject.container_id) {
+            return Err(PactError::ScopeMismatch {
+                pact_id: pact.pact_id.clone(),
+                container_id: subject.container_id.to_string(),
+            });
+        }
 
         // Check time window
         if !pact.window.is_valid(now) {
@@ -190,44 +407,66 @@ impl PactRegistry {
         }
 
         // Check risk level
-        let required_risk = RiskLevel::from_intent_class(intent_class);
-        if pact.risk_level < required_risk {
+        if pact.risk_level < required {
             return Err(PactError::RiskMismatch {
-                intent: required_risk,
+                intent: required,
                 pact: pact.risk_level,
             });
         }
 
-        // Count valid signatures
-        let mut valid_count = 0;
-        let mut seen_pubkeys = HashSet::new();
-
-        for sig in &proof.signatures {
-            // Check for duplicates
-            if !seen_pubkeys.insert(&sig.pubkey) {
-                continue;
-            }
-
-            // Check if signer is authorized
-            if !pact.signers.contains(&sig.pubkey) {
-                return Err(PactError::UnauthorizedSigner(sig.pubkey.clone()));
-            }
+        check_signatures(pact, proof, subject)
+    }
+}
 
-            // In a real implementation, we'd verify the signature here
-            // For now, we trust the signature is valid
-            valid_count += 1;
+/// Signatures of `proof` meet `pact`'s threshold and quorum over `subject`
+fn check_signatures(pact: &Pact, proof: &PactProof, subject: &PactSubject) -> Result<()> {
+    // Count valid signatures
+    let message = subject.signing_bytes(&pact.pact_id);
+    let mut valid_count = 0;
+    let mut seen_pubkeys = BTreeSet::new();
+
+    for sig in &proof.signatures {
+        // Check for duplicates
+        if !seen_pubkeys.insert(sig.pubkey.as_str()) {
+            continue;
         }
 
-        // Check threshold
-        if valid_count < pact.threshold {
-            return Err(PactError::InsufficientSignatures {
-                got: valid_count,
-                need: pact.threshold,
-            });
+        // Check if signer is authorized
+        if !pact.signers.contains(&sig.pubkey) {
+            return Err(PactError::UnauthorizedSigner(sig.pubkey.clone()));
         }
 
-        Ok(())
+        // SPEC-UBL-PACT I2: authority must be proven by a verifiable signature
+        ubl_kernel::verify(&sig.pubkey, &message, &sig.signature)
+            .map_err(|_| PactError::InvalidSignature(sig.pubkey.clone()))?;
+
+        valid_count += 1;
+    }
+
+    // Check threshold
+    if valid_count < pact.threshold {
+        return Err(PactError::InsufficientSignatures {
+            got: valid_count,
+            need: pact.threshold,
+        });
+    }
+
+    // Check quorum expression (SPEC-UBL-PACT v1.0 §4.2)
+    if let Some(quorum) = &pact.quorum {
+        quorum
+            .evaluate(&seen_pubkeys)
+            .map_err(PactError::QuorumNotMet)?;
     }
+
+    Ok(())
+}
+
+fn format_unmet(clauses: &[UnmetClause]) -> String {
+    clauses
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl Default for PactRegistry {
@@ -239,118 +478,337 @@ impl Default for PactRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ed25519_dalek::SigningKey;
+
+    const SUBJECT: PactSubject<'static> = PactSubject {
+        container_id: "test",
+        expected_sequence: 7,
+        previous_hash: "prev",
+        atom_hash: "atom",
+        intent_class: 0x01,
+        physics_delta: -100,
+    };
+
+    fn keys(n: usize) -> Vec<(String, SigningKey)> {
+        (0..n).map(|_| ubl_kernel::generate_keypair()).collect()
+    }
 
-    fn make_pact(threshold: usize, signers: Vec<&str>) -> Pact {
+    fn make_pact(threshold: usize, signers: &[(String, SigningKey)]) -> Pact {
         Pact {
             pact_id: "pact_test".to_string(),
             version: 1,
             scope: PactScope::Container,
             threshold,
-            signers: signers.into_iter().map(|s| s.to_string()).collect(),
+            signers: signers.iter().map(|(pk, _)| pk.clone()).collect(),
             window: TimeWindow {
                 not_before: 0,
                 not_after: i64::MAX,
             },
             risk_level: RiskLevel::L2,
             container_id: Some("test".to_string()),
+            quorum: None,
+        }
+    }
+
+    fn sign(key: &(String, SigningKey), subject: &PactSubject) -> PactSignature {
+        PactSignature {
+            pubkey: key.0.clone(),
+            signature: ubl_kernel::sign(&key.1, &subject.signing_bytes("pact_test")),
+        }
+    }
+
+    fn proof(signatures: Vec<PactSignature>) -> PactProof {
+        PactProof {
+            pact_id: "pact_test".to_string(),
+            signatures,
         }
     }
 
     #[test]
     fn test_valid_pact() {
+        let signers = keys(3);
         let mut registry = PactRegistry::new();
-        registry.register(make_pact(2, vec!["alice", "bob", "charlie"]));
+        registry.register(make_pact(2, &signers));
 
-        let proof = PactProof {
-            pact_id: "pact_test".to_string(),
-            signatures: vec![
-                PactSignature {
-                    pubkey: "alice".to_string(),
-                    signature: "sig1".to_string(),
-                },
-                PactSignature {
-                    pubkey: "bob".to_string(),
-                    signature: "sig2".to_string(),
-                },
-            ],
-        };
+        let proof = proof(vec![sign(&signers[0], &SUBJECT), sign(&signers[1], &SUBJECT)]);
 
-        let result = registry.validate(&proof, 0x01, 1000);
+        let result = registry.validate(&proof, &SUBJECT, 1000);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_insufficient_signatures() {
+        let signers = keys(3);
         let mut registry = PactRegistry::new();
-        registry.register(make_pact(3, vec!["alice", "bob", "charlie"]));
+        registry.register(make_pact(3, &signers));
 
-        let proof = PactProof {
-            pact_id: "pact_test".to_string(),
-            signatures: vec![PactSignature {
-                pubkey: "alice".to_string(),
-                signature: "sig1".to_string(),
-            }],
-        };
+        let proof = proof(vec![sign(&signers[0], &SUBJECT)]);
 
-        let result = registry.validate(&proof, 0x01, 1000);
+        let result = registry.validate(&proof, &SUBJECT, 1000);
         assert!(matches!(
             result,
             Err(PactError::InsufficientSignatures { got: 1, need: 3 })
         ));
     }
 
+    #[test]
+    fn test_duplicate_signatures_count_once() {
+        let signers = keys(2);
+        let mut registry = PactRegistry::new();
+        registry.register(make_pact(2, &signers));
+
+        let proof = proof(vec![sign(&signers[0], &SUBJECT), sign(&signers[0], &SUBJECT)]);
+
+        let result = registry.validate(&proof, &SUBJECT, 1000);
+        assert!(matches!(
+            result,
+            Err(PactError::InsufficientSignatures { got: 1, need: 2 })
+        ));
+    }
+
     #[test]
     fn test_unauthorized_signer() {
+        let signers = keys(2);
+        let eve = keys(1);
         let mut registry = PactRegistry::new();
-        registry.register(make_pact(1, vec!["alice", "bob"]));
+        registry.register(make_pact(1, &signers));
 
-        let proof = PactProof {
-            pact_id: "pact_test".to_string(),
-            signatures: vec![PactSignature {
-                pubkey: "eve".to_string(),
-                signature: "sig1".to_string(),
-            }],
-        };
+        let proof = proof(vec![sign(&eve[0], &SUBJECT)]);
 
-        let result = registry.validate(&proof, 0x01, 1000);
+        let result = registry.validate(&proof, &SUBJECT, 1000);
         assert!(matches!(result, Err(PactError::UnauthorizedSigner(_))));
     }
 
+    #[test]
+    fn test_forged_signature() {
+        let signers = keys(1);
+        let mut registry = PactRegistry::new();
+        registry.register(make_pact(1, &signers));
+
+        let mut forged = sign(&signers[0], &SUBJECT);
+        forged.signature = "00".repeat(64);
+
+        let result = registry.validate(&proof(vec![forged]), &SUBJECT, 1000);
+        assert!(matches!(result, Err(PactError::InvalidSignature(_))));
+    }
+
+    #[test]
+    fn test_signature_bound_to_subject() {
+        let signers = keys(1);
+        let mut registry = PactRegistry::new();
+        registry.register(Pact {
+            scope: PactScope::Global,
+            ..make_pact(1, &signers)
+        });
+        let proof = proof(vec![sign(&signers[0], &SUBJECT)]);
+
+        // Replayed against a bigger delta, another container or a later position
+        for other in [
+            PactSubject {
+                physics_delta: -1_000_000,
+                ..SUBJECT
+            },
+            PactSubject {
+                container_id: "other",
+                ..SUBJECT
+            },
+            PactSubject {
+                expected_sequence: 8,
+                ..SUBJECT
+            },
+            PactSubject {
+                previous_hash: "next",
+                ..SUBJECT
+            },
+        ] {
+            let result = registry.validate(&proof, &other, 1000);
+            assert!(matches!(result, Err(PactError::InvalidSignature(_))));
+        }
+
+        // Fields are length-prefixed, so moving bytes between them changes the message
+        let shifted = PactSubject {
+            previous_hash: "pre",
+            atom_hash: "vatom",
+            ..SUBJECT
+        };
+        assert_ne!(SUBJECT.signing_bytes("pact_test"), shifted.signing_bytes("pact_test"));
+    }
+
     #[test]
     fn test_expired_pact() {
+        let signers = keys(1);
         let mut registry = PactRegistry::new();
-        let mut pact = make_pact(1, vec!["alice"]);
+        let mut pact = make_pact(1, &signers);
         pact.window.not_after = 1000;
         registry.register(pact);
 
-        let proof = PactProof {
-            pact_id: "pact_test".to_string(),
-            signatures: vec![PactSignature {
-                pubkey: "alice".to_string(),
-                signature: "sig1".to_string(),
-            }],
-        };
+        let proof = proof(vec![sign(&signers[0], &SUBJECT)]);
 
-        let result = registry.validate(&proof, 0x01, 2000);
+        let result = registry.validate(&proof, &SUBJECT, 2000);
         assert!(matches!(result, Err(PactError::PactExpired)));
     }
 
+    #[test]
+    fn test_validate_at_higher_risk() {
+        let signers = keys(1);
+        let mut registry = PactRegistry::new();
+        registry.register(make_pact(1, &signers)); // L2
+        let proof = proof(vec![sign(&signers[0], &SUBJECT)]);
+
+        assert!(registry.validate_at_risk(&proof, &SUBJECT, RiskLevel::L2, 1000).is_ok());
+        let result = registry.validate_at_risk(&proof, &SUBJECT, RiskLevel::L3, 1000);
+        assert!(matches!(
+            result,
+            Err(PactError::RiskMismatch { intent: RiskLevel::L3, pact: RiskLevel::L2 })
+        ));
+    }
+
     #[test]
     fn test_risk_mismatch() {
+        let signers = keys(1);
         let mut registry = PactRegistry::new();
-        let mut pact = make_pact(1, vec!["alice"]);
+        let mut pact = make_pact(1, &signers);
         pact.risk_level = RiskLevel::L1; // Too low for Conservation
         registry.register(pact);
 
-        let proof = PactProof {
-            pact_id: "pact_test".to_string(),
-            signatures: vec![PactSignature {
-                pubkey: "alice".to_string(),
-                signature: "sig1".to_string(),
-            }],
-        };
+        let proof = proof(vec![sign(&signers[0], &SUBJECT)]);
 
-        let result = registry.validate(&proof, 0x01, 1000); // Conservation requires L2
+        let result = registry.validate(&proof, &SUBJECT, 1000); // Conservation requires L2
         assert!(matches!(result, Err(PactError::RiskMismatch { .. })));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_quorum_reports_unmet_clauses() {
+        let signers = keys(4);
+        let (guardian, cfo, clerk, legal) = (&signers[0], &signers[1], &signers[2], &signers[3]);
+        let quorum = Quorum::all(vec![
+            Quorum::signer(guardian.0.clone()).labeled("guardian"),
+            Quorum::Weighted {
+                threshold: 2,
+                weights: [(cfo.0.clone(), 2), (clerk.0.clone(), 1)].into(),
+                label: Some("finance".to_string()),
+            },
+            Quorum::n_of(1, [legal.0.clone()]).labeled("legal"),
+        ]);
+        let mut registry = PactRegistry::new();
+        registry.register(Pact {
+            quorum: Some(quorum),
+            ..make_pact(2, &signers)
+        });
+
+        let approve = |by: &[&(String, SigningKey)]| {
+            registry.validate(
+                &proof(by.iter().map(|key| sign(key, &SUBJECT)).collect()),
+                &SUBJECT,
+                1000,
+            )
+        };
+        approve(&[guardian, cfo, legal]).unwrap();
+
+        let Err(PactError::QuorumNotMet(unmet)) = approve(&[clerk, legal]) else {
+            panic!("quorum should not be met");
+        };
+        let labels: Vec<_> = unmet.iter().filter_map(|c| c.label.as_deref()).collect();
+        assert_eq!(labels, ["guardian", "finance"]);
+        assert_eq!((unmet[2].got, unmet[2].need), (1, 2));
+    }
+
+    fn scoped(id: &str, scope: PactScope, target: Option<&str>, risk: RiskLevel) -> Pact {
+        Pact {
+            pact_id: id.to_string(),
+            scope,
+            container_id: target.map(str::to_string),
+            risk_level: risk,
+            ..make_pact(1, &keys(1))
+        }
+    }
+
+    #[test]
+    fn test_scope_enforced() {
+        let signers = keys(1);
+        let for_wallets = |scope, target: Option<&str>| {
+            let mut registry = PactRegistry::new();
+            registry.register(Pact {
+                scope,
+                container_id: target.map(str::to_string),
+                ..make_pact(1, &signers)
+            });
+            registry
+        };
+        let alice = PactSubject {
+            container_id: "wallet/alice",
+            ..SUBJECT
+        };
+        let jobs = PactSubject {
+            container_id: "C.Jobs",
+            ..SUBJECT
+        };
+        let signed = |subject| proof(vec![sign(&signers[0], subject)]);
+
+        let container = for_wallets(PactScope::Container, Some("wallet/alice"));
+        assert!(container.validate(&signed(&alice), &alice, 1000).is_ok());
+        let sibling = PactSubject {
+            container_id: "wallet/alice2",
+            ..alice
+        };
+        assert!(matches!(
+            container.validate(&signed(&sibling), &sibling, 1000),
+            Err(PactError::ScopeMismatch { .. })
+        ));
+
+        let namespace = for_wallets(PactScope::Namespace, Some("wallet/"));
+        assert!(namespace.validate(&signed(&alice), &alice, 1000).is_ok());
+        assert!(matches!(
+            namespace.validate(&signed(&jobs), &jobs, 1000),
+            Err(PactError::ScopeMismatch { .. })
+        ));
+
+        let global = for_wallets(PactScope::Global, None);
+        assert!(global.validate(&signed(&jobs), &jobs, 1000).is_ok());
+    }
+
+    #[test]
+    fn test_applicable_most_specific_first() {
+        let mut registry = PactRegistry::new();
+        for pact in [
+            scoped("global", PactScope::Global, None, RiskLevel::L5),
+            scoped("wallets", PactScope::Namespace, Some("wallet/"), RiskLevel::L3),
+            scoped("eu_wallets", PactScope::Namespace, Some("wallet/eu/"), RiskLevel::L3),
+            scoped("alice", PactScope::Container, Some("wallet/eu/alice"), RiskLevel::L2),
+            scoped("bob", PactScope::Container, Some("wallet/eu/bob"), RiskLevel::L5),
+            scoped("weak", PactScope::Namespace, Some("wallet/"), RiskLevel::L1),
+            Pact {
+                window: TimeWindow {
+                    not_before: 0,
+                    not_after: 500,
+                },
+                ..scoped("expired", PactScope::Container, Some("wallet/eu/alice"), RiskLevel::L5)
+            },
+        ] {
+            registry.register(pact);
+        }
+
+        let ids = |container, required, at| -> Vec<String> {
+            registry
+                .applicable(container, required, at)
+                .iter()
+                .map(|p| p.pact_id.clone())
+                .collect()
+        };
+        assert_eq!(
+            ids("wallet/eu/alice", RiskLevel::L2, 1000),
+            ["alice", "eu_wallets", "wallets", "global"]
+        );
+        // Entropy under the default physics (L4), and in a container that lowers it to L3
+        assert_eq!(ids("wallet/eu/alice", RiskLevel::L4, 1000), ["global"]);
+        assert_eq!(
+            ids("wallet/eu/alice", RiskLevel::L3, 1000),
+            ["eu_wallets", "wallets", "global"]
+        );
+        assert_eq!(
+            ids("wallet/us/carol", RiskLevel::L0, 1000),
+            ["wallets", "weak", "global"]
+        );
+        assert_eq!(ids("wallet/eu/alice", RiskLevel::L4, 400), ["expired", "global"]);
+    }
+}
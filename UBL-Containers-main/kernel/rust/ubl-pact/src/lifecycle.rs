@@ -0,0 +1,527 @@
+//! Pact lifecycle as ledger history (SPEC-UBL-PACT v1.0 §10)
+//!
+//! Pacts are created, amended and revoked by Evolution commits to the
+//! [`PACTS_CONTAINER`]. Each commit's atom is a [`PactEvent`]:
+//!
+//! ```text
+//! { "type": "pact/lifecycle", "op": "create", "pact": { ... } }
+//! { "type": "pact/lifecycle", "op": "amend",  "pact": { ... } }
+//! { "type": "pact/lifecycle", "op": "revoke", "pact_id": "..." }
+//! ```
+//!
+//! The [`PactRegistry`] is a projection of that container: replaying its
+//! events in order, each taking effect at its commit time, yields every
+//! version a pact has had. Validation at time T uses the version in force at
+//! T, so an amendment or revocation never reaches back to links accepted
+//! before it.
+//!
+//! Authority over `C.Pacts` itself (an L5 pact) is not enough to change a
+//! pact: an amend or revoke must also be approved by the target pact's own
+//! signers, meeting its threshold and quorum ([`PactRegistry::check_approval`]).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ubl_link::{IntentClass, LinkCommit};
+
+use crate::{Pact, PactError, PactProof, PactRegistry, PactScope, PactSubject, Result};
+
+/// Container whose Evolution commits define the pacts
+pub const PACTS_CONTAINER: &str = "C.Pacts";
+
+/// `type` of a pact lifecycle atom
+pub const PACT_ATOM_TYPE: &str = "pact/lifecycle";
+
+/// One change to the set of pacts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PactEvent {
+    /// Introduce a new pact id
+    Create {
+        /// First version of the pact
+        pact: Pact,
+    },
+    /// Replace a live pact (signers, threshold, window, risk)
+    Amend {
+        /// Next version of the pact
+        pact: Pact,
+    },
+    /// End a pact; it can never be amended or used again
+    Revoke {
+        /// Pact being revoked
+        pact_id: String,
+    },
+}
+
+impl PactEvent {
+    /// Pact the event is about
+    pub fn pact_id(&self) -> &str {
+        match self {
+            PactEvent::Create { pact } | PactEvent::Amend { pact } => &pact.pact_id,
+            PactEvent::Revoke { pact_id } => pact_id,
+        }
+    }
+
+    /// The atom committed for this event
+    pub fn to_atom(&self) -> Value {
+        let mut atom = serde_json::to_value(self).expect("pact events serialize");
+        atom["type"] = Value::from(PACT_ATOM_TYPE);
+        atom
+    }
+
+    /// `atom_hash` of [`to_atom`](PactEvent::to_atom)
+    pub fn atom_hash(&self) -> Result<String> {
+        ubl_atom::hash_value(&self.to_atom()).map_err(|e| malformed(e.to_string()))
+    }
+
+    /// Decode a lifecycle atom
+    pub fn from_atom(atom: &Value) -> Result<Self> {
+        if atom.get("type").and_then(Value::as_str) != Some(PACT_ATOM_TYPE) {
+            return Err(malformed(format!("atom type is not {}", PACT_ATOM_TYPE)));
+        }
+        serde_json::from_value(atom.clone()).map_err(|e| malformed(e.to_string()))
+    }
+
+    /// Decode the event a `C.Pacts` commit carries, checking that the link
+    /// is an Evolution on the pacts container and commits exactly `atom`
+    pub fn from_link(link: &LinkCommit, atom: &Value) -> Result<Self> {
+        if link.container_id != PACTS_CONTAINER {
+            return Err(malformed(format!("link targets {}", link.container_id)));
+        }
+        if link.intent_class != IntentClass::Evolution {
+            return Err(malformed("pact changes must be Evolution"));
+        }
+        if ubl_atom::hash_value(atom).map_err(|e| malformed(e.to_string()))? != link.atom_hash {
+            return Err(malformed("atom does not match the link's atom_hash"));
+        }
+        Self::from_atom(atom)
+    }
+}
+
+/// A pact as it stood from `effective_from` until the next version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PactVersion {
+    /// Unix time the version took effect
+    pub effective_from: i64,
+    /// The pact, or `None` once revoked
+    pub pact: Option<Pact>,
+}
+
+impl PactRegistry {
+    /// Check that `event` may be applied at `effective_at` without applying it
+    pub fn check(&self, event: &PactEvent, effective_at: i64) -> Result<()> {
+        let id = event.pact_id();
+        let latest = self.history(id).last();
+
+        if let Some(latest) = latest {
+            if effective_at < latest.effective_from {
+                return Err(PactError::OutOfOrder {
+                    pact_id: id.to_string(),
+                    effective_at,
+                    latest: latest.effective_from,
+                });
+            }
+        }
+        match (event, latest) {
+            (PactEvent::Create { .. }, Some(_)) => Err(PactError::PactExists(id.to_string())),
+            (PactEvent::Create { pact }, None) => check_pact(pact),
+            (_, None) => Err(PactError::UnknownPact(id.to_string())),
+            (_, Some(PactVersion { pact: None, .. })) => {
+                Err(PactError::PactRevoked(id.to_string()))
+            }
+            (
+                PactEvent::Amend { pact },
+                Some(PactVersion {
+                    pact: Some(current),
+                    ..
+                }),
+            ) => {
+                if pact.version <= current.version {
+                    return Err(PactError::StaleVersion {
+                        pact_id: id.to_string(),
+                        version: pact.version,
+                        latest: current.version,
+                    });
+                }
+                check_pact(pact)
+            }
+            (PactEvent::Revoke { .. }, Some(_)) => Ok(()),
+        }
+    }
+
+    /// Check that the pact an amend or revoke targets approved `link`
+    ///
+    /// The approval is `approval`, or the link's own proof when it is for the
+    /// target; its signatures over the link must meet the threshold and quorum
+    /// of the target version in force at `at`. Scope, window and risk do not
+    /// apply: they govern what the pact authorizes, not who may change it.
+    /// Creates need no approval.
+    pub fn check_approval(
+        &self,
+        event: &PactEvent,
+        link: &LinkCommit,
+        approval: Option<&PactProof>,
+        at: i64,
+    ) -> Result<()> {
+        if matches!(event, PactEvent::Create { .. }) {
+            return Ok(());
+        }
+        let id = event.pact_id();
+        let target = self.get_at(id, at)?;
+        let proof = approval
+            .into_iter()
+            .chain(link.pact.as_ref())
+            .find(|proof| proof.pact_id == id)
+            .ok_or(PactError::InsufficientSignatures {
+                got: 0,
+                need: target.threshold,
+            })?;
+        crate::check_signatures(target, proof, &PactSubject::of(link))
+    }
+
+    /// Apply a lifecycle event taking effect at `effective_at`
+    pub fn apply(&mut self, event: &PactEvent, effective_at: i64) -> Result<()> {
+        self.check(event, effective_at)?;
+        let pact = match event {
+            PactEvent::Create { pact } | PactEvent::Amend { pact } => Some(pact.clone()),
+            PactEvent::Revoke { .. } => None,
+        };
+        self.pacts
+            .entry(event.pact_id().to_string())
+            .or_default()
+            .push(PactVersion {
+                effective_from: effective_at,
+                pact,
+            });
+        Ok(())
+    }
+
+    /// Rebuild a registry from `(event, effective_at)` pairs in commit order
+    pub fn replay(events: impl IntoIterator<Item = (PactEvent, i64)>) -> Result<Self> {
+        let mut registry = Self::new();
+        for (event, effective_at) in events {
+            registry.apply(&event, effective_at)?;
+        }
+        Ok(registry)
+    }
+}
+
+fn check_pact(pact: &Pact) -> Result<()> {
+    if pact.scope != PactScope::Global && pact.container_id.is_none() {
+        return Err(PactError::InvalidPact(format!(
+            "{:?} scope needs container_id",
+            pact.scope
+        )));
+    }
+    if pact.threshold == 0 || pact.threshold > pact.signers.len() {
+        return Err(PactError::InvalidPact(format!(
+            "threshold {} with {} signers",
+            pact.threshold,
+            pact.signers.len()
+        )));
+    }
+    if let Some(quorum) = &pact.quorum {
+        let named = quorum.signers();
+        if let Some(stranger) = named.iter().find(|pk| !pact.signers.contains(**pk)) {
+            return Err(PactError::InvalidPact(format!(
+                "quorum names {} who is not a signer",
+                stranger
+            )));
+        }
+        if quorum.evaluate(&named).is_err() {
+            return Err(PactError::InvalidPact(
+                "quorum cannot be met by its signers".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn malformed(reason: impl Into<String>) -> PactError {
+    PactError::MalformedEvent(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PactSignature, RiskLevel, TimeWindow};
+    use ubl_kernel::SigningKey;
+
+    const SUBJECT: PactSubject<'static> = PactSubject {
+        container_id: "wallet/alice",
+        expected_sequence: 1,
+        previous_hash: "0x00",
+        atom_hash: "atom",
+        intent_class: 0x01,
+        physics_delta: -5,
+    };
+
+    fn pact(threshold: usize, signers: &[&(String, SigningKey)]) -> Pact {
+        Pact {
+            pact_id: "treasury".to_string(),
+            version: 1,
+            scope: PactScope::Global,
+            threshold,
+            signers: signers.iter().map(|(pk, _)| pk.clone()).collect(),
+            window: TimeWindow {
+                not_before: 0,
+                not_after: i64::MAX,
+            },
+            risk_level: RiskLevel::L3,
+            container_id: None,
+            quorum: None,
+        }
+    }
+
+    fn proof(signers: &[&(String, SigningKey)]) -> PactProof {
+        PactProof {
+            pact_id: "treasury".to_string(),
+            signatures: signers
+                .iter()
+                .map(|(pk, key)| PactSignature {
+                    pubkey: pk.clone(),
+                    signature: ubl_kernel::sign(key, &SUBJECT.signing_bytes("treasury")),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_validation_uses_version_in_force() {
+        let (alice, bob) = (
+            ubl_kernel::generate_keypair(),
+            ubl_kernel::generate_keypair(),
+        );
+        let registry = PactRegistry::replay([
+            (
+                PactEvent::Create {
+                    pact: pact(1, &[&alice]),
+                },
+                100,
+            ),
+            (
+                PactEvent::Amend {
+                    pact: Pact {
+                        version: 2,
+                        ..pact(1, &[&bob])
+                    },
+                },
+                200,
+            ),
+            (
+                PactEvent::Revoke {
+                    pact_id: "treasury".into(),
+                },
+                300,
+            ),
+        ])
+        .unwrap();
+
+        let by_alice = proof(&[&alice]);
+        assert!(matches!(
+            registry.validate(&by_alice, &SUBJECT, 50),
+            Err(PactError::UnknownPact(_))
+        ));
+        registry.validate(&by_alice, &SUBJECT, 150).unwrap();
+        assert!(matches!(
+            registry.validate(&by_alice, &SUBJECT, 250),
+            Err(PactError::UnauthorizedSigner(_))
+        ));
+        registry.validate(&proof(&[&bob]), &SUBJECT, 250).unwrap();
+        assert!(matches!(
+            registry.validate(&proof(&[&bob]), &SUBJECT, 300),
+            Err(PactError::PactRevoked(_))
+        ));
+        assert!(registry.get("treasury").is_none());
+        assert_eq!(registry.history("treasury").len(), 3);
+        assert!(registry.is_empty_at(50) && registry.is_empty_at(300));
+        assert!(!registry.is_empty_at(150));
+    }
+
+    #[test]
+    fn test_lifecycle_rules() {
+        let alice = ubl_kernel::generate_keypair();
+        let mut registry = PactRegistry::new();
+        let create = PactEvent::Create {
+            pact: pact(1, &[&alice]),
+        };
+        let revoke = PactEvent::Revoke {
+            pact_id: "treasury".into(),
+        };
+
+        assert!(matches!(
+            registry.apply(&revoke, 10),
+            Err(PactError::UnknownPact(_))
+        ));
+        assert!(matches!(
+            registry.apply(
+                &PactEvent::Create {
+                    pact: pact(2, &[&alice])
+                },
+                10
+            ),
+            Err(PactError::InvalidPact(_))
+        ));
+        let impossible = Pact {
+            quorum: Some(crate::Quorum::n_of(2, [alice.0.clone()])),
+            ..pact(1, &[&alice])
+        };
+        assert!(matches!(
+            registry.apply(&PactEvent::Create { pact: impossible }, 10),
+            Err(PactError::InvalidPact(_))
+        ));
+        registry.apply(&create, 10).unwrap();
+        assert!(matches!(
+            registry.apply(&create, 20),
+            Err(PactError::PactExists(_))
+        ));
+        // Amendments must raise the version
+        assert!(matches!(
+            registry.apply(
+                &PactEvent::Amend {
+                    pact: pact(1, &[&alice])
+                },
+                20
+            ),
+            Err(PactError::StaleVersion {
+                version: 1,
+                latest: 1,
+                ..
+            })
+        ));
+        assert_eq!(registry.history("treasury").len(), 1);
+        assert!(matches!(
+            registry.apply(&revoke, 5),
+            Err(PactError::OutOfOrder { latest: 10, .. })
+        ));
+        registry.apply(&revoke, 20).unwrap();
+        assert!(matches!(
+            registry.apply(
+                &PactEvent::Amend {
+                    pact: pact(1, &[&alice])
+                },
+                30
+            ),
+            Err(PactError::PactRevoked(_))
+        ));
+    }
+
+    #[test]
+    fn test_amend_needs_target_quorum() {
+        let (alice, bob) = (
+            ubl_kernel::generate_keypair(),
+            ubl_kernel::generate_keypair(),
+        );
+        let registry = PactRegistry::replay([(
+            PactEvent::Create {
+                pact: pact(2, &[&alice, &bob]),
+            },
+            10,
+        )])
+        .unwrap();
+        let amend = PactEvent::Amend {
+            pact: Pact {
+                version: 2,
+                ..pact(1, &[&alice])
+            },
+        };
+        let link = LinkCommit {
+            version: 2,
+            container_id: PACTS_CONTAINER.to_string(),
+            expected_sequence: 2,
+            previous_hash: "prev".to_string(),
+            atom_hash: amend.atom_hash().unwrap(),
+            intent_class: IntentClass::Evolution,
+            physics_delta: 0,
+            constraints: Vec::new(),
+            pact: None,
+            author_pubkey: alice.0.clone(),
+            signature: String::new(),
+        };
+        let approval = |signers: &[&(String, SigningKey)]| PactProof {
+            pact_id: "treasury".to_string(),
+            signatures: signers
+                .iter()
+                .map(|(pk, key)| PactSignature {
+                    pubkey: pk.clone(),
+                    signature: ubl_kernel::sign(
+                        key,
+                        &PactSubject::of(&link).signing_bytes("treasury"),
+                    ),
+                })
+                .collect(),
+        };
+
+        assert!(matches!(
+            registry.check_approval(&amend, &link, None, 20),
+            Err(PactError::InsufficientSignatures { got: 0, need: 2 })
+        ));
+        assert!(matches!(
+            registry.check_approval(&amend, &link, Some(&approval(&[&alice])), 20),
+            Err(PactError::InsufficientSignatures { got: 1, need: 2 })
+        ));
+        // A proof for another pact (e.g. the L5 pact over C.Pacts) is not an approval
+        let foreign = PactProof {
+            pact_id: "board".to_string(),
+            ..approval(&[&alice, &bob])
+        };
+        assert!(registry
+            .check_approval(&amend, &link, Some(&foreign), 20)
+            .is_err());
+        registry
+            .check_approval(&amend, &link, Some(&approval(&[&alice, &bob])), 20)
+            .unwrap();
+        let by_proof = LinkCommit {
+            pact: Some(approval(&[&alice, &bob])),
+            ..link.clone()
+        };
+        registry
+            .check_approval(&amend, &by_proof, None, 20)
+            .unwrap();
+        let create = PactEvent::Create {
+            pact: pact(1, &[&bob]),
+        };
+        registry.check_approval(&create, &link, None, 20).unwrap();
+    }
+
+    #[test]
+    fn test_event_atoms_round_trip() {
+        let alice = ubl_kernel::generate_keypair();
+        let event = PactEvent::Amend {
+            pact: pact(1, &[&alice]),
+        };
+        let atom = event.to_atom();
+        assert_eq!(atom["type"], PACT_ATOM_TYPE);
+        assert_eq!(atom["op"], "amend");
+        assert_eq!(PactEvent::from_atom(&atom).unwrap(), event);
+        assert_eq!(
+            event.atom_hash().unwrap(),
+            ubl_atom::hash_value(&atom).unwrap()
+        );
+
+        let link = LinkCommit {
+            version: 2,
+            container_id: PACTS_CONTAINER.to_string(),
+            expected_sequence: 1,
+            previous_hash: "0x00".to_string(),
+            atom_hash: event.atom_hash().unwrap(),
+            intent_class: IntentClass::Evolution,
+            physics_delta: 0,
+            constraints: Vec::new(),
+            pact: None,
+            author_pubkey: alice.0.clone(),
+            signature: String::new(),
+        };
+        assert_eq!(PactEvent::from_link(&link, &atom).unwrap(), event);
+
+        let revoke = PactEvent::Revoke {
+            pact_id: "treasury".into(),
+        }
+        .to_atom();
+        assert!(PactEvent::from_link(&link, &revoke).is_err());
+        let entropy = LinkCommit {
+            intent_class: IntentClass::Entropy,
+            ..link
+        };
+        assert!(PactEvent::from_link(&entropy, &atom).is_err());
+    }
+}
@@ -0,0 +1,273 @@
+//! Quorum expressions (SPEC-UBL-PACT v1.0 §4.2)
+//!
+//! A flat `threshold` over `signers` cannot say "the CFO counts twice",
+//! "the guardian must sign" or "2 of legal AND 1 of finance". A [`Quorum`]
+//! is a tree of clauses evaluated over the set of signers whose signatures
+//! verified:
+//!
+//! ```json
+//! { "kind": "at_least", "threshold": 3, "clauses": [
+//!     { "kind": "signer", "pubkey": "…", "label": "guardian" },
+//!     { "kind": "weighted", "threshold": 2, "label": "legal",
+//!       "weights": { "…": 1, "…": 1, "…": 1 } },
+//!     { "kind": "weighted", "threshold": 2, "label": "finance",
+//!       "weights": { "<cfo>": 2, "…": 1 } } ] }
+//! ```
+//!
+//! Evaluation reports every unmet clause by path (`/1` is the second clause
+//! of the root) and label, so a caller can tell whose signature is missing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A quorum clause
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Quorum {
+    /// This signer must sign
+    Signer {
+        /// Public key (hex)
+        pubkey: String,
+        /// Name used when reporting the clause
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    /// The weights of the signers who signed add up to `threshold`
+    Weighted {
+        /// Weight required
+        threshold: u64,
+        /// Weight of each signer (public key hex)
+        weights: BTreeMap<String, u64>,
+        /// Name used when reporting the clause
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+    /// At least `threshold` of the sub-clauses hold (all of them: AND; one: OR)
+    AtLeast {
+        /// Sub-clauses required
+        threshold: usize,
+        /// Sub-clauses
+        clauses: Vec<Quorum>,
+        /// Name used when reporting the clause
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
+}
+
+/// A clause the signatures did not satisfy
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnmetClause {
+    /// Position in the tree: `""` for the root, `/1/0` for nested clauses
+    pub path: String,
+    /// Label of the clause, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Weight, signatures or sub-clauses present
+    pub got: u64,
+    /// Amount required
+    pub need: u64,
+}
+
+impl fmt::Display for UnmetClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "/"
+        } else {
+            &self.path
+        };
+        match &self.label {
+            Some(label) => write!(f, "{} ({}): {} of {}", path, label, self.got, self.need),
+            None => write!(f, "{}: {} of {}", path, self.got, self.need),
+        }
+    }
+}
+
+impl Quorum {
+    /// `pubkey` must sign
+    pub fn signer(pubkey: impl Into<String>) -> Self {
+        Quorum::Signer {
+            pubkey: pubkey.into(),
+            label: None,
+        }
+    }
+
+    /// `n` of `signers`, each counting once
+    pub fn n_of(n: u64, signers: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Quorum::Weighted {
+            threshold: n,
+            weights: signers.into_iter().map(|pk| (pk.into(), 1)).collect(),
+            label: None,
+        }
+    }
+
+    /// Every clause must hold
+    pub fn all(clauses: Vec<Quorum>) -> Self {
+        Quorum::AtLeast {
+            threshold: clauses.len(),
+            clauses,
+            label: None,
+        }
+    }
+
+    /// One clause must hold
+    pub fn any(clauses: Vec<Quorum>) -> Self {
+        Quorum::AtLeast {
+            threshold: 1,
+            clauses,
+            label: None,
+        }
+    }
+
+    /// Name the clause in reports
+    pub fn labeled(mut self, name: impl Into<String>) -> Self {
+        match &mut self {
+            Quorum::Signer { label, .. }
+            | Quorum::Weighted { label, .. }
+            | Quorum::AtLeast { label, .. } => *label = Some(name.into()),
+        }
+        self
+    }
+
+    /// Every public key the expression mentions
+    pub fn signers(&self) -> BTreeSet<&str> {
+        let mut out = BTreeSet::new();
+        self.collect_signers(&mut out);
+        out
+    }
+
+    /// Evaluate over the signers whose signatures verified
+    ///
+    /// `Err` lists every unmet clause, parents before their children.
+    pub fn evaluate(&self, signed: &BTreeSet<&str>) -> Result<(), Vec<UnmetClause>> {
+        let mut unmet = Vec::new();
+        if self.check("", signed, &mut unmet) {
+            Ok(())
+        } else {
+            Err(unmet)
+        }
+    }
+
+    fn check(&self, path: &str, signed: &BTreeSet<&str>, unmet: &mut Vec<UnmetClause>) -> bool {
+        let (got, need, label, children) = match self {
+            Quorum::Signer { pubkey, label } => (
+                u64::from(signed.contains(pubkey.as_str())),
+                1,
+                label,
+                Vec::new(),
+            ),
+            Quorum::Weighted {
+                threshold,
+                weights,
+                label,
+            } => {
+                let got = weights
+                    .iter()
+                    .filter(|(pk, _)| signed.contains(pk.as_str()))
+                    .fold(0u64, |sum, (_, weight)| sum.saturating_add(*weight));
+                (got, *threshold, label, Vec::new())
+            }
+            Quorum::AtLeast {
+                threshold,
+                clauses,
+                label,
+            } => {
+                // Children are reported after their parent, so check them aside
+                let mut children = Vec::new();
+                let got = clauses
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, clause)| {
+                        clause.check(&format!("{}/{}", path, i), signed, &mut children)
+                    })
+                    .count();
+                (got as u64, *threshold as u64, label, children)
+            }
+        };
+
+        let met = got >= need;
+        if !met {
+            unmet.push(UnmetClause {
+                path: path.to_string(),
+                label: label.clone(),
+                got,
+                need,
+            });
+            unmet.extend(children);
+        }
+        met
+    }
+
+    fn collect_signers<'a>(&'a self, out: &mut BTreeSet<&'a str>) {
+        match self {
+            Quorum::Signer { pubkey, .. } => {
+                out.insert(pubkey);
+            }
+            Quorum::Weighted { weights, .. } => out.extend(weights.keys().map(String::as_str)),
+            Quorum::AtLeast { clauses, .. } => {
+                for clause in clauses {
+                    clause.collect_signers(out);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governance() -> Quorum {
+        Quorum::all(vec![
+            Quorum::signer("guardian").labeled("guardian"),
+            Quorum::n_of(2, ["l1", "l2", "l3"]).labeled("legal"),
+            Quorum::Weighted {
+                threshold: 2,
+                weights: [("cfo".to_string(), 2), ("f1".to_string(), 1)].into(),
+                label: Some("finance".to_string()),
+            },
+        ])
+    }
+
+    fn signed<'a>(keys: &[&'a str]) -> BTreeSet<&'a str> {
+        keys.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_weighted_mandatory_and_nested() {
+        let quorum = governance();
+        quorum
+            .evaluate(&signed(&["guardian", "l1", "l3", "cfo"]))
+            .unwrap();
+
+        let unmet = quorum.evaluate(&signed(&["l1", "l2", "f1"])).unwrap_err();
+        let report: Vec<String> = unmet.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            report,
+            ["/: 1 of 3", "/0 (guardian): 0 of 1", "/2 (finance): 1 of 2"]
+        );
+    }
+
+    #[test]
+    fn test_any_and_serde() {
+        let quorum = Quorum::any(vec![
+            Quorum::signer("root"),
+            Quorum::all(vec![Quorum::signer("a"), Quorum::signer("b")]),
+        ]);
+        assert!(quorum.evaluate(&signed(&["a", "b"])).is_ok());
+        let unmet = quorum.evaluate(&signed(&["a"])).unwrap_err();
+        assert_eq!(
+            unmet.iter().map(|c| c.path.as_str()).collect::<Vec<_>>(),
+            ["", "/0", "/1", "/1/1"]
+        );
+
+        let json = serde_json::to_value(&quorum).unwrap();
+        assert_eq!(json["kind"], "at_least");
+        assert_eq!(serde_json::from_value::<Quorum>(json).unwrap(), quorum);
+        assert_eq!(
+            quorum.signers().into_iter().collect::<Vec<_>>(),
+            ["a", "b", "root"]
+        );
+    }
+}
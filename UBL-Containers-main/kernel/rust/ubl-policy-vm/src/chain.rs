@@ -0,0 +1,374 @@
+//! Policy chains (SPEC-UBL-POLICY v1.0 §7)
+//!
+//! A translation may be governed by several policies, listed from most
+//! specific to global (container → namespace → global). Each is evaluated
+//! on its own and the results combine as:
+//!
+//! - **deny-overrides**: any policy that denies, or cannot be run, denies
+//!   the chain; the most specific denial is reported;
+//! - a policy where no rule matched abstains;
+//! - the allows must agree on the intent class, or the chain denies; the
+//!   strictest ([`TrustLevel`]) one requiring a pact sets the pact;
+//! - constraints are conjunctive: all of them are kept, most specific first.
+
+use serde::{Deserialize, Serialize};
+
+use crate::exec;
+use crate::{Constraint, EvaluationContext, PolicyVM, TranslationDecision};
+
+/// Trust level (L0-L5) a translation demands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TrustLevel {
+    /// L0 - Observation only
+    L0 = 0,
+    /// L1 - Low impact
+    L1 = 1,
+    /// L2 - Local impact
+    L2 = 2,
+    /// L3 - Financial impact
+    L3 = 3,
+    /// L4 - Systemic impact
+    L4 = 4,
+    /// L5 - Sovereignty/Evolution
+    L5 = 5,
+}
+
+impl TrustLevel {
+    /// Level `name` (`"L0"`…`"L5"`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "L0" => TrustLevel::L0,
+            "L1" => TrustLevel::L1,
+            "L2" => TrustLevel::L2,
+            "L3" => TrustLevel::L3,
+            "L4" => TrustLevel::L4,
+            "L5" => TrustLevel::L5,
+            _ => return None,
+        })
+    }
+
+    /// Whether translations at this level need a pact
+    pub fn requires_pact(&self) -> bool {
+        *self >= TrustLevel::L3
+    }
+
+    /// Level an allow decision demands: its intent class's level, raised to
+    /// L3 by a required pact and to any `risk_level` constraint
+    pub fn of(decision: &TranslationDecision) -> Self {
+        let TranslationDecision::Allow {
+            intent_class,
+            required_pact,
+            constraints,
+        } = decision
+        else {
+            return TrustLevel::L0;
+        };
+        let mut level = match intent_class {
+            0x01 => TrustLevel::L2,
+            0x02 => TrustLevel::L4,
+            0x03 => TrustLevel::L5,
+            _ => TrustLevel::L0,
+        };
+        if required_pact.is_some() {
+            level = level.max(TrustLevel::L3);
+        }
+        constraints
+            .iter()
+            .filter(|c| c.kind == "risk_level")
+            .filter_map(|c| TrustLevel::from_name(&c.value))
+            .fold(level, Ord::max)
+    }
+}
+
+/// Policies that governed a translation, most specific first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyChain {
+    /// Each policy and what it decided
+    pub policies: Vec<PolicyRef>,
+    /// Level of the combined decision (L0 when denied)
+    pub effective_level: TrustLevel,
+    /// Every policy was registered and pinned, at least one applied and
+    /// none denied
+    pub is_valid: bool,
+}
+
+impl PolicyChain {
+    /// Whether the chain holds and covers `required`
+    pub fn allows(&self, required: TrustLevel) -> bool {
+        self.is_valid && self.effective_level >= required
+    }
+}
+
+/// One policy in a chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyRef {
+    /// Policy id
+    pub id: String,
+    /// Version evaluated (0 if not registered)
+    pub version: u64,
+    /// Hash of the code that ran (empty if not registered)
+    pub bytecode_hash: String,
+    /// Level its decision demands
+    pub level: TrustLevel,
+    /// Registered, and its code matches `bytecode_hash`
+    pub is_pinned: bool,
+    /// What it decided on its own; `None` if no rule matched (it abstained)
+    pub decision: Option<TranslationDecision>,
+}
+
+/// Combined decision of a chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChainDecision {
+    /// Decision to act on
+    pub decision: TranslationDecision,
+    /// How each policy contributed
+    pub chain: PolicyChain,
+}
+
+impl PolicyVM {
+    /// Evaluate `policy_ids`, most specific first, and combine them
+    ///
+    /// Fails closed: a missing, tampered or failing policy denies.
+    pub fn evaluate_chain(
+        &self,
+        policy_ids: &[&str],
+        context: &EvaluationContext,
+    ) -> ChainDecision {
+        let policies: Vec<PolicyRef> = policy_ids
+            .iter()
+            .map(|&id| {
+                let latest = self.history(id).last();
+                let decision = self
+                    .pinned(id, None)
+                    .and_then(|policy| self.decide(policy, context))
+                    .unwrap_or_else(|e| {
+                        Some(TranslationDecision::Deny {
+                            reason: e.to_string(),
+                        })
+                    });
+                PolicyRef {
+                    id: id.to_string(),
+                    version: latest.map_or(0, |p| p.version),
+                    bytecode_hash: latest.map(|p| p.bytecode_hash.clone()).unwrap_or_default(),
+                    level: decision.as_ref().map_or(TrustLevel::L0, TrustLevel::of),
+                    is_pinned: self.pinned(id, None).is_ok(),
+                    decision,
+                }
+            })
+            .collect();
+
+        let decision = combine(&policies);
+        let denied = matches!(decision, TranslationDecision::Deny { .. });
+        ChainDecision {
+            chain: PolicyChain {
+                effective_level: TrustLevel::of(&decision),
+                is_valid: !denied && policies.iter().all(|p| p.is_pinned),
+                policies,
+            },
+            decision,
+        }
+    }
+}
+
+fn combine(policies: &[PolicyRef]) -> TranslationDecision {
+    let denial = policies.iter().find_map(|p| match &p.decision {
+        Some(TranslationDecision::Deny { reason }) => Some((p, reason)),
+        _ => None,
+    });
+    if let Some((policy, reason)) = denial {
+        return TranslationDecision::Deny {
+            reason: format!("{}: {reason}", policy.id),
+        };
+    }
+
+    let allows: Vec<(&PolicyRef, u8)> = policies
+        .iter()
+        .filter_map(|p| Some((p, intent_class_of(p)?)))
+        .collect();
+    let Some(&(first, intent_class)) = allows.first() else {
+        return exec::no_match();
+    };
+    if let Some((other, class)) = allows.iter().find(|(_, class)| *class != intent_class) {
+        return TranslationDecision::Deny {
+            reason: format!(
+                "{} allows intent class {:#04x} but {} allows {:#04x}",
+                first.id, intent_class, other.id, class
+            ),
+        };
+    }
+
+    let pacts = allows
+        .iter()
+        .map(|(p, _)| *p)
+        .filter(|p| pact_of(p).is_some());
+    let required_pact = strictest(pacts).and_then(pact_of).map(str::to_string);
+    let mut constraints: Vec<Constraint> = Vec::new();
+    for (p, _) in &allows {
+        if let Some(TranslationDecision::Allow {
+            constraints: own, ..
+        }) = &p.decision
+        {
+            for c in own {
+                if !constraints.contains(c) {
+                    constraints.push(c.clone());
+                }
+            }
+        }
+    }
+    TranslationDecision::Allow {
+        intent_class,
+        required_pact,
+        constraints,
+    }
+}
+
+/// Highest level; ties go to the first, i.e. most specific, policy
+fn strictest<'a>(policies: impl Iterator<Item = &'a PolicyRef>) -> Option<&'a PolicyRef> {
+    policies.reduce(|best, p| if p.level > best.level { p } else { best })
+}
+
+fn pact_of(policy: &PolicyRef) -> Option<&str> {
+    match &policy.decision {
+        Some(TranslationDecision::Allow { required_pact, .. }) => required_pact.as_deref(),
+        _ => None,
+    }
+}
+
+fn intent_class_of(policy: &PolicyRef) -> Option<u8> {
+    match policy.decision {
+        Some(TranslationDecision::Allow { intent_class, .. }) => Some(intent_class),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Policy;
+    use serde_json::json;
+
+    fn vm() -> PolicyVM {
+        let mut vm = PolicyVM::new();
+        for (id, source) in [
+            (
+                "container",
+                r#"rule pay when intent.type == "transfer" allow Conservation
+                     constrain max_delta = 500"#,
+            ),
+            (
+                "namespace",
+                r#"rule big when intent.amount != null and intent.amount > 100 allow Conservation
+                     require pact "treasury" constrain max_delta = 1000
+                   rule frozen when state.frozen deny "namespace frozen""#,
+            ),
+            (
+                "global",
+                r#"rule mint when intent.type == "mint" allow Entropy require pact "mint"
+                   rule any allow Conservation"#,
+            ),
+            ("strict", r#"rule nope deny "closed""#),
+            ("observer", r#"rule look allow Observation"#),
+        ] {
+            vm.register(Policy::from_source(id, 1, id, source).unwrap())
+                .unwrap();
+        }
+        vm
+    }
+
+    fn context(intent: serde_json::Value, frozen: bool) -> EvaluationContext {
+        EvaluationContext {
+            container_id: "wallet/alice".to_string(),
+            actor: "alice".to_string(),
+            intent,
+            state: Some(json!({ "frozen": frozen })),
+            timestamp: 1000,
+        }
+    }
+
+    #[test]
+    fn test_chain_merges_constraints_and_strictest_pact() {
+        let vm = vm();
+        let chain = ["container", "namespace", "global"];
+        let big = context(json!({"type": "transfer", "amount": 150}), false);
+
+        let result = vm.evaluate_chain(&chain, &big);
+        assert_eq!(
+            result.decision,
+            TranslationDecision::Allow {
+                intent_class: 0x01,
+                required_pact: Some("treasury".to_string()),
+                constraints: vec![
+                    Constraint {
+                        kind: "max_delta".to_string(),
+                        value: "500".to_string()
+                    },
+                    Constraint {
+                        kind: "max_delta".to_string(),
+                        value: "1000".to_string()
+                    },
+                ],
+            }
+        );
+        assert!(result.chain.is_valid);
+        assert_eq!(result.chain.effective_level, TrustLevel::L3);
+        assert!(result.chain.allows(TrustLevel::L3) && !result.chain.allows(TrustLevel::L4));
+        let levels: Vec<_> = result.chain.policies.iter().map(|p| p.level).collect();
+        assert_eq!(levels, [TrustLevel::L2, TrustLevel::L3, TrustLevel::L2]);
+
+        // The namespace abstains; the global mint rule is strictest
+        let mint = vm.evaluate_chain(&chain, &context(json!({"type": "mint"}), false));
+        assert!(matches!(
+            mint.decision,
+            TranslationDecision::Allow { intent_class: 0x02, ref required_pact, .. }
+                if required_pact.as_deref() == Some("mint")
+        ));
+        assert_eq!(mint.chain.effective_level, TrustLevel::L4);
+        assert!(mint.chain.is_valid);
+        assert_eq!(mint.chain.policies[1].decision, None);
+    }
+
+    #[test]
+    fn test_chain_denies_and_fails_closed() {
+        let vm = vm();
+        let frozen = context(json!({"type": "transfer", "amount": 5}), true);
+        let result = vm.evaluate_chain(&["container", "namespace", "global"], &frozen);
+        assert_eq!(
+            result.decision,
+            TranslationDecision::Deny {
+                reason: "namespace: namespace frozen".to_string()
+            }
+        );
+        assert!(!result.chain.is_valid);
+        assert_eq!(result.chain.effective_level, TrustLevel::L0);
+
+        let ok = context(json!({"type": "transfer"}), false);
+        let missing = vm.evaluate_chain(&["container", "ghost"], &ok);
+        assert!(matches!(missing.decision, TranslationDecision::Deny { .. }));
+        assert!(!missing.chain.policies[1].is_pinned && !missing.chain.is_valid);
+
+        assert!(
+            !vm.evaluate_chain(&["container", "strict"], &ok)
+                .chain
+                .is_valid
+        );
+        // Allows that disagree on the intent class deny
+        let split = vm.evaluate_chain(&["container", "observer"], &ok);
+        assert_eq!(
+            split.decision,
+            TranslationDecision::Deny {
+                reason: "container allows intent class 0x01 but observer allows 0x00".to_string()
+            }
+        );
+        assert!(!split.chain.is_valid);
+
+        let empty = vm.evaluate_chain(&[], &ok);
+        assert_eq!(
+            empty.decision,
+            TranslationDecision::Deny {
+                reason: exec::NO_MATCH.to_string()
+            }
+        );
+        assert!(!empty.chain.is_valid);
+    }
+}
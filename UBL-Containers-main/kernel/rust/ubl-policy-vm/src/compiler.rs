@@ -0,0 +1,267 @@
+//! TDLN bytecode (SPEC-UBL-POLICY v1.0 §9)
+//!
+//! The compiled form is a flat, deterministic byte string: the same source
+//! always produces the same bytes, whitespace and comments included or not,
+//! so `bytecode_hash = BLAKE3(bytecode)` identifies the policy.
+//!
+//! ```text
+//! "TDLN" version
+//! RULE id  <condition>  JUMP_IF_FALSE next_rule  ALLOW … | DENY …
+//! RULE id  …
+//! ```
+//!
+//! Conditions are stack code. Strings are `u32 BE length || UTF-8`, integers
+//! are `i64 BE`, jump targets are absolute `u32 BE` offsets and only ever
+//! point forward, so every program terminates.
+
+use crate::parser::{self, CmpOp, Expr, Literal, Outcome, Root};
+use crate::Result;
+
+/// Magic prefix of TDLN bytecode
+pub const MAGIC: &[u8; 4] = b"TDLN";
+
+/// Bytecode format version
+pub const VERSION: u8 = 1;
+
+/// Opcodes
+pub mod op {
+    /// `root:u8 n:u8 field:str × n` — push the context value at the path
+    pub const LOAD: u8 = 0x01;
+    /// `str` — push a string
+    pub const PUSH_STR: u8 = 0x02;
+    /// `i64` — push an integer
+    pub const PUSH_INT: u8 = 0x03;
+    /// `u8` — push a boolean
+    pub const PUSH_BOOL: u8 = 0x04;
+    /// push null
+    pub const PUSH_NULL: u8 = 0x05;
+    /// pop b, a; push a == b
+    pub const EQ: u8 = 0x10;
+    /// pop b, a; push a != b
+    pub const NE: u8 = 0x11;
+    /// pop b, a; push a < b
+    pub const LT: u8 = 0x12;
+    /// pop b, a; push a <= b
+    pub const LE: u8 = 0x13;
+    /// pop b, a; push a > b
+    pub const GT: u8 = 0x14;
+    /// pop b, a; push a >= b
+    pub const GE: u8 = 0x15;
+    /// pop b, a; push whether string a starts with string b
+    pub const STARTS_WITH: u8 = 0x16;
+    /// `n:u16` — pop n items, then x; push whether x equals one of them
+    pub const IN: u8 = 0x17;
+    /// pop a; push !a
+    pub const NOT: u8 = 0x20;
+    /// pop b, a; push a && b
+    pub const AND: u8 = 0x21;
+    /// pop b, a; push a || b
+    pub const OR: u8 = 0x22;
+    /// `str` — start of a rule (its id)
+    pub const RULE: u8 = 0x30;
+    /// `target:u32` — pop a; jump forward to `target` unless a is true
+    pub const JUMP_IF_FALSE: u8 = 0x31;
+    /// `class:u8 has_pact:u8 [pact:str] n:u16 (kind:str value:str) × n` — allow and halt
+    pub const ALLOW: u8 = 0x40;
+    /// `reason:str` — deny and halt
+    pub const DENY: u8 = 0x41;
+}
+
+/// Parse and compile TDLN source to bytecode
+pub fn compile(source: &str) -> Result<Vec<u8>> {
+    compile_program(&parser::parse(source)?)
+}
+
+/// Compile a parsed program to bytecode
+///
+/// Conditions deeper than [`parser::MAX_DEPTH`] (plus the rule's scope) are
+/// a syntax error, as when parsing.
+pub fn compile_program(program: &parser::Program) -> Result<Vec<u8>> {
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+
+    for rule in &program.rules {
+        out.push(op::RULE);
+        put_str(&mut out, &rule.rule_id);
+
+        let scope = rule.applies_to.as_ref().map(|prefix| {
+            Expr::Compare(
+                CmpOp::StartsWith,
+                Box::new(Expr::Path(Root::Container, Vec::new())),
+                Box::new(Expr::Literal(Literal::Str(prefix.clone()))),
+            )
+        });
+        let condition = match (scope, rule.when.clone()) {
+            (Some(scope), Some(when)) => Some(Expr::And(Box::new(scope), Box::new(when))),
+            (scope, when) => scope.or(when),
+        };
+        let jump = match condition {
+            Some(condition) => {
+                expr(&mut out, &condition, 1)?;
+                out.push(op::JUMP_IF_FALSE);
+                out.extend_from_slice(&[0; 4]);
+                Some(out.len() - 4)
+            }
+            None => None,
+        };
+
+        match &rule.outcome {
+            Outcome::Allow {
+                intent_class,
+                required_pact,
+                constraints,
+            } => {
+                out.push(op::ALLOW);
+                out.push(*intent_class);
+                match required_pact {
+                    Some(pact) => {
+                        out.push(1);
+                        put_str(&mut out, pact);
+                    }
+                    None => out.push(0),
+                }
+                out.extend_from_slice(&(constraints.len() as u16).to_be_bytes());
+                for (kind, value) in constraints {
+                    put_str(&mut out, kind);
+                    put_str(&mut out, value);
+                }
+            }
+            Outcome::Deny { reason } => {
+                out.push(op::DENY);
+                put_str(&mut out, reason);
+            }
+        }
+
+        if let Some(at) = jump {
+            let target = (out.len() as u32).to_be_bytes();
+            out[at..at + 4].copy_from_slice(&target);
+        }
+    }
+    Ok(out)
+}
+
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_be_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn literal(out: &mut Vec<u8>, value: &Literal) {
+    match value {
+        Literal::Str(s) => {
+            out.push(op::PUSH_STR);
+            put_str(out, s);
+        }
+        Literal::Int(i) => {
+            out.push(op::PUSH_INT);
+            out.extend_from_slice(&i.to_be_bytes());
+        }
+        Literal::Bool(b) => out.extend_from_slice(&[op::PUSH_BOOL, u8::from(*b)]),
+        Literal::Null => out.push(op::PUSH_NULL),
+    }
+}
+
+/// Emit `e`, found `depth` nodes down from its rule's condition root
+fn expr(out: &mut Vec<u8>, e: &Expr, depth: usize) -> Result<()> {
+    // One level for the scope check a `for` clause adds
+    if depth > parser::MAX_DEPTH + 1 {
+        return Err(parser::syntax(1, 1, "expression nested too deeply"));
+    }
+    match e {
+        Expr::Path(root, fields) => {
+            out.extend_from_slice(&[op::LOAD, *root as u8, fields.len() as u8]);
+            for field in fields {
+                put_str(out, field);
+            }
+        }
+        Expr::Literal(value) => literal(out, value),
+        Expr::Not(inner) => {
+            expr(out, inner, depth + 1)?;
+            out.push(op::NOT);
+        }
+        Expr::And(a, b) | Expr::Or(a, b) => {
+            expr(out, a, depth + 1)?;
+            expr(out, b, depth + 1)?;
+            out.push(if matches!(e, Expr::And(..)) {
+                op::AND
+            } else {
+                op::OR
+            });
+        }
+        Expr::Compare(cmp, a, b) => {
+            expr(out, a, depth + 1)?;
+            expr(out, b, depth + 1)?;
+            out.push(match cmp {
+                CmpOp::Eq => op::EQ,
+                CmpOp::Ne => op::NE,
+                CmpOp::Lt => op::LT,
+                CmpOp::Le => op::LE,
+                CmpOp::Gt => op::GT,
+                CmpOp::Ge => op::GE,
+                CmpOp::StartsWith => op::STARTS_WITH,
+            });
+        }
+        Expr::In(x, items) => {
+            expr(out, x, depth + 1)?;
+            for item in items {
+                literal(out, item);
+            }
+            out.push(op::IN);
+            out.extend_from_slice(&(items.len() as u16).to_be_bytes());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytecode_is_canonical() {
+        let compact = compile(r#"rule a when intent.n > 1 allow Observation"#).unwrap();
+        let spaced =
+            compile("# doc\nrule a\n  when intent.n > 1 # big\n  allow Observation\n").unwrap();
+        assert_eq!(compact, spaced);
+        assert_ne!(
+            compact,
+            compile(r#"rule a when intent.n > 2 allow Observation"#).unwrap()
+        );
+
+        #[rustfmt::skip]
+        let expected = [
+            b'T', b'D', b'L', b'N', VERSION,
+            op::RULE, 0, 0, 0, 1, b'a',
+            op::LOAD, Root::Intent as u8, 1, 0, 0, 0, 1, b'n',
+            op::PUSH_INT, 0, 0, 0, 0, 0, 0, 0, 1,
+            op::GT,
+            op::JUMP_IF_FALSE, 0, 0, 0, 39,
+            op::ALLOW, 0x00, 0, 0, 0,
+        ];
+        assert_eq!(compact, expected);
+    }
+
+    #[test]
+    fn test_rejects_deep_conditions() {
+        let nested = |depth: usize| {
+            let mut when = Expr::Literal(Literal::Bool(true));
+            for _ in 1..depth {
+                when = Expr::Not(Box::new(when));
+            }
+            parser::Program {
+                rules: vec![parser::Rule {
+                    rule_id: "a".to_string(),
+                    applies_to: Some("wallet/".to_string()),
+                    when: Some(when),
+                    outcome: Outcome::Deny {
+                        reason: "x".to_string(),
+                    },
+                }],
+            }
+        };
+        assert!(compile_program(&nested(parser::MAX_DEPTH)).is_ok());
+        assert!(matches!(
+            compile_program(&nested(parser::MAX_DEPTH + 1)),
+            Err(crate::PolicyError::Syntax { .. })
+        ));
+    }
+}
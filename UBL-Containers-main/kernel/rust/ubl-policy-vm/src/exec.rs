@@ -0,0 +1,510 @@
+//! TDLN bytecode interpreter (SPEC-UBL-POLICY v1.0 §6, §11)
+//!
+//! Executes [`compiler`](crate::compiler) output against an
+//! [`EvaluationContext`] and nothing else: no ledger, no clock, no I/O.
+//! Values are JSON; a missing field reads as `null`. Integers compare
+//! numerically and strings lexicographically. Ordering anything else (a
+//! string against an integer, a missing field) is undefined: `and`/`or`
+//! still decide when their other side settles them, but a rule condition
+//! left undefined fails the evaluation, which denies. Conditions hold only
+//! when they evaluate to exactly `true`.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::compiler::{op, MAGIC, VERSION};
+use crate::parser::Root;
+use crate::trace::{Operand, PredicateTrace, RuleTrace};
+use crate::{Constraint, EvaluationContext, PolicyError, Result, TranslationDecision};
+
+/// Instructions a single evaluation may execute
+pub const MAX_STEPS: usize = 100_000;
+
+/// Values the stack may hold at once
+pub const MAX_STACK: usize = 1024;
+
+/// Reason given when no rule matches
+pub const NO_MATCH: &str = "no rule matched";
+
+/// Run `bytecode` against `context`; no matching rule denies with [`NO_MATCH`]
+pub fn run(bytecode: &[u8], context: &EvaluationContext) -> Result<TranslationDecision> {
+    Ok(decide(bytecode, context)?.unwrap_or_else(no_match))
+}
+
+/// Run `bytecode` against `context`: `None` if no rule matched
+pub fn decide(bytecode: &[u8], context: &EvaluationContext) -> Result<Option<TranslationDecision>> {
+    execute(bytecode, context, None)
+}
+
+/// Run `bytecode` against `context`, recording each rule tested
+pub fn run_traced(
+    bytecode: &[u8],
+    context: &EvaluationContext,
+) -> Result<(TranslationDecision, Vec<RuleTrace>)> {
+    let mut rules = Vec::new();
+    let decision = execute(bytecode, context, Some(&mut rules))?;
+    Ok((decision.unwrap_or_else(no_match), rules))
+}
+
+/// The denial for a policy where no rule matched
+pub fn no_match() -> TranslationDecision {
+    TranslationDecision::Deny {
+        reason: NO_MATCH.to_string(),
+    }
+}
+
+fn execute(
+    bytecode: &[u8],
+    context: &EvaluationContext,
+    mut trace: Option<&mut Vec<RuleTrace>>,
+) -> Result<Option<TranslationDecision>> {
+    let mut code = Reader {
+        bytes: bytecode,
+        pc: 0,
+    };
+    if code.take(MAGIC.len())? != MAGIC || code.u8()? != VERSION {
+        return Err(PolicyError::InvalidBytecode);
+    }
+
+    let mut stack: Vec<Cell> = Vec::new();
+    for _ in 0..MAX_STEPS {
+        if code.pc == bytecode.len() {
+            return Ok(None);
+        }
+        if stack.len() > MAX_STACK {
+            return Err(PolicyError::ExecutionFailed("stack overflow".to_string()));
+        }
+
+        match code.u8()? {
+            op::LOAD => {
+                let root = Root::from_byte(code.u8()?).ok_or(PolicyError::InvalidBytecode)?;
+                let mut value = match root {
+                    Root::Actor => Value::from(context.actor.as_str()),
+                    Root::Container => Value::from(context.container_id.as_str()),
+                    Root::Timestamp => Value::from(context.timestamp),
+                    Root::Intent => context.intent.clone(),
+                    Root::State => context.state.clone().unwrap_or(Value::Null),
+                };
+                let mut path = trace.is_some().then(|| root.name().to_string());
+                for _ in 0..code.u8()? {
+                    let field = code.str()?;
+                    value = value.get(field).cloned().unwrap_or(Value::Null);
+                    if let Some(path) = &mut path {
+                        path.push('.');
+                        path.push_str(field);
+                    }
+                }
+                stack.push(Cell::Known(Operand { path, value }));
+            }
+            op::PUSH_STR => stack.push(literal(Value::from(code.str()?))),
+            op::PUSH_INT => stack.push(literal(Value::from(i64::from_be_bytes(code.array()?)))),
+            op::PUSH_BOOL => stack.push(literal(Value::Bool(code.u8()? != 0))),
+            op::PUSH_NULL => stack.push(literal(Value::Null)),
+            opcode @ (op::EQ | op::NE | op::LT | op::LE | op::GT | op::GE | op::STARTS_WITH) => {
+                let b = known(pop(&mut stack)?);
+                let a = known(pop(&mut stack)?);
+                let (a, b) = match (a, b) {
+                    (Ok(a), Ok(b)) => (a, b),
+                    (Err(why), _) | (_, Err(why)) => {
+                        stack.push(Cell::Undefined(why));
+                        continue;
+                    }
+                };
+                let outcome = match opcode {
+                    op::EQ => equal(&a.value, &b.value),
+                    op::NE => !equal(&a.value, &b.value),
+                    op::STARTS_WITH => match (a.value.as_str(), b.value.as_str()) {
+                        (Some(a), Some(b)) => a.starts_with(b),
+                        _ => false,
+                    },
+                    _ => match compare(&a.value, &b.value) {
+                        Ok(order) => match opcode {
+                            op::LT => order.is_lt(),
+                            op::LE => order.is_le(),
+                            op::GT => order.is_gt(),
+                            _ => order.is_ge(),
+                        },
+                        Err(why) => {
+                            stack.push(Cell::Undefined(why));
+                            continue;
+                        }
+                    },
+                };
+                record(&mut trace, opcode, a, b, outcome);
+                stack.push(literal(Value::Bool(outcome)));
+            }
+            op::IN => {
+                let n = u16::from_be_bytes(code.array()?) as usize;
+                if stack.len() < n + 1 {
+                    return Err(PolicyError::InvalidBytecode);
+                }
+                let items = stack
+                    .split_off(stack.len() - n)
+                    .into_iter()
+                    .map(|item| match item {
+                        Cell::Known(item) => Ok(item.value),
+                        Cell::Undefined(_) => Err(PolicyError::InvalidBytecode),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let x = match known(pop(&mut stack)?) {
+                    Ok(x) => x,
+                    Err(why) => {
+                        stack.push(Cell::Undefined(why));
+                        continue;
+                    }
+                };
+                let outcome = items.iter().any(|item| equal(&x.value, item));
+                let items = Operand {
+                    path: None,
+                    value: items.into(),
+                };
+                record(&mut trace, op::IN, x, items, outcome);
+                stack.push(literal(Value::Bool(outcome)));
+            }
+            op::NOT => {
+                let cell = match pop(&mut stack)? {
+                    Cell::Known(a) => literal(Value::Bool(!holds(&a))),
+                    undefined => undefined,
+                };
+                stack.push(cell);
+            }
+            opcode @ (op::AND | op::OR) => {
+                let b = pop(&mut stack)?;
+                let a = pop(&mut stack)?;
+                // `false and _` and `true or _` are settled whatever `_` is
+                let settles = opcode == op::OR;
+                let cell = match (a, b) {
+                    (Cell::Known(a), Cell::Known(b)) => literal(Value::Bool(if settles {
+                        holds(&a) || holds(&b)
+                    } else {
+                        holds(&a) && holds(&b)
+                    })),
+                    (Cell::Known(side), undefined) | (undefined, Cell::Known(side)) => {
+                        if holds(&side) == settles {
+                            literal(Value::Bool(settles))
+                        } else {
+                            undefined
+                        }
+                    }
+                    (undefined, Cell::Undefined(_)) => undefined,
+                };
+                stack.push(cell);
+            }
+            op::RULE => {
+                let rule_id = code.str()?;
+                if let Some(rules) = trace.as_deref_mut() {
+                    rules.push(RuleTrace {
+                        rule_id: rule_id.to_string(),
+                        predicates: Vec::new(),
+                        matched: false,
+                    });
+                }
+                stack.clear();
+            }
+            op::JUMP_IF_FALSE => {
+                let target = u32::from_be_bytes(code.array()?) as usize;
+                if target <= code.pc || target > bytecode.len() {
+                    return Err(PolicyError::InvalidBytecode);
+                }
+                match known(pop(&mut stack)?) {
+                    Ok(condition) if holds(&condition) => {}
+                    Ok(_) => code.pc = target,
+                    Err(why) => return Err(PolicyError::ExecutionFailed(why)),
+                }
+            }
+            op::ALLOW => {
+                decided(&mut trace);
+                let intent_class = code.u8()?;
+                let required_pact = match code.u8()? {
+                    0 => None,
+                    1 => Some(code.str()?.to_string()),
+                    _ => return Err(PolicyError::InvalidBytecode),
+                };
+                let n = u16::from_be_bytes(code.array()?);
+                let mut constraints = Vec::with_capacity(n as usize);
+                for _ in 0..n {
+                    constraints.push(Constraint {
+                        kind: code.str()?.to_string(),
+                        value: code.str()?.to_string(),
+                    });
+                }
+                return Ok(Some(TranslationDecision::Allow {
+                    intent_class,
+                    required_pact,
+                    constraints,
+                }));
+            }
+            op::DENY => {
+                decided(&mut trace);
+                return Ok(Some(TranslationDecision::Deny {
+                    reason: code.str()?.to_string(),
+                }));
+            }
+            _ => return Err(PolicyError::InvalidBytecode),
+        }
+    }
+    Err(PolicyError::Timeout)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pc: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pc.checked_add(n).ok_or(PolicyError::InvalidBytecode)?;
+        let bytes = self
+            .bytes
+            .get(self.pc..end)
+            .ok_or(PolicyError::InvalidBytecode)?;
+        self.pc = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn str(&mut self) -> Result<&'a str> {
+        let len = u32::from_be_bytes(self.array()?) as usize;
+        std::str::from_utf8(self.take(len)?).map_err(|_| PolicyError::InvalidBytecode)
+    }
+}
+
+/// A stack slot: a value, or a comparison that has none and why
+enum Cell {
+    Known(Operand),
+    Undefined(String),
+}
+
+fn pop(stack: &mut Vec<Cell>) -> Result<Cell> {
+    stack.pop().ok_or(PolicyError::InvalidBytecode)
+}
+
+fn known(cell: Cell) -> std::result::Result<Operand, String> {
+    match cell {
+        Cell::Known(operand) => Ok(operand),
+        Cell::Undefined(why) => Err(why),
+    }
+}
+
+fn literal(value: Value) -> Cell {
+    Cell::Known(Operand { path: None, value })
+}
+
+fn holds(operand: &Operand) -> bool {
+    operand.value == Value::Bool(true)
+}
+
+/// Mark the rule being traced as the deciding one
+fn decided(trace: &mut Option<&mut Vec<RuleTrace>>) {
+    if let Some(rule) = trace.as_deref_mut().and_then(|rules| rules.last_mut()) {
+        rule.matched = true;
+    }
+}
+
+/// Add a comparison to the rule being traced
+fn record(
+    trace: &mut Option<&mut Vec<RuleTrace>>,
+    opcode: u8,
+    left: Operand,
+    right: Operand,
+    outcome: bool,
+) {
+    let Some(rule) = trace.as_deref_mut().and_then(|rules| rules.last_mut()) else {
+        return;
+    };
+    let op = match opcode {
+        op::EQ => "==",
+        op::NE => "!=",
+        op::LT => "<",
+        op::LE => "<=",
+        op::GT => ">",
+        op::GE => ">=",
+        op::STARTS_WITH => "starts_with",
+        _ => "in",
+    };
+    rule.predicates.push(PredicateTrace {
+        op: op.to_string(),
+        left,
+        right,
+        outcome,
+    });
+}
+
+fn integer(value: &Value) -> Option<i128> {
+    value
+        .as_i64()
+        .map(i128::from)
+        .or_else(|| value.as_u64().map(i128::from))
+}
+
+fn equal(a: &Value, b: &Value) -> bool {
+    match (integer(a), integer(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Order two integers or two strings; anything else has no order
+fn compare(a: &Value, b: &Value) -> std::result::Result<Ordering, String> {
+    match (integer(a), integer(b), a, b) {
+        (Some(a), Some(b), _, _) => Ok(a.cmp(&b)),
+        (_, _, Value::String(a), Value::String(b)) => Ok(a.cmp(b)),
+        _ => Err(format!("cannot order {} against {}", kind(a), kind(b))),
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) if integer(value).is_some() => "an integer",
+        Value::Number(_) => "a non-integer number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "a list",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compiler::compile;
+    use serde_json::json;
+
+    fn context(intent: Value) -> EvaluationContext {
+        EvaluationContext {
+            container_id: "wallet/alice".to_string(),
+            actor: "alice".to_string(),
+            intent,
+            state: Some(json!({"frozen": false})),
+            timestamp: 1000,
+        }
+    }
+
+    #[test]
+    fn test_first_matching_rule_decides() {
+        let code = compile(
+            r#"
+            rule frozen when state.frozen deny "frozen"
+            rule other for "vault/" allow Entropy
+            rule small when intent.amount < 100 or intent.amount == null allow Conservation
+            rule big for "wallet/" when intent.amount >= 100 and actor != "mallory"
+              allow Conservation require pact "board" constrain max_delta = 100
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            run(&code, &context(json!({"amount": 100}))).unwrap(),
+            TranslationDecision::Allow {
+                intent_class: 0x01,
+                required_pact: Some("board".to_string()),
+                constraints: vec![Constraint {
+                    kind: "max_delta".to_string(),
+                    value: "100".to_string(),
+                }],
+            }
+        );
+        let small = run(&code, &context(json!({}))).unwrap();
+        assert!(matches!(
+            small,
+            TranslationDecision::Allow {
+                required_pact: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            run(&code, &context(json!({"amount": 5}))).unwrap(),
+            TranslationDecision::Allow { .. }
+        ));
+        // Ordering a string against an integer is an error, not a miss
+        assert_eq!(
+            run(&code, &context(json!({"amount": "lots"}))),
+            Err(PolicyError::ExecutionFailed(
+                "cannot order a string against an integer".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_undefined_orderings() {
+        let decide = |when: &str, intent: Value| {
+            let code = compile(&format!("rule a when {} allow Observation", when)).unwrap();
+            run(&code, &context(intent)).map(|d| matches!(d, TranslationDecision::Allow { .. }))
+        };
+        let failed = |r: Result<bool>| matches!(r, Err(PolicyError::ExecutionFailed(_)));
+
+        assert!(failed(decide("intent.amount > 5", json!({}))));
+        assert!(failed(decide("not (intent.amount > 5)", json!({}))));
+        assert!(failed(decide(
+            "intent.n > 1 or intent.amount > 5",
+            json!({"n": 0})
+        )));
+        assert!(failed(decide(
+            "intent.n > 1 and intent.amount > 5",
+            json!({"n": 2})
+        )));
+        // The other side settles it
+        assert_eq!(
+            decide("intent.amount != null and intent.amount > 5", json!({})),
+            Ok(false)
+        );
+        assert_eq!(
+            decide("intent.amount == null or intent.amount > 5", json!({})),
+            Ok(true)
+        );
+        assert_eq!(decide("intent.amount > 5 and false", json!({})), Ok(false));
+        assert_eq!(decide("intent.amount > 5", json!({"amount": 6})), Ok(true));
+    }
+
+    #[test]
+    fn test_trace_records_scope_and_inputs() {
+        let code = compile(
+            r#"
+            rule vault for "vault/" allow Entropy
+            rule cap when intent.amount <= 10 and actor in ["alice", "bob"] allow Conservation
+            "#,
+        )
+        .unwrap();
+
+        let (decision, rules) = run_traced(&code, &context(json!({"amount": 7}))).unwrap();
+        assert_eq!(
+            decision,
+            run(&code, &context(json!({"amount": 7}))).unwrap()
+        );
+        assert_eq!(rules.len(), 2);
+        assert!(!rules[0].matched && rules[1].matched);
+        assert_eq!(
+            rules[0].predicates[0].to_string(),
+            r#"container ("wallet/alice") starts_with "vault/" is false"#
+        );
+        let actor = &rules[1].predicates[1];
+        assert_eq!(actor.op, "in");
+        assert_eq!(actor.right.value, json!(["alice", "bob"]));
+        assert!(actor.outcome);
+    }
+
+    #[test]
+    fn test_malformed_bytecode_is_rejected() {
+        let ctx = context(json!({}));
+        let code = compile(r#"rule a when intent.x == 1 allow Observation"#).unwrap();
+        assert_eq!(
+            run(&code[..code.len() - 1], &ctx),
+            Err(PolicyError::InvalidBytecode)
+        );
+        assert_eq!(run(b"TDLX\x01", &ctx), Err(PolicyError::InvalidBytecode));
+        assert_eq!(run(&[], &ctx), Err(PolicyError::InvalidBytecode));
+        let mut backwards = code.clone();
+        let jump = backwards.len() - 9;
+        backwards[jump..jump + 4].copy_from_slice(&5u32.to_be_bytes());
+        assert_eq!(run(&backwards, &ctx), Err(PolicyError::InvalidBytecode));
+    }
+}
@@ -9,13 +9,35 @@
 //!
 //! TDLN - Deterministic Translation of Language to Notation
 //! Executor WASM determinístico (semantically blind)
+//!
+//! Policies are written in TDLN ([`parser`]), compiled to deterministic
+//! bytecode ([`compiler`]) identified by its BLAKE3 hash, and executed by a
+//! sandboxed interpreter ([`exec`]). A policy may instead be a WASM module,
+//! run fuel-metered with no imports ([`wasm`]). [`PolicyVM::evaluate_with_trace`]
+//! also reports how the decision was reached ([`trace`]), and
+//! [`PolicyVM::evaluate_chain`] combines container, namespace and global
+//! policies ([`chain`]). Policies are published as bundles to `C.Policy`
+//! and replayed into the VM, every version pinned by its hash ([`registry`]).
 
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod chain;
+pub mod compiler;
+pub mod exec;
+pub mod parser;
+pub mod registry;
+pub mod trace;
+pub mod wasm;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub use chain::{ChainDecision, PolicyChain, PolicyRef, TrustLevel};
+pub use compiler::compile;
+pub use registry::{PolicyBundle, PublishedPolicy, POLICY_CONTAINER};
+pub use trace::PolicyTrace;
+
 /// Errors from policy evaluation
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum PolicyError {
@@ -34,6 +56,45 @@ pub enum PolicyError {
     /// Timeout during execution
     #[error("Execution timeout")]
     Timeout,
+
+    /// TDLN source does not parse
+    #[error("Syntax error at {line}:{column}: {message}")]
+    Syntax {
+        /// 1-based line
+        line: usize,
+        /// 1-based column
+        column: usize,
+        /// What was expected
+        message: String,
+    },
+
+    /// `C.Policy` bundle cannot be decoded
+    #[error("Malformed policy bundle: {0}")]
+    MalformedBundle(String),
+
+    /// A policy governing the translation denied it
+    #[error("Policy denied: {0}")]
+    Denied(String),
+
+    /// A registered version cannot be replaced by different code
+    #[error("Policy {policy_id} v{version} is already registered with other bytecode")]
+    VersionConflict {
+        /// Policy being registered
+        policy_id: String,
+        /// Version already registered
+        version: u64,
+    },
+
+    /// Published version is not newer than the one registered
+    #[error("Policy {policy_id} v{version} is not newer than v{latest}")]
+    StaleVersion {
+        /// Policy being published
+        policy_id: String,
+        /// Version published
+        version: u64,
+        /// Latest version registered
+        latest: u64,
+    },
 }
 
 /// Result type for policy operations
@@ -73,14 +134,13 @@ pub struct Policy {
     /// Policy identifier
     pub policy_id: String,
     
-    /// Version
-    pub version: String,
+    /// Version; each publication of a policy id must be newer than the last
+    pub version: u64,
     
     /// Hash of the policy bytecode (BLAKE3)
     pub bytecode_hash: String,
     
-    /// Compiled WASM bytecode (in production)
-    /// For now, we use a simple rule-based system
+    /// Compiled TDLN bytecode ([`compiler`]) or a WASM module ([`wasm`])
     #[serde(skip)]
     pub bytecode: Vec<u8>,
     
@@ -88,6 +148,62 @@ pub struct Policy {
     pub description: String,
 }
 
+impl Policy {
+    /// Compile TDLN `source` into a policy
+    pub fn from_source(
+        policy_id: impl Into<String>,
+        version: u64,
+        description: impl Into<String>,
+        source: &str,
+    ) -> Result<Self> {
+        let bytecode = compile(source)?;
+        Ok(Self {
+            policy_id: policy_id.into(),
+            version,
+            bytecode_hash: bytecode_hash(&bytecode),
+            bytecode,
+            description: description.into(),
+        })
+    }
+
+    /// Wrap a WASM policy module
+    pub fn from_wasm(
+        policy_id: impl Into<String>,
+        version: u64,
+        description: impl Into<String>,
+        module: Vec<u8>,
+    ) -> Self {
+        Self {
+            policy_id: policy_id.into(),
+            version,
+            bytecode_hash: bytecode_hash(&module),
+            bytecode: module,
+            description: description.into(),
+        }
+    }
+}
+
+/// `bytecode_hash` of compiled bytecode: BLAKE3, hex
+pub fn bytecode_hash(bytecode: &[u8]) -> String {
+    blake3::hash(bytecode).to_hex().to_string()
+}
+
+/// A decision and the policy version that made it
+///
+/// Re-running [`PolicyVM::evaluate_version`] with the same version and context
+/// reproduces the decision, whatever has been published since.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Evaluation {
+    /// Policy evaluated
+    pub policy_id: String,
+    /// Version that decided
+    pub version: u64,
+    /// Hash of the code that ran
+    pub bytecode_hash: String,
+    /// Outcome
+    pub decision: TranslationDecision,
+}
+
 /// Policy evaluation context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EvaluationContext {
@@ -108,8 +224,14 @@ pub struct EvaluationContext {
 }
 
 /// Policy VM - executes TDLN policies
+#[derive(Clone)]
 pub struct PolicyVM {
-    policies: std::collections::HashMap<String, Policy>,
+    /// Every version of each policy, oldest first
+    policies: std::collections::HashMap<String, Vec<Policy>>,
+    /// Fuel for one WASM evaluation
+    fuel: u64,
+    /// Recently used WASM policies, compiled
+    modules: wasm::ModuleCache,
 }
 
 impl PolicyVM {
@@ -117,96 +239,131 @@ impl PolicyVM {
     pub fn new() -> Self {
         Self {
             policies: std::collections::HashMap::new(),
+            fuel: wasm::DEFAULT_FUEL,
+            modules: wasm::ModuleCache::new(),
+        }
+    }
+
+    /// Limit each WASM evaluation to `fuel` (exhaustion is [`PolicyError::Timeout`])
+    pub fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Register a policy version directly (published policies go through
+    /// [`PolicyVM::publish`]); registering a version again is a no-op if its
+    /// code is the same and a [`PolicyError::VersionConflict`] otherwise
+    pub fn register(&mut self, policy: Policy) -> Result<()> {
+        let versions = self.policies.entry(policy.policy_id.clone()).or_default();
+        match versions.binary_search_by_key(&policy.version, |p| p.version) {
+            Ok(at) if versions[at].bytecode == policy.bytecode => Ok(()),
+            Ok(_) => Err(PolicyError::VersionConflict {
+                policy_id: policy.policy_id,
+                version: policy.version,
+            }),
+            Err(at) => {
+                versions.insert(at, policy);
+                Ok(())
+            }
         }
     }
 
-    /// Register a policy
-    pub fn register(&mut self, policy: Policy) {
-        self.policies.insert(policy.policy_id.clone(), policy);
+    /// Every registered version of a policy, oldest first
+    pub fn history(&self, policy_id: &str) -> &[Policy] {
+        self.policies.get(policy_id).map_or(&[], Vec::as_slice)
     }
 
-    /// Evaluate a policy (SPEC-UBL-POLICY v1.0 §6)
-    /// 
-    /// In a full implementation, this would:
-    /// 1. Load the WASM module from bytecode
-    /// 2. Execute it in a sandboxed environment
-    /// 3. Return the translation decision
-    /// 
-    /// For now, we implement a simple rule-based system
-    pub fn evaluate(
+    /// Evaluate the latest version of a policy (SPEC-UBL-POLICY v1.0 §6)
+    ///
+    /// Runs the policy's TDLN bytecode or WASM module against `context`;
+    /// code that does not hash to the registered `bytecode_hash` is never executed.
+    pub fn evaluate(&self, policy_id: &str, context: &EvaluationContext) -> Result<Evaluation> {
+        self.run(self.pinned(policy_id, None)?, context)
+    }
+
+    /// Evaluate a specific version of a policy, e.g. to reproduce a recorded
+    /// [`Evaluation`] after an upgrade
+    pub fn evaluate_version(
         &self,
         policy_id: &str,
+        version: u64,
         context: &EvaluationContext,
-    ) -> Result<TranslationDecision> {
-        let _policy = self
-            .policies
-            .get(policy_id)
-            .ok_or_else(|| PolicyError::PolicyNotFound(policy_id.to_string()))?;
-
-        // Simple rule-based evaluation
-        // In production, this would execute WASM
-        
-        // Extract intent type from context
-        let intent_type = context
-            .intent
-            .get("type")
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-
-        // Simple rules based on intent type
-        match intent_type {
-            "observe" | "read" => Ok(TranslationDecision::Allow {
-                intent_class: 0x00, // Observation
-                required_pact: None,
-                constraints: vec![],
-            }),
-            
-            "transfer" | "send" => {
-                // Check for amount limits
-                let amount = context
-                    .intent
-                    .get("amount")
-                    .and_then(|v| v.as_i64())
-                    .unwrap_or(0);
-                
-                if amount > 10000 {
-                    // Large transfers require a pact
-                    Ok(TranslationDecision::Allow {
-                        intent_class: 0x01, // Conservation
-                        required_pact: Some("high_value_transfer".to_string()),
-                        constraints: vec![Constraint {
-                            kind: "max_amount".to_string(),
-                            value: "10000".to_string(),
-                        }],
-                    })
-                } else {
-                    Ok(TranslationDecision::Allow {
-                        intent_class: 0x01, // Conservation
-                        required_pact: None,
-                        constraints: vec![],
-                    })
-                }
-            }
-            
-            "create" | "mint" => Ok(TranslationDecision::Allow {
-                intent_class: 0x02, // Entropy
-                required_pact: Some("creation_authority".to_string()),
-                constraints: vec![],
-            }),
-            
-            "evolve" | "upgrade" => Ok(TranslationDecision::Allow {
-                intent_class: 0x03, // Evolution
-                required_pact: Some("evolution_l5".to_string()),
-                constraints: vec![Constraint {
-                    kind: "risk_level".to_string(),
-                    value: "L5".to_string(),
-                }],
-            }),
-            
-            _ => Ok(TranslationDecision::Deny {
-                reason: format!("Unknown intent type: {}", intent_type),
-            }),
+    ) -> Result<Evaluation> {
+        self.run(self.pinned(policy_id, Some(version))?, context)
+    }
+
+    fn run(&self, policy: &Policy, context: &EvaluationContext) -> Result<Evaluation> {
+        let decision = self.decide(policy, context)?.unwrap_or_else(exec::no_match);
+        Ok(Evaluation {
+            policy_id: policy.policy_id.clone(),
+            version: policy.version,
+            bytecode_hash: policy.bytecode_hash.clone(),
+            decision,
+        })
+    }
+
+    /// Evaluate a policy and report the rules tested, their comparisons and
+    /// the constraints gathered
+    pub fn evaluate_with_trace(
+        &self,
+        policy_id: &str,
+        context: &EvaluationContext,
+    ) -> Result<PolicyTrace> {
+        let policy = self.pinned(policy_id, None)?;
+        let (decision, rules) = if wasm::is_wasm(&policy.bytecode) {
+            (self.run_wasm(policy, context)?, Vec::new())
+        } else {
+            exec::run_traced(&policy.bytecode, context)?
+        };
+        let constraints = match &decision {
+            TranslationDecision::Allow { constraints, .. } => constraints.clone(),
+            TranslationDecision::Deny { .. } => Vec::new(),
+        };
+        Ok(PolicyTrace {
+            policy_id: policy.policy_id.clone(),
+            version: policy.version,
+            bytecode_hash: policy.bytecode_hash.clone(),
+            rules,
+            decision,
+            constraints,
+        })
+    }
+
+    /// What `policy` decides, `None` if none of its rules matched (a WASM
+    /// module always decides)
+    fn decide(
+        &self,
+        policy: &Policy,
+        context: &EvaluationContext,
+    ) -> Result<Option<TranslationDecision>> {
+        if wasm::is_wasm(&policy.bytecode) {
+            self.run_wasm(policy, context).map(Some)
+        } else {
+            exec::decide(&policy.bytecode, context)
+        }
+    }
+
+    fn run_wasm(&self, policy: &Policy, context: &EvaluationContext) -> Result<TranslationDecision> {
+        self.modules.run(&policy.bytecode_hash, &policy.bytecode, context, self.fuel)
+    }
+
+    /// Registered policy `version` (latest if `None`) whose code still
+    /// matches its `bytecode_hash`
+    fn pinned(&self, policy_id: &str, version: Option<u64>) -> Result<&Policy> {
+        let versions = self.history(policy_id);
+        let policy = match version {
+            None => versions.last(),
+            Some(v) => versions.iter().find(|p| p.version == v),
+        }
+        .ok_or_else(|| match version {
+            None => PolicyError::PolicyNotFound(policy_id.to_string()),
+            Some(v) => PolicyError::PolicyNotFound(format!("{policy_id} v{v}")),
+        })?;
+
+        if bytecode_hash(&policy.bytecode) != policy.bytecode_hash {
+            return Err(PolicyError::InvalidBytecode);
         }
+        Ok(policy)
     }
 }
 
@@ -221,6 +378,27 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    const DEFAULT: &str = r#"
+        rule observe when intent.type in ["observe", "read"] allow Observation
+
+        rule large_transfer
+          when intent.type in ["transfer", "send"] and intent.amount > 10000
+          allow Conservation require pact "high_value_transfer"
+          constrain max_delta = 1000000
+        rule transfer when intent.type in ["transfer", "send"] allow Conservation
+
+        rule create when intent.type in ["create", "mint"]
+          allow Entropy require pact "creation_authority"
+        rule evolve when intent.type in ["evolve", "upgrade"]
+          allow Evolution require pact "evolution_l5" constrain risk_level = "L5"
+
+        rule unknown deny "Unknown intent type"
+    "#;
+
+    fn default_policy() -> Policy {
+        Policy::from_source("default", 1, "Default policy", DEFAULT).unwrap()
+    }
+
     fn make_context(intent_type: &str, amount: Option<i64>) -> EvaluationContext {
         let mut intent = json!({"type": intent_type});
         if let Some(amt) = amount {
@@ -239,16 +417,10 @@ mod tests {
     #[test]
     fn test_observe_allows_observation() {
         let mut vm = PolicyVM::new();
-        vm.register(Policy {
-            policy_id: "default".to_string(),
-            version: "1.0".to_string(),
-            bytecode_hash: "test".to_string(),
-            bytecode: vec![],
-            description: "Default policy".to_string(),
-        });
+        vm.register(default_policy()).unwrap();
 
         let context = make_context("observe", None);
-        let decision = vm.evaluate("default", &context).unwrap();
+        let decision = vm.evaluate("default", &context).unwrap().decision;
 
         match decision {
             TranslationDecision::Allow { intent_class, .. } => {
@@ -261,16 +433,10 @@ mod tests {
     #[test]
     fn test_small_transfer_no_pact() {
         let mut vm = PolicyVM::new();
-        vm.register(Policy {
-            policy_id: "default".to_string(),
-            version: "1.0".to_string(),
-            bytecode_hash: "test".to_string(),
-            bytecode: vec![],
-            description: "Default policy".to_string(),
-        });
+        vm.register(default_policy()).unwrap();
 
         let context = make_context("transfer", Some(100));
-        let decision = vm.evaluate("default", &context).unwrap();
+        let decision = vm.evaluate("default", &context).unwrap().decision;
 
         match decision {
             TranslationDecision::Allow {
@@ -288,16 +454,10 @@ mod tests {
     #[test]
     fn test_large_transfer_requires_pact() {
         let mut vm = PolicyVM::new();
-        vm.register(Policy {
-            policy_id: "default".to_string(),
-            version: "1.0".to_string(),
-            bytecode_hash: "test".to_string(),
-            bytecode: vec![],
-            description: "Default policy".to_string(),
-        });
+        vm.register(default_policy()).unwrap();
 
         let context = make_context("transfer", Some(20000));
-        let decision = vm.evaluate("default", &context).unwrap();
+        let decision = vm.evaluate("default", &context).unwrap().decision;
 
         match decision {
             TranslationDecision::Allow {
@@ -312,16 +472,10 @@ mod tests {
     #[test]
     fn test_evolution_requires_l5_pact() {
         let mut vm = PolicyVM::new();
-        vm.register(Policy {
-            policy_id: "default".to_string(),
-            version: "1.0".to_string(),
-            bytecode_hash: "test".to_string(),
-            bytecode: vec![],
-            description: "Default policy".to_string(),
-        });
+        vm.register(default_policy()).unwrap();
 
         let context = make_context("evolve", None);
-        let decision = vm.evaluate("default", &context).unwrap();
+        let decision = vm.evaluate("default", &context).unwrap().decision;
 
         match decision {
             TranslationDecision::Allow {
@@ -339,17 +493,95 @@ mod tests {
     #[test]
     fn test_unknown_intent_denies() {
         let mut vm = PolicyVM::new();
-        vm.register(Policy {
-            policy_id: "default".to_string(),
-            version: "1.0".to_string(),
-            bytecode_hash: "test".to_string(),
-            bytecode: vec![],
-            description: "Default policy".to_string(),
-        });
+        vm.register(default_policy()).unwrap();
 
         let context = make_context("hack_the_planet", None);
-        let decision = vm.evaluate("default", &context).unwrap();
+        let decision = vm.evaluate("default", &context).unwrap().decision;
 
         assert!(matches!(decision, TranslationDecision::Deny { .. }));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_register_never_replaces_code() {
+        let mut vm = PolicyVM::new();
+        vm.register(default_policy()).unwrap();
+        vm.register(default_policy()).unwrap();
+        let other = Policy::from_source("default", 1, "", "rule a allow Observation").unwrap();
+        assert_eq!(
+            vm.register(other),
+            Err(PolicyError::VersionConflict {
+                policy_id: "default".to_string(),
+                version: 1
+            })
+        );
+        assert_eq!(vm.history("default").len(), 1);
+        assert_eq!(vm.history("default")[0].bytecode, default_policy().bytecode);
+    }
+
+    #[test]
+    fn test_bytecode_hash_pins_policy() {
+        let policy = default_policy();
+        assert_eq!(policy.bytecode_hash, bytecode_hash(&compile(DEFAULT).unwrap()));
+
+        let mut tampered = policy.clone();
+        let last = tampered.bytecode.len() - 1;
+        tampered.bytecode[last] ^= 1;
+        let mut vm = PolicyVM::new();
+        vm.register(tampered).unwrap();
+        assert_eq!(
+            vm.evaluate("default", &make_context("observe", None)),
+            Err(PolicyError::InvalidBytecode)
+        );
+    }
+
+    #[test]
+    fn test_trace_explains_required_pact() {
+        let mut vm = PolicyVM::new();
+        vm.register(default_policy()).unwrap();
+        let context = make_context("send", Some(20000));
+
+        let trace = vm.evaluate_with_trace("default", &context).unwrap();
+        assert_eq!(trace.decision, vm.evaluate("default", &context).unwrap().decision);
+        let tested: Vec<_> = trace.rules.iter().map(|r| r.rule_id.as_str()).collect();
+        assert_eq!(tested, ["observe", "large_transfer"]);
+        assert_eq!(trace.deciding_rule().unwrap().rule_id, "large_transfer");
+        assert_eq!(trace.constraints[0].kind, "max_delta");
+
+        let amount = &trace.rules[1].predicates[1];
+        assert_eq!(amount.op, ">");
+        assert_eq!(amount.left.path.as_deref(), Some("intent.amount"));
+        assert_eq!(amount.left.value, json!(20000));
+        assert_eq!(amount.right.value, json!(10000));
+        assert!(amount.outcome);
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(json["rules"][0]["predicates"][0]["op"], "in");
+        assert_eq!(json["rules"][0]["predicates"][0]["outcome"], false);
+        assert_eq!(serde_json::from_value::<PolicyTrace>(json).unwrap(), trace);
+
+        let explanation = trace.explain();
+        assert!(explanation.contains(r#"intent.amount (20000) > 10000 is true"#));
+        assert!(explanation.ends_with(
+            r#"allow Conservation, requires pact "high_value_transfer", max_delta = 1000000"#
+        ));
+    }
+
+    #[test]
+    fn test_wasm_policy_is_fuel_limited() {
+        let spin = wat::parse_str(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "evaluate") (param i32 i32) (result i64)
+                   (loop $spin (br $spin)) (unreachable)))"#,
+        )
+        .unwrap();
+        let mut vm = PolicyVM::new().with_fuel(1_000);
+        vm.register(Policy::from_wasm("spin", 1, "never decides", spin))
+            .unwrap();
+        assert_eq!(
+            vm.evaluate("spin", &make_context("observe", None)),
+            Err(PolicyError::Timeout)
+        );
+    }
+}
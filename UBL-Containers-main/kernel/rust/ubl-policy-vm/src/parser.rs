@@ -0,0 +1,677 @@
+//! TDLN source language (SPEC-UBL-POLICY v1.0 §4–§5)
+//!
+//! A policy is a list of rules, tried in order; the first whose conditions
+//! hold decides the translation:
+//!
+//! ```text
+//! # Large transfers need the board
+//! rule large_transfer for "wallet/"
+//!   when intent.type in ["transfer", "send"] and intent.amount > 10000
+//!   allow Conservation require pact "high_value_transfer"
+//!   constrain max_delta = 10000
+//!
+//! rule fallback deny "no translation for this intent"
+//! ```
+//!
+//! - `for "<prefix>"` restricts the rule to containers whose id starts with
+//!   the prefix (`applies_to`).
+//! - Conditions read `actor`, `container`, `timestamp`, `intent.<key>…` and
+//!   `state.<key>…`, compare with `== != < <= > >= starts_with in`, and
+//!   combine with `and`, `or`, `not` and parentheses.
+//! - Literals are strings, integers, `true`, `false` and `null`.
+//! - `#` starts a comment that runs to the end of the line.
+
+use crate::{PolicyError, Result};
+
+/// A parsed policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// Rules in evaluation order
+    pub rules: Vec<Rule>,
+}
+
+/// A policy rule (SPEC-UBL-POLICY v1.0 §4.1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    /// Rule identifier
+    pub rule_id: String,
+    /// Container-id prefix the rule is limited to
+    pub applies_to: Option<String>,
+    /// Conditions; `None` always holds
+    pub when: Option<Expr>,
+    /// Decision when the rule holds
+    pub outcome: Outcome,
+}
+
+/// What a matching rule decides
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Translation allowed
+    Allow {
+        /// Intent class byte
+        intent_class: u8,
+        /// Pact the link must carry
+        required_pact: Option<String>,
+        /// `(kind, value)` constraints snapshot
+        constraints: Vec<(String, String)>,
+    },
+    /// Translation denied
+    Deny {
+        /// Reason for denial
+        reason: String,
+    },
+}
+
+/// Part of the evaluation context a path starts from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Root {
+    /// `actor`
+    Actor = 0,
+    /// `container`
+    Container = 1,
+    /// `timestamp`
+    Timestamp = 2,
+    /// `intent.…`
+    Intent = 3,
+    /// `state.…`
+    State = 4,
+}
+
+impl Root {
+    /// Root named `name`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "actor" => Root::Actor,
+            "container" => Root::Container,
+            "timestamp" => Root::Timestamp,
+            "intent" => Root::Intent,
+            "state" => Root::State,
+            _ => return None,
+        })
+    }
+
+    /// Source name of the root
+    pub fn name(self) -> &'static str {
+        match self {
+            Root::Actor => "actor",
+            Root::Container => "container",
+            Root::Timestamp => "timestamp",
+            Root::Intent => "intent",
+            Root::State => "state",
+        }
+    }
+
+    /// Decode a root byte
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        [
+            Root::Actor,
+            Root::Container,
+            Root::Timestamp,
+            Root::Intent,
+            Root::State,
+        ]
+        .into_iter()
+        .find(|root| *root as u8 == byte)
+    }
+
+    /// Whether `.key` segments may follow
+    fn has_fields(self) -> bool {
+        matches!(self, Root::Intent | Root::State)
+    }
+}
+
+/// Constant in a condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Literal {
+    /// String
+    Str(String),
+    /// Integer
+    Int(i64),
+    /// Boolean
+    Bool(bool),
+    /// Null (also what a missing field reads as)
+    Null,
+}
+
+/// Comparison operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    /// `==`
+    Eq,
+    /// `!=`
+    Ne,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `starts_with`
+    StartsWith,
+}
+
+/// Condition expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    /// Value at a path of the context
+    Path(Root, Vec<String>),
+    /// Constant
+    Literal(Literal),
+    /// Negation
+    Not(Box<Expr>),
+    /// Both hold
+    And(Box<Expr>, Box<Expr>),
+    /// Either holds
+    Or(Box<Expr>, Box<Expr>),
+    /// Comparison
+    Compare(CmpOp, Box<Expr>, Box<Expr>),
+    /// Membership in a list of constants
+    In(Box<Expr>, Vec<Literal>),
+}
+
+/// Deepest condition tree a rule may have (nodes from root to leaf); keeps
+/// parsing, compiling and tracing off the end of the stack
+pub const MAX_DEPTH: usize = 128;
+
+/// Parse TDLN source into a [`Program`]
+pub fn parse(source: &str) -> Result<Program> {
+    let mut parser = Parser {
+        tokens: lex(source)?,
+        pos: 0,
+        depth: 0,
+    };
+    let mut rules = Vec::new();
+    while !parser.at_end() {
+        rules.push(parser.rule()?);
+    }
+    Ok(Program { rules })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Ident(String),
+    Str(String),
+    Int(i64),
+    Sym(&'static str),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    line: usize,
+    column: usize,
+}
+
+pub(crate) fn syntax(line: usize, column: usize, message: impl Into<String>) -> PolicyError {
+    PolicyError::Syntax {
+        line,
+        column,
+        message: message.into(),
+    }
+}
+
+const SYMBOLS: [&str; 13] = [
+    "==", "!=", "<=", ">=", "<", ">", "=", "(", ")", "[", "]", ",", ".",
+];
+
+fn lex(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    for (index, text) in source.lines().enumerate() {
+        let line = index + 1;
+        let chars: Vec<char> = text.chars().collect();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let column = i + 1;
+            if c.is_whitespace() {
+                i += 1;
+            } else if c == '#' {
+                break;
+            } else if c == '"' {
+                let mut value = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        None => return Err(syntax(line, column, "unterminated string")),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(i + 1) {
+                                Some(&escaped @ ('"' | '\\')) => value.push(escaped),
+                                Some('n') => value.push('\n'),
+                                _ => return Err(syntax(line, i + 1, "invalid escape")),
+                            }
+                            i += 2;
+                        }
+                        Some(&other) => {
+                            value.push(other);
+                            i += 1;
+                        }
+                    }
+                }
+                i += 1;
+                tokens.push(Token {
+                    tok: Tok::Str(value),
+                    line,
+                    column,
+                });
+            } else if c.is_ascii_digit()
+                || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+            {
+                let start = i;
+                i += 1;
+                while chars.get(i).is_some_and(char::is_ascii_digit) {
+                    i += 1;
+                }
+                let digits: String = chars[start..i].iter().collect();
+                let value = digits.parse().map_err(|_| {
+                    syntax(line, column, format!("integer out of range: {}", digits))
+                })?;
+                tokens.push(Token {
+                    tok: Tok::Int(value),
+                    line,
+                    column,
+                });
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let start = i;
+                while chars
+                    .get(i)
+                    .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    i += 1;
+                }
+                tokens.push(Token {
+                    tok: Tok::Ident(chars[start..i].iter().collect()),
+                    line,
+                    column,
+                });
+            } else {
+                let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+                let sym = SYMBOLS
+                    .iter()
+                    .find(|sym| rest.starts_with(**sym))
+                    .ok_or_else(|| syntax(line, column, format!("unexpected character '{}'", c)))?;
+                i += sym.len();
+                tokens.push(Token {
+                    tok: Tok::Sym(sym),
+                    line,
+                    column,
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// `not`s and parentheses currently open
+    depth: usize,
+}
+
+/// A parsed expression and the height of its tree
+type Parsed = (Expr, usize);
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn error(&self, message: impl Into<String>) -> PolicyError {
+        match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some(token) => syntax(token.line, token.column, message),
+            None => syntax(1, 1, message),
+        }
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Tok> {
+        let tok = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error(format!("expected {}, found end of policy", expected)))?;
+        self.pos += 1;
+        Ok(tok)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Tok::Ident(word)) if word == keyword)
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", keyword)))
+        }
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let found = matches!(self.peek(), Some(Tok::Sym(s)) if *s == sym);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn sym(&mut self, sym: &str) -> Result<()> {
+        if self.eat_sym(sym) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", sym)))
+        }
+    }
+
+    fn ident(&mut self, what: &str) -> Result<String> {
+        match self.peek() {
+            Some(Tok::Ident(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Ok(name)
+            }
+            _ => Err(self.error(format!("expected {}", what))),
+        }
+    }
+
+    fn string(&mut self, what: &str) -> Result<String> {
+        match self.peek() {
+            Some(Tok::Str(value)) => {
+                let value = value.clone();
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(self.error(format!("expected {} string", what))),
+        }
+    }
+
+    fn rule(&mut self) -> Result<Rule> {
+        self.keyword("rule")?;
+        let rule_id = self.ident("rule id")?;
+        let applies_to = if self.eat_keyword("for") {
+            Some(self.string("container prefix")?)
+        } else {
+            None
+        };
+        let when = if self.eat_keyword("when") {
+            Some(self.expr()?.0)
+        } else {
+            None
+        };
+
+        let outcome = if self.eat_keyword("allow") {
+            let class = self.ident("intent class")?;
+            let intent_class = match class.as_str() {
+                "Observation" => 0x00,
+                "Conservation" => 0x01,
+                "Entropy" => 0x02,
+                "Evolution" => 0x03,
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error(format!("unknown intent class '{}'", class)));
+                }
+            };
+            let required_pact = if self.eat_keyword("require") {
+                self.keyword("pact")?;
+                Some(self.string("pact id")?)
+            } else {
+                None
+            };
+            let mut constraints = Vec::new();
+            while self.eat_keyword("constrain") {
+                let kind = self.ident("constraint kind")?;
+                self.sym("=")?;
+                let value = match self.next("constraint value")? {
+                    Tok::Str(value) => value,
+                    Tok::Int(value) => value.to_string(),
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.error("constraint value must be a string or integer"));
+                    }
+                };
+                constraints.push((kind, value));
+                if constraints.len() > u16::MAX as usize {
+                    return Err(self.error("too many constraints"));
+                }
+            }
+            Outcome::Allow {
+                intent_class,
+                required_pact,
+                constraints,
+            }
+        } else if self.eat_keyword("deny") {
+            Outcome::Deny {
+                reason: self.string("denial reason")?,
+            }
+        } else {
+            return Err(self.error("expected 'allow' or 'deny'"));
+        };
+
+        Ok(Rule {
+            rule_id,
+            applies_to,
+            when,
+            outcome,
+        })
+    }
+
+    /// Parse `f` inside one more `not` or parenthesis, refusing to recurse
+    /// past [`MAX_DEPTH`]
+    fn nested(&mut self, f: impl FnOnce(&mut Self) -> Result<Parsed>) -> Result<Parsed> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+        self.depth += 1;
+        let parsed = f(self);
+        self.depth -= 1;
+        parsed
+    }
+
+    /// `expr` as a node over children of height `below`
+    fn node(&self, expr: Expr, below: usize) -> Result<Parsed> {
+        if below >= MAX_DEPTH {
+            return Err(self.error("expression nested too deeply"));
+        }
+        Ok((expr, below + 1))
+    }
+
+    fn expr(&mut self) -> Result<Parsed> {
+        let (mut left, mut height) = self.and()?;
+        while self.eat_keyword("or") {
+            let (right, right_height) = self.and()?;
+            (left, height) = self.node(
+                Expr::Or(Box::new(left), Box::new(right)),
+                height.max(right_height),
+            )?;
+        }
+        Ok((left, height))
+    }
+
+    fn and(&mut self) -> Result<Parsed> {
+        let (mut left, mut height) = self.not()?;
+        while self.eat_keyword("and") {
+            let (right, right_height) = self.not()?;
+            (left, height) = self.node(
+                Expr::And(Box::new(left), Box::new(right)),
+                height.max(right_height),
+            )?;
+        }
+        Ok((left, height))
+    }
+
+    fn not(&mut self) -> Result<Parsed> {
+        if self.eat_keyword("not") {
+            let (inner, height) = self.nested(Self::not)?;
+            self.node(Expr::Not(Box::new(inner)), height)
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Parsed> {
+        let (left, height) = self.operand()?;
+        if self.eat_keyword("in") {
+            self.sym("[")?;
+            let mut items = Vec::new();
+            if !self.eat_sym("]") {
+                loop {
+                    items.push(self.literal()?);
+                    if items.len() > u16::MAX as usize {
+                        return Err(self.error("list too long"));
+                    }
+                    if self.eat_sym("]") {
+                        break;
+                    }
+                    self.sym(",")?;
+                }
+            }
+            return self.node(Expr::In(Box::new(left), items), height);
+        }
+        let op = match self.peek() {
+            Some(Tok::Sym("==")) => CmpOp::Eq,
+            Some(Tok::Sym("!=")) => CmpOp::Ne,
+            Some(Tok::Sym("<")) => CmpOp::Lt,
+            Some(Tok::Sym("<=")) => CmpOp::Le,
+            Some(Tok::Sym(">")) => CmpOp::Gt,
+            Some(Tok::Sym(">=")) => CmpOp::Ge,
+            Some(Tok::Ident(word)) if word == "starts_with" => CmpOp::StartsWith,
+            _ => return Ok((left, height)),
+        };
+        self.pos += 1;
+        let (right, right_height) = self.operand()?;
+        self.node(
+            Expr::Compare(op, Box::new(left), Box::new(right)),
+            height.max(right_height),
+        )
+    }
+
+    fn operand(&mut self) -> Result<Parsed> {
+        if self.eat_sym("(") {
+            let inner = self.nested(Self::expr)?;
+            self.sym(")")?;
+            return Ok(inner);
+        }
+        if let Some(Tok::Ident(name)) = self.peek() {
+            if let Some(root) = Root::from_name(name) {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                while self.eat_sym(".") {
+                    if !root.has_fields() {
+                        return Err(self.error(format!("{:?} has no fields", root)));
+                    }
+                    if fields.len() == u8::MAX as usize {
+                        return Err(self.error("path too deep"));
+                    }
+                    fields.push(match self.next("field name")? {
+                        Tok::Ident(field) | Tok::Str(field) => field,
+                        _ => {
+                            self.pos -= 1;
+                            return Err(self.error("expected field name"));
+                        }
+                    });
+                }
+                return Ok((Expr::Path(root, fields), 1));
+            }
+        }
+        Ok((Expr::Literal(self.literal()?), 1))
+    }
+
+    fn literal(&mut self) -> Result<Literal> {
+        let literal = match self.next("value")? {
+            Tok::Str(value) => Literal::Str(value),
+            Tok::Int(value) => Literal::Int(value),
+            Tok::Ident(word) if word == "true" => Literal::Bool(true),
+            Tok::Ident(word) if word == "false" => Literal::Bool(false),
+            Tok::Ident(word) if word == "null" => Literal::Null,
+            _ => {
+                self.pos -= 1;
+                return Err(self.error("expected a value"));
+            }
+        };
+        Ok(literal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rule() {
+        let program = parse(
+            r#"
+            # comment
+            rule big for "wallet/"
+              when intent.type in ["transfer", "send"] and not (intent.amount <= 10000)
+              allow Conservation require pact "board" constrain max_delta = 10000
+            rule rest deny "no"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(program.rules.len(), 2);
+        let big = &program.rules[0];
+        assert_eq!(big.applies_to.as_deref(), Some("wallet/"));
+        assert!(matches!(big.when, Some(Expr::And(..))));
+        assert_eq!(
+            big.outcome,
+            Outcome::Allow {
+                intent_class: 0x01,
+                required_pact: Some("board".to_string()),
+                constraints: vec![("max_delta".to_string(), "10000".to_string())],
+            }
+        );
+        assert_eq!(program.rules[1].when, None);
+    }
+
+    #[test]
+    fn test_syntax_errors_have_positions() {
+        let err = parse("rule a\n  when intent.x == \n  allow Observation").unwrap_err();
+        assert_eq!(
+            err,
+            PolicyError::Syntax {
+                line: 3,
+                column: 3,
+                message: "expected a value".to_string()
+            }
+        );
+        assert!(matches!(
+            parse("rule a allow Magic"),
+            Err(PolicyError::Syntax { column: 14, .. })
+        ));
+        assert!(matches!(
+            parse("rule a when actor.name == 1 deny \"x\""),
+            Err(PolicyError::Syntax { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_deep_nesting() {
+        let too_deep = |condition: String| {
+            let source = format!("rule a when {} deny \"x\"", condition);
+            matches!(parse(&source), Err(PolicyError::Syntax { message, .. }) if message.contains("too deeply"))
+        };
+        assert!(too_deep(format!("{}true", "not ".repeat(100_000))));
+        assert!(too_deep(format!(
+            "{}true{}",
+            "(".repeat(100_000),
+            ")".repeat(100_000)
+        )));
+        assert!(too_deep(vec!["actor == \"a\""; MAX_DEPTH + 1].join(" or ")));
+
+        let deep = format!("{}true", "not ".repeat(MAX_DEPTH - 1));
+        assert!(parse(&format!("rule a when {} deny \"x\"", deep)).is_ok());
+    }
+}
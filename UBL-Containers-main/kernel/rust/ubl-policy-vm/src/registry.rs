@@ -0,0 +1,307 @@
+//! Versioned policies as ledger history (SPEC-UBL-POLICY v1.0 §8)
+//!
+//! Policies are published by Evolution commits to [`POLICY_CONTAINER`], so
+//! publishing needs the same L5 authority as any other rule change. Each
+//! commit's atom is a [`PolicyBundle`]:
+//!
+//! ```text
+//! { "type": "policy/bundle",
+//!   "policies": [ { "policy_id": "...", "version": 2, "description": "...",
+//!                   "bytecode_hash": "...", "bytecode": "<hex>" } ] }
+//! ```
+//!
+//! Replaying the container in order rebuilds the [`PolicyVM`]. Every version
+//! stays registered, pinned by its `bytecode_hash`; evaluation uses the
+//! latest and reports which version decided ([`Evaluation`]), so a recorded
+//! decision can be re-run with [`PolicyVM::evaluate_version`] after upgrades.
+//!
+//! [`Evaluation`]: crate::Evaluation
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ubl_link::{IntentClass, LinkCommit};
+
+use crate::{bytecode_hash, Policy, PolicyError, PolicyVM, Result};
+
+/// Container whose Evolution commits publish policies
+pub const POLICY_CONTAINER: &str = "C.Policy";
+
+/// `type` of a policy bundle atom
+pub const POLICY_ATOM_TYPE: &str = "policy/bundle";
+
+/// Policy versions published together
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyBundle {
+    /// The versions, at most one per policy id
+    pub policies: Vec<PublishedPolicy>,
+}
+
+/// One policy version as carried in a bundle
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublishedPolicy {
+    /// Policy identifier
+    pub policy_id: String,
+    /// Version, newer than any published before for this id
+    pub version: u64,
+    /// Human-readable description
+    pub description: String,
+    /// BLAKE3 of the bytecode, hex
+    pub bytecode_hash: String,
+    /// TDLN bytecode or WASM module, hex
+    pub bytecode: String,
+}
+
+impl PolicyBundle {
+    /// Bundle `policies` for publication
+    pub fn new(policies: impl IntoIterator<Item = Policy>) -> Self {
+        Self {
+            policies: policies
+                .into_iter()
+                .map(|p| PublishedPolicy {
+                    bytecode: hex::encode(&p.bytecode),
+                    policy_id: p.policy_id,
+                    version: p.version,
+                    description: p.description,
+                    bytecode_hash: p.bytecode_hash,
+                })
+                .collect(),
+        }
+    }
+
+    /// The atom committed for this bundle
+    pub fn to_atom(&self) -> Value {
+        let mut atom = serde_json::to_value(self).expect("policy bundles serialize");
+        atom["type"] = Value::from(POLICY_ATOM_TYPE);
+        atom
+    }
+
+    /// `atom_hash` of [`to_atom`](PolicyBundle::to_atom)
+    pub fn atom_hash(&self) -> Result<String> {
+        ubl_atom::hash_value(&self.to_atom()).map_err(|e| malformed(e.to_string()))
+    }
+
+    /// Decode a bundle atom
+    pub fn from_atom(atom: &Value) -> Result<Self> {
+        if atom.get("type").and_then(Value::as_str) != Some(POLICY_ATOM_TYPE) {
+            return Err(malformed(format!("atom type is not {}", POLICY_ATOM_TYPE)));
+        }
+        serde_json::from_value(atom.clone()).map_err(|e| malformed(e.to_string()))
+    }
+
+    /// Decode the bundle a `C.Policy` commit carries, checking that the link
+    /// is an Evolution on the policy container and commits exactly `atom`
+    pub fn from_link(link: &LinkCommit, atom: &Value) -> Result<Self> {
+        if link.container_id != POLICY_CONTAINER {
+            return Err(malformed(format!("link targets {}", link.container_id)));
+        }
+        if link.intent_class != IntentClass::Evolution {
+            return Err(malformed("policy changes must be Evolution"));
+        }
+        if ubl_atom::hash_value(atom).map_err(|e| malformed(e.to_string()))? != link.atom_hash {
+            return Err(malformed("atom does not match the link's atom_hash"));
+        }
+        Self::from_atom(atom)
+    }
+
+    /// The bundled policies, each checked against its `bytecode_hash`
+    pub fn policies(&self) -> Result<Vec<Policy>> {
+        if self.policies.is_empty() {
+            return Err(malformed("empty bundle"));
+        }
+        let mut out: Vec<Policy> = Vec::with_capacity(self.policies.len());
+        for published in &self.policies {
+            if out.iter().any(|p| p.policy_id == published.policy_id) {
+                return Err(malformed(format!("{} appears twice", published.policy_id)));
+            }
+            let bytecode = hex::decode(&published.bytecode)
+                .map_err(|_| malformed(format!("{} bytecode is not hex", published.policy_id)))?;
+            if bytecode_hash(&bytecode) != published.bytecode_hash {
+                return Err(PolicyError::InvalidBytecode);
+            }
+            out.push(Policy {
+                policy_id: published.policy_id.clone(),
+                version: published.version,
+                bytecode_hash: published.bytecode_hash.clone(),
+                bytecode,
+                description: published.description.clone(),
+            });
+        }
+        Ok(out)
+    }
+}
+
+impl PolicyVM {
+    /// Check that `bundle` may be published without publishing it
+    pub fn check(&self, bundle: &PolicyBundle) -> Result<()> {
+        self.checked(bundle).map(drop)
+    }
+
+    /// Publish a bundle: all of its versions are registered, or none
+    pub fn publish(&mut self, bundle: &PolicyBundle) -> Result<()> {
+        for policy in self.checked(bundle)? {
+            self.register(policy)?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild a VM from `C.Policy` bundles in commit order
+    pub fn replay(bundles: impl IntoIterator<Item = PolicyBundle>) -> Result<Self> {
+        let mut vm = Self::new();
+        for bundle in bundles {
+            vm.publish(&bundle)?;
+        }
+        Ok(vm)
+    }
+
+    fn checked(&self, bundle: &PolicyBundle) -> Result<Vec<Policy>> {
+        let policies = bundle.policies()?;
+        for policy in &policies {
+            if policy.version == 0 {
+                return Err(malformed(format!(
+                    "{} has version 0; versions start at 1",
+                    policy.policy_id
+                )));
+            }
+            if let Some(latest) = self.history(&policy.policy_id).last() {
+                if policy.version <= latest.version {
+                    return Err(PolicyError::StaleVersion {
+                        policy_id: policy.policy_id.clone(),
+                        version: policy.version,
+                        latest: latest.version,
+                    });
+                }
+            }
+        }
+        Ok(policies)
+    }
+}
+
+fn malformed(reason: impl Into<String>) -> PolicyError {
+    PolicyError::MalformedBundle(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EvaluationContext, TranslationDecision};
+    use serde_json::json;
+
+    fn limit(version: u64, max: i64) -> Policy {
+        let source = format!(
+            r#"rule small when intent.amount <= {max} allow Conservation
+               rule big allow Conservation require pact "treasury""#
+        );
+        Policy::from_source("wallet", version, "transfer limit", &source).unwrap()
+    }
+
+    fn context(amount: i64) -> EvaluationContext {
+        EvaluationContext {
+            container_id: "wallet/alice".to_string(),
+            actor: "alice".to_string(),
+            intent: json!({ "amount": amount }),
+            state: None,
+            timestamp: 1000,
+        }
+    }
+
+    #[test]
+    fn test_replay_keeps_every_version_reproducible() {
+        let vm = PolicyVM::replay([
+            PolicyBundle::new([limit(1, 100)]),
+            PolicyBundle::new([limit(2, 1000)]),
+        ])
+        .unwrap();
+
+        let now = vm.evaluate("wallet", &context(500)).unwrap();
+        assert_eq!(now.version, 2);
+        assert_eq!(now.bytecode_hash, limit(2, 1000).bytecode_hash);
+        assert!(matches!(
+            now.decision,
+            TranslationDecision::Allow {
+                required_pact: None,
+                ..
+            }
+        ));
+
+        // The decision v1 made is still reproducible after the upgrade
+        let before = vm.evaluate_version("wallet", 1, &context(500)).unwrap();
+        assert_eq!(before.version, 1);
+        assert!(matches!(
+            before.decision,
+            TranslationDecision::Allow {
+                required_pact: Some(_),
+                ..
+            }
+        ));
+        assert_eq!(vm.history("wallet").len(), 2);
+        assert!(matches!(
+            vm.evaluate_version("wallet", 3, &context(500)),
+            Err(PolicyError::PolicyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_publish_rules() {
+        let mut vm = PolicyVM::new();
+        vm.publish(&PolicyBundle::new([limit(2, 100)])).unwrap();
+
+        assert!(matches!(
+            vm.publish(&PolicyBundle::new([limit(2, 500)])),
+            Err(PolicyError::StaleVersion { latest: 2, .. })
+        ));
+        let mut tampered = PolicyBundle::new([limit(3, 100)]);
+        tampered.policies[0].bytecode_hash = limit(3, 999).bytecode_hash;
+        assert_eq!(vm.check(&tampered), Err(PolicyError::InvalidBytecode));
+        assert!(matches!(
+            vm.check(&PolicyBundle::new([limit(3, 1), limit(4, 2)])),
+            Err(PolicyError::MalformedBundle(_))
+        ));
+        assert!(matches!(
+            vm.check(&PolicyBundle::new([])),
+            Err(PolicyError::MalformedBundle(_))
+        ));
+        let unversioned = Policy::from_source("fresh", 0, "", "rule a allow Observation").unwrap();
+        assert!(matches!(
+            vm.check(&PolicyBundle::new([unversioned])),
+            Err(PolicyError::MalformedBundle(_))
+        ));
+
+        // All or nothing: a stale entry keeps the fresh one out too
+        let other = Policy::from_source("other", 1, "", "rule a allow Observation").unwrap();
+        assert!(vm
+            .publish(&PolicyBundle::new([other, limit(1, 5)]))
+            .is_err());
+        assert!(vm.history("other").is_empty());
+    }
+
+    #[test]
+    fn test_bundle_atoms_round_trip() {
+        let bundle = PolicyBundle::new([limit(1, 100)]);
+        let atom = bundle.to_atom();
+        assert_eq!(atom["type"], POLICY_ATOM_TYPE);
+        assert_eq!(PolicyBundle::from_atom(&atom).unwrap(), bundle);
+
+        let link = LinkCommit {
+            version: ubl_link::LINK_V2,
+            container_id: POLICY_CONTAINER.to_string(),
+            expected_sequence: 1,
+            previous_hash: "0x00".to_string(),
+            atom_hash: bundle.atom_hash().unwrap(),
+            intent_class: IntentClass::Evolution,
+            physics_delta: 0,
+            constraints: Vec::new(),
+            pact: None,
+            author_pubkey: "pk".to_string(),
+            signature: String::new(),
+        };
+        assert_eq!(PolicyBundle::from_link(&link, &atom).unwrap(), bundle);
+
+        let other = PolicyBundle::new([limit(2, 100)]).to_atom();
+        assert!(PolicyBundle::from_link(&link, &other).is_err());
+        let entropy = LinkCommit {
+            intent_class: IntentClass::Entropy,
+            ..link
+        };
+        assert!(PolicyBundle::from_link(&entropy, &atom).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+//! Evaluation traces (SPEC-UBL-POLICY v1.0 §6)
+//!
+//! A [`PolicyTrace`] records why a decision was reached: the rules tested,
+//! in order, each comparison with its inputs and outcome, and the
+//! constraints gathered by the deciding rule. Traces serialize to JSON so a
+//! narrator can tell an agent, for instance, why an affordance requires a
+//! pact. WASM policies are opaque: their traces carry the decision only.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Constraint, TranslationDecision};
+
+/// How a policy reached its decision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyTrace {
+    /// Policy evaluated
+    pub policy_id: String,
+    /// Version that decided
+    pub version: u64,
+    /// Hash of the code that ran
+    pub bytecode_hash: String,
+    /// Rules tested, in order; empty for WASM policies
+    pub rules: Vec<RuleTrace>,
+    /// Outcome
+    pub decision: TranslationDecision,
+    /// Constraints the decision carries
+    pub constraints: Vec<Constraint>,
+}
+
+/// One rule tested
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuleTrace {
+    /// Rule id
+    pub rule_id: String,
+    /// Comparisons evaluated for its condition (scope first)
+    pub predicates: Vec<PredicateTrace>,
+    /// Whether the rule decided
+    pub matched: bool,
+}
+
+/// One comparison and its result
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PredicateTrace {
+    /// `==`, `!=`, `<`, `<=`, `>`, `>=`, `starts_with` or `in`
+    pub op: String,
+    /// Left-hand input
+    pub left: Operand,
+    /// Right-hand input (the candidate list for `in`)
+    pub right: Operand,
+    /// Result
+    pub outcome: bool,
+}
+
+/// Input to a comparison
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Operand {
+    /// Context path it was read from (`intent.amount`); none for literals
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Value compared
+    pub value: Value,
+}
+
+impl PolicyTrace {
+    /// Rule that decided, if any
+    pub fn deciding_rule(&self) -> Option<&RuleTrace> {
+        self.rules.iter().find(|rule| rule.matched)
+    }
+
+    /// Plain-text account: one line per rule tested, then the decision
+    pub fn explain(&self) -> String {
+        let mut out = format!("policy {} v{}\n", self.policy_id, self.version);
+        for rule in &self.rules {
+            out.push_str(&format!("{rule}\n"));
+        }
+        match &self.decision {
+            TranslationDecision::Allow {
+                intent_class,
+                required_pact,
+                ..
+            } => {
+                out.push_str(&format!("allow {}", class_name(*intent_class)));
+                if let Some(pact) = required_pact {
+                    out.push_str(&format!(", requires pact {pact:?}"));
+                }
+                for c in &self.constraints {
+                    out.push_str(&format!(", {} = {}", c.kind, c.value));
+                }
+            }
+            TranslationDecision::Deny { reason } => out.push_str(&format!("deny: {reason}")),
+        }
+        out
+    }
+}
+
+impl fmt::Display for RuleTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.matched { "matched" } else { "skipped" };
+        write!(f, "rule {} {verdict}", self.rule_id)?;
+        for (i, p) in self.predicates.iter().enumerate() {
+            write!(f, "{} {p}", if i == 0 { ":" } else { ";" })?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PredicateTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} is {}",
+            self.left, self.op, self.right, self.outcome
+        )
+    }
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{path} ({})", self.value),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+fn class_name(class: u8) -> String {
+    match class {
+        0x00 => "Observation".to_string(),
+        0x01 => "Conservation".to_string(),
+        0x02 => "Entropy".to_string(),
+        0x03 => "Evolution".to_string(),
+        other => format!("class 0x{other:02x}"),
+    }
+}
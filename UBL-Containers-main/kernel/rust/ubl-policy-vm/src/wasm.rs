@@ -0,0 +1,337 @@
+//! Sandboxed WASM policies (SPEC-UBL-POLICY v1.0 §9.1)
+//!
+//! A policy module runs in a fresh `wasmi` store with nothing to import: no
+//! WASI, no clock, no randomness, and floats disabled, so the same module and
+//! context always produce the same decision. Execution is fuel-metered and
+//! running out of fuel is a [`PolicyError::Timeout`]. Each VM keeps the
+//! [`MAX_MODULES`] most recently used modules compiled, keyed by
+//! `bytecode_hash`; only the store is fresh.
+//!
+//! Host ABI (all integers little-endian wasm `i32`/`i64`):
+//!
+//! ```text
+//! (export "memory" (memory …))
+//! (export "alloc"    (func (param $len i32) (result $ptr i32)))
+//! (export "evaluate" (func (param $ptr i32) (param $len i32) (result i64)))
+//! ```
+//!
+//! The host calls `alloc` for the input, writes the canonical JSON of the
+//! [`EvaluationContext`] (ubl-atom canonical form) there, and calls
+//! `evaluate`. The result packs `ptr << 32 | len` of a JSON
+//! [`TranslationDecision`] in the module's memory.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use wasmi::core::TrapCode;
+use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+use crate::{EvaluationContext, PolicyError, Result, TranslationDecision};
+
+/// Magic prefix of a WASM module
+pub const WASM_MAGIC: &[u8; 4] = b"\0asm";
+
+/// Fuel given to one evaluation unless the VM is configured otherwise
+pub const DEFAULT_FUEL: u64 = 10_000_000;
+
+/// Linear memory a policy may grow to
+pub const MAX_MEMORY: usize = 16 << 20;
+
+/// Largest decision a policy may return
+pub const MAX_OUTPUT: usize = 64 << 10;
+
+/// Compiled modules a VM keeps before evicting the least recently used
+pub const MAX_MODULES: usize = 64;
+
+/// Whether `bytecode` is a WASM module rather than TDLN bytecode
+pub fn is_wasm(bytecode: &[u8]) -> bool {
+    bytecode.starts_with(WASM_MAGIC)
+}
+
+/// Compiled policy modules by `bytecode_hash`, sharing one engine
+///
+/// Clones share the cache; a hash names its code, so entries never go stale.
+/// Lookups trust the caller's hash, so only the VM, which passes each
+/// registered policy's `bytecode_hash`, runs modules through it.
+#[derive(Clone)]
+pub(crate) struct ModuleCache {
+    engine: Engine,
+    modules: Arc<Mutex<Modules>>,
+    capacity: usize,
+}
+
+/// Cached modules and their hashes, least recently used first
+#[derive(Default)]
+struct Modules {
+    by_hash: HashMap<String, Arc<Module>>,
+    recent: VecDeque<String>,
+}
+
+impl Modules {
+    fn get(&mut self, hash: &str) -> Option<Arc<Module>> {
+        let module = self.by_hash.get(hash)?.clone();
+        self.recent.retain(|h| h != hash);
+        self.recent.push_back(hash.to_string());
+        Some(module)
+    }
+
+    fn insert(&mut self, hash: &str, module: Arc<Module>, capacity: usize) -> Arc<Module> {
+        if let Some(module) = self.get(hash) {
+            return module;
+        }
+        while self.by_hash.len() >= capacity {
+            let Some(oldest) = self.recent.pop_front() else {
+                break;
+            };
+            self.by_hash.remove(&oldest);
+        }
+        self.by_hash.insert(hash.to_string(), module.clone());
+        self.recent.push_back(hash.to_string());
+        module
+    }
+}
+
+impl ModuleCache {
+    /// An empty cache of [`MAX_MODULES`] with a metered, float-free engine
+    pub(crate) fn new() -> Self {
+        Self::with_capacity(MAX_MODULES)
+    }
+
+    /// An empty cache keeping at most `capacity` modules
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        let mut config = Config::default();
+        config.consume_fuel(true).floats(false);
+        Self {
+            engine: Engine::new(&config),
+            modules: Arc::default(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Run `module`, whose `bytecode_hash` is `hash`, against `context` with
+    /// at most `fuel`, compiling it on first use
+    pub(crate) fn run(
+        &self,
+        hash: &str,
+        module: &[u8],
+        context: &EvaluationContext,
+        fuel: u64,
+    ) -> Result<TranslationDecision> {
+        let cached = self.modules.lock().expect("module cache").get(hash);
+        let module = match cached {
+            Some(module) => module,
+            None => {
+                let module = Arc::new(self.compile(module)?);
+                self.modules
+                    .lock()
+                    .expect("module cache")
+                    .insert(hash, module, self.capacity)
+            }
+        };
+        execute(&module, context, fuel)
+    }
+
+    /// Modules compiled and kept
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.modules.lock().expect("module cache").by_hash.len()
+    }
+
+    fn compile(&self, module: &[u8]) -> Result<Module> {
+        Module::new(&self.engine, module).map_err(|_| PolicyError::InvalidBytecode)
+    }
+}
+
+impl Default for ModuleCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compile and run a policy module against `context` with at most `fuel`
+pub fn run(module: &[u8], context: &EvaluationContext, fuel: u64) -> Result<TranslationDecision> {
+    execute(&ModuleCache::new().compile(module)?, context, fuel)
+}
+
+fn execute(module: &Module, context: &EvaluationContext, fuel: u64) -> Result<TranslationDecision> {
+    let engine = module.engine();
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY)
+        .instances(1)
+        .memories(1)
+        .tables(1)
+        .build();
+    let mut store = Store::new(engine, limits);
+    store.limiter(|limits| limits);
+    store.set_fuel(fuel).expect("fuel metering is enabled");
+
+    // No host functions: a module importing anything (WASI included) cannot link
+    let instance = Linker::<StoreLimits>::new(engine)
+        .instantiate(&mut store, module)
+        .map_err(|_| PolicyError::InvalidBytecode)?
+        .start(&mut store)
+        .map_err(trap)?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or(PolicyError::InvalidBytecode)?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|_| PolicyError::InvalidBytecode)?;
+    let evaluate = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "evaluate")
+        .map_err(|_| PolicyError::InvalidBytecode)?;
+
+    let context = serde_json::to_value(context).expect("context serializes");
+    let input = ubl_atom::canonicalize(&context)
+        .map_err(|e| PolicyError::ExecutionFailed(format!("context: {}", e)))?;
+    let len = i32::try_from(input.len())
+        .map_err(|_| PolicyError::ExecutionFailed("context too large".to_string()))?;
+    let ptr = alloc.call(&mut store, len).map_err(trap)?;
+    memory
+        .write(&mut store, ptr as u32 as usize, &input)
+        .map_err(|_| {
+            PolicyError::ExecutionFailed("alloc returned an invalid pointer".to_string())
+        })?;
+
+    let packed = evaluate.call(&mut store, (ptr, len)).map_err(trap)? as u64;
+    let (out_ptr, out_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if out_len > MAX_OUTPUT {
+        return Err(PolicyError::ExecutionFailed(format!(
+            "decision of {} bytes exceeds {}",
+            out_len, MAX_OUTPUT
+        )));
+    }
+    let mut output = vec![0; out_len];
+    memory
+        .read(&store, out_ptr, &mut output)
+        .map_err(|_| PolicyError::ExecutionFailed("decision out of bounds".to_string()))?;
+    serde_json::from_slice(&output)
+        .map_err(|e| PolicyError::ExecutionFailed(format!("invalid decision: {}", e)))
+}
+
+fn trap(error: wasmi::Error) -> PolicyError {
+    match error.as_trap_code() {
+        Some(TrapCode::OutOfFuel) => PolicyError::Timeout,
+        _ => PolicyError::ExecutionFailed(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ALLOW: &str = r#"{"Allow":{"intent_class":1,"required_pact":null,"constraints":[]}}"#;
+    const DENY: &str = r#"{"Deny":{"reason":"not an object"}}"#;
+
+    /// A module implementing the ABI, with ALLOW at 1024 and DENY at 2048
+    fn policy(evaluate_body: &str) -> Vec<u8> {
+        wat::parse_str(format!(
+            r#"(module
+                 (memory (export "memory") 1)
+                 (data (i32.const 1024) "{allow}")
+                 (data (i32.const 2048) "{deny}")
+                 (func (export "alloc") (param i32) (result i32) (i32.const 4096))
+                 (func (export "evaluate") (param $ptr i32) (param $len i32) (result i64)
+                   {evaluate_body}))"#,
+            allow = ALLOW.replace('"', "\\\""),
+            deny = DENY.replace('"', "\\\""),
+        ))
+        .unwrap()
+    }
+
+    fn packed(ptr: usize, len: usize) -> String {
+        format!("(i64.const {})", (ptr as u64) << 32 | len as u64)
+    }
+
+    fn context() -> EvaluationContext {
+        EvaluationContext {
+            container_id: "wallet/alice".to_string(),
+            actor: "alice".to_string(),
+            intent: json!({"type": "transfer"}),
+            state: None,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn test_module_decides_from_context() {
+        let module = policy(&format!(
+            "(if (result i64) (i32.eq (i32.load8_u (local.get $ptr)) (i32.const 123))
+               (then {}) (else {}))",
+            packed(1024, ALLOW.len()),
+            packed(2048, DENY.len())
+        ));
+        assert!(is_wasm(&module));
+        assert_eq!(
+            run(&module, &context(), DEFAULT_FUEL).unwrap(),
+            TranslationDecision::Allow {
+                intent_class: 1,
+                required_pact: None,
+                constraints: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_cache_compiles_each_hash_once() {
+        let module = policy(&packed(1024, ALLOW.len()));
+        let hash = crate::bytecode_hash(&module);
+        let cache = ModuleCache::new();
+        for _ in 0..3 {
+            let decision = cache.clone().run(&hash, &module, &context(), DEFAULT_FUEL);
+            assert_eq!(decision, run(&module, &context(), DEFAULT_FUEL));
+        }
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            cache.run("other", b"\0asm garbage", &context(), DEFAULT_FUEL),
+            Err(PolicyError::InvalidBytecode)
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let allow = policy(&packed(1024, ALLOW.len()));
+        let deny = policy(&packed(2048, DENY.len()));
+        let cache = ModuleCache::with_capacity(2);
+        let run = |hash: &str, module: &[u8]| cache.run(hash, module, &context(), DEFAULT_FUEL);
+
+        run("allow", &allow).unwrap();
+        run("deny", &deny).unwrap();
+        run("allow", &allow).unwrap();
+        // "deny" is least recently used, so it makes room for "third"
+        run("third", &allow).unwrap();
+        assert_eq!(cache.len(), 2);
+        let modules = cache.modules.lock().unwrap();
+        assert!(modules.by_hash.contains_key("allow") && modules.by_hash.contains_key("third"));
+        assert_eq!(modules.recent, ["allow", "third"]);
+    }
+
+    #[test]
+    fn test_sandbox_rejects_imports_and_floats() {
+        let wasi = wat::parse_str(
+            r#"(module
+                 (import "wasi_snapshot_preview1" "clock_time_get"
+                   (func (param i32 i64 i32) (result i32)))
+                 (memory (export "memory") 1))"#,
+        )
+        .unwrap();
+        assert_eq!(
+            run(&wasi, &context(), DEFAULT_FUEL),
+            Err(PolicyError::InvalidBytecode)
+        );
+
+        let floats = policy("(drop (f64.const 1.5)) (i64.const 0)");
+        assert_eq!(
+            run(&floats, &context(), DEFAULT_FUEL),
+            Err(PolicyError::InvalidBytecode)
+        );
+
+        let garbage = policy(&packed(1024, ALLOW.len() - 1));
+        assert!(matches!(
+            run(&garbage, &context(), DEFAULT_FUEL),
+            Err(PolicyError::ExecutionFailed(_))
+        ));
+    }
+}
@@ -1,12 +1,34 @@
 //! Database layer - PostgreSQL ledger with SERIALIZABLE transactions
 //! SPEC-UBL-LEDGER v1.0 compliant
 
-use blake3::Hasher;
 use serde::{Deserialize, Serialize};
-use sqlx::{PgPool, Postgres, Transaction};
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use std::sync::{Arc, RwLock};
 use time::OffsetDateTime;
+use ubl_kernel::SigningKey;
+use ubl_ledger::{ChainBreak, ChainFault, ChainSummary, ChainVerifier, Checkpoint, Frontier};
+use ubl_link::{Constraint, IntentClass, LinkCommit, PactProof};
+use ubl_membrane::constraints::{self, REQUIRED_PACT};
+use ubl_membrane::transfer::{CREDIT, DEBIT};
+use ubl_membrane::{ContainerRules, LedgerState, Membrane, MembraneError, TransferLeg};
+use ubl_pact::{PactError, PactEvent, PactRegistry, PactVersion, PACTS_CONTAINER};
+use ubl_policy_vm::{
+    EvaluationContext, Policy, PolicyBundle, PolicyChain, PolicyError, PolicyVM,
+    TranslationDecision, POLICY_CONTAINER,
+};
 
-#[derive(Debug, Deserialize)]
+/// Previous hash of the first entry in a container
+pub const GENESIS_PREVIOUS_HASH: &str = "0x00";
+
+/// `ledger_entry.hash_scheme` of rows hashed before `hash_link`/`hash_entry` (sql/007)
+const HASH_SCHEME_LEGACY: i16 = 0;
+/// `ledger_entry.hash_scheme` of rows hashed with `hash_link`/`hash_entry`
+const HASH_SCHEME_LINK: i16 = 1;
+
+/// `ledger_entry.metadata` key of the policy chain that admitted the entry
+const POLICY_CHAIN_KEY: &str = "policy_chain";
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct LinkDraft {
     pub version: u8,
     pub container_id: String,
@@ -15,8 +37,82 @@ pub struct LinkDraft {
     pub atom_hash: String,
     pub intent_class: String,     // "Observation"|"Conservation"|"Entropy"|"Evolution"
     pub physics_delta: String,    // i128 string (já validado na Membrane)
+    /// Policy constraints (signed from v3; enforced by the membrane)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<Constraint>,
     pub author_pubkey: String,    // hex
     pub signature: String,        // hex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pact: Option<PactProof>,
+    /// Atom body; required for `C.Pacts` (pact lifecycle events) and
+    /// `C.Policy` (policy bundles)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub atom: Option<serde_json::Value>,
+    /// `C.Pacts` amend/revoke: the target pact's own signatures over this link
+    /// (not needed when `pact` is already the target's proof)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<PactProof>,
+}
+
+impl LinkDraft {
+    /// Parse the wire draft into the kernel's LinkCommit
+    pub fn to_commit(&self) -> Result<LinkCommit, TangencyError> {
+        let intent_class = match self.intent_class.as_str() {
+            "Observation" => IntentClass::Observation,
+            "Conservation" => IntentClass::Conservation,
+            "Entropy" => IntentClass::Entropy,
+            "Evolution" => IntentClass::Evolution,
+            other => {
+                return Err(TangencyError::Malformed(format!("unknown intent_class: {}", other)))
+            }
+        };
+        let physics_delta: i128 = self.physics_delta.parse().map_err(|_| {
+            TangencyError::Malformed(format!("invalid physics_delta: {}", self.physics_delta))
+        })?;
+        let expected_sequence = u64::try_from(self.expected_sequence).map_err(|_| {
+            TangencyError::Malformed(format!("invalid expected_sequence: {}", self.expected_sequence))
+        })?;
+
+        Ok(LinkCommit {
+            version: self.version,
+            container_id: self.container_id.clone(),
+            expected_sequence,
+            previous_hash: self.previous_hash.clone(),
+            atom_hash: self.atom_hash.clone(),
+            intent_class,
+            physics_delta,
+            constraints: self.constraints.clone(),
+            pact: self.pact.clone(),
+            author_pubkey: self.author_pubkey.clone(),
+            signature: self.signature.clone(),
+        })
+    }
+
+    /// Pact lifecycle event carried by a `C.Pacts` draft (`None` for other containers)
+    pub fn pact_event(&self, commit: &LinkCommit) -> Result<Option<PactEvent>, TangencyError> {
+        if self.container_id != PACTS_CONTAINER {
+            return Ok(None);
+        }
+        let atom = self.atom.as_ref().ok_or_else(|| {
+            TangencyError::Malformed(format!("{} commits must carry their atom", PACTS_CONTAINER))
+        })?;
+        PactEvent::from_link(commit, atom)
+            .map(Some)
+            .map_err(TangencyError::Pact)
+    }
+
+    /// Policy bundle carried by a `C.Policy` draft (`None` for other containers)
+    pub fn policy_bundle(&self, commit: &LinkCommit) -> Result<Option<PolicyBundle>, TangencyError> {
+        if self.container_id != POLICY_CONTAINER {
+            return Ok(None);
+        }
+        let atom = self.atom.as_ref().ok_or_else(|| {
+            TangencyError::Malformed(format!("{} commits must carry their atom", POLICY_CONTAINER))
+        })?;
+        PolicyBundle::from_link(commit, atom)
+            .map(Some)
+            .map_err(TangencyError::Policy)
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -31,113 +127,582 @@ pub struct LedgerEntry {
 
 #[derive(Debug)]
 pub enum TangencyError {
-    InvalidVersion,
-    InvalidTarget,
-    RealityDrift,
-    SequenceMismatch,
+    /// Draft could not be parsed into a LinkCommit
+    Malformed(String),
+    /// Rejected by ubl-membrane (SPEC-UBL-MEMBRANE v1.0)
+    Membrane(MembraneError),
+    /// Pact lifecycle event does not apply (SPEC-UBL-PACT v1.0 §10)
+    Pact(PactError),
+    /// Policy bundle does not apply (SPEC-UBL-POLICY v1.0 §8), or a policy
+    /// governing the container denies the link (§7)
+    Policy(PolicyError),
+    /// Postgres failed, or holds a value that does not decode
+    Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for TangencyError {
+    fn from(e: sqlx::Error) -> Self {
+        TangencyError::Database(e)
+    }
+}
+
+/// A transfer leg failed; `index` is `transfer::DEBIT` or `transfer::CREDIT`
+#[derive(Debug)]
+pub struct TransferError {
+    pub index: usize,
+    pub error: TangencyError,
 }
 
 #[derive(Clone)]
 pub struct PgLedger {
     pool: PgPool,
+    /// Per-container physics (standard: `repo://` is observation-only)
+    rules: Arc<ContainerRules>,
+    /// Pacts that can authorize Entropy / Evolution: genesis pacts plus the
+    /// projection of `C.Pacts` (see `load_pacts`)
+    pacts: Arc<RwLock<PactRegistry>>,
+    /// Held by a `C.Pacts` append from its check until its registry is swapped in
+    pact_writes: Arc<tokio::sync::Mutex<()>>,
+    /// Policies published to `C.Policy`, every version (see `load_policies`)
+    policies: Arc<RwLock<PolicyVM>>,
+    /// Held by a `C.Policy` append from its check until its VM is swapped in
+    policy_writes: Arc<tokio::sync::Mutex<()>>,
+    /// Sign a checkpoint every N entries (SPEC-UBL-LEDGER v1.0 §8.2)
+    checkpoints: Option<(Arc<SigningKey>, u64)>,
 }
 
 impl PgLedger {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            rules: Arc::new(ContainerRules::standard()),
+            pacts: Arc::new(RwLock::new(PactRegistry::new())),
+            pact_writes: Arc::new(tokio::sync::Mutex::new(())),
+            policies: Arc::new(RwLock::new(PolicyVM::new())),
+            policy_writes: Arc::new(tokio::sync::Mutex::new(())),
+            checkpoints: None,
+        }
+    }
+
+    /// Sign a checkpoint with `key` whenever a sequence is a multiple of `every`
+    pub fn with_checkpoints(mut self, key: SigningKey, every: u64) -> Self {
+        self.checkpoints = Some((Arc::new(key), every.max(1)));
+        self
+    }
+
+    /// Public key checkpoints must be signed with to be trusted
+    fn checkpoint_signer(&self) -> Option<String> {
+        self.checkpoints
+            .as_ref()
+            .map(|(key, _)| ubl_kernel::pubkey_from_signing_key(key))
+    }
+
+    /// Replace the per-container physics rules
+    pub fn with_rules(mut self, rules: ContainerRules) -> Self {
+        self.rules = Arc::new(rules);
+        self
+    }
+
+    /// Pacts in force before any `C.Pacts` history (bootstraps the first L5 pact)
+    pub fn with_pacts(self, genesis: PactRegistry) -> Self {
+        *self.pacts.write().expect("pact registry") = genesis;
+        self
+    }
+
+    /// Run `f` with a membrane using this ledger's rules and pacts, at the current time
+    /// (the registry lock is released before returning, never held across an await)
+    fn with_membrane<T>(&self, f: impl FnOnce(&Membrane<'_>) -> T) -> T {
+        let pacts = self.pacts.read().expect("pact registry");
+        f(&Membrane::new()
+            .with_rules(&self.rules)
+            .with_pacts(&pacts)
+            .at(OffsetDateTime::now_utc().unix_timestamp()))
+    }
+
+    /// Run `f` against the pact registry (lock released before returning)
+    pub fn with_registry<T>(&self, f: impl FnOnce(&PactRegistry) -> T) -> T {
+        f(&self.pacts.read().expect("pact registry"))
+    }
+
+    /// The registry with a `C.Pacts` event applied at `effective_at`, once the
+    /// target pact approved it (the live registry is left untouched)
+    fn with_pact_event(
+        &self,
+        event: &PactEvent,
+        commit: &LinkCommit,
+        link: &LinkDraft,
+        effective_at: i64,
+    ) -> Result<PactRegistry, TangencyError> {
+        let mut registry = self.pacts.read().expect("pact registry").clone();
+        registry
+            .check_approval(event, commit, link.approval.as_ref(), effective_at)
+            .and_then(|()| registry.apply(event, effective_at))
+            .map_err(TangencyError::Pact)?;
+        Ok(registry)
+    }
+
+    /// The policy VM with a `C.Policy` bundle published over the current
+    /// versions (the live VM is left untouched)
+    fn with_policy_bundle(&self, bundle: &PolicyBundle) -> Result<PolicyVM, TangencyError> {
+        let mut vm = self.policies.read().expect("policy vm").clone();
+        vm.publish(bundle).map_err(TangencyError::Policy)?;
+        Ok(vm)
+    }
+
+    /// Evaluate the policies governing the link's container against its atom
+    /// and require the link to bind their decision (SPEC-UBL-POLICY v1.0 §7):
+    /// the same intent class, every constraint, and the required pact.
+    /// Returns the chain that decided (`None` for ungoverned containers), so
+    /// the entry can record which policy versions it was admitted under
+    fn check_policies(
+        &self,
+        link: &LinkDraft,
+        commit: &LinkCommit,
+    ) -> Result<Option<PolicyChain>, TangencyError> {
+        let chain = &self.rules.for_container(&commit.container_id).policies;
+        if chain.is_empty() {
+            return Ok(None);
+        }
+        let atom = link.atom.as_ref().ok_or_else(|| {
+            TangencyError::Malformed(format!(
+                "{} is governed by policies; commits must carry their atom",
+                commit.container_id
+            ))
+        })?;
+        let atom_hash =
+            ubl_atom::hash_value(atom).map_err(|e| TangencyError::Malformed(e.to_string()))?;
+        if atom_hash != commit.atom_hash {
+            return Err(TangencyError::Malformed("atom does not match the link's atom_hash".into()));
+        }
+
+        let context = EvaluationContext {
+            container_id: commit.container_id.clone(),
+            actor: commit.author_pubkey.clone(),
+            intent: atom.clone(),
+            state: None,
+            timestamp: OffsetDateTime::now_utc().unix_timestamp(),
+        };
+        let ids: Vec<&str> = chain.iter().map(String::as_str).collect();
+        let evaluated = self.policies.read().expect("policy vm").evaluate_chain(&ids, &context);
+        let denied = |reason| TangencyError::Policy(PolicyError::Denied(reason));
+        let (intent_class, required_pact, decided) = match evaluated.decision {
+            TranslationDecision::Allow { intent_class, required_pact, constraints } => {
+                (intent_class, required_pact, constraints)
+            }
+            TranslationDecision::Deny { reason } => return Err(denied(reason)),
+        };
+        if intent_class != commit.intent_class.as_byte() {
+            return Err(denied(format!(
+                "policies allow intent class {:#04x}, the link is {:?}",
+                intent_class, commit.intent_class
+            )));
+        }
+        let decided: Vec<Constraint> = decided
+            .into_iter()
+            .map(|c| Constraint { kind: c.kind, value: c.value })
+            .chain(required_pact.map(|value| Constraint { kind: REQUIRED_PACT.to_string(), value }))
+            .collect();
+        constraints::require(commit, &decided).map_err(TangencyError::Membrane)?;
+        Ok(Some(evaluated.chain))
+    }
+
+    /// Fold the `C.Pacts` history into the registry (SPEC-UBL-PACT v1.0 §10)
+    /// Each event takes effect at its entry's commit time. An event that no
+    /// longer applies fails the whole replay: skipping it would leave pacts
+    /// the ledger revoked or amended in force.
+    pub async fn load_pacts(&self) -> Result<usize, TangencyError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT sequence, ts_unix_ms, metadata
+            FROM ledger_entry
+            WHERE container_id = $1
+            ORDER BY sequence ASC
+            "#,
+            PACTS_CONTAINER
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut registry = self.pacts.write().expect("pact registry");
+        let mut applied = 0;
+        for row in rows {
+            serde_json::from_value::<LinkDraft>(row.metadata)
+                .map_err(|e| TangencyError::Malformed(e.to_string()))
+                .and_then(|draft| draft.pact_event(&draft.to_commit()?))
+                .and_then(|event| event.ok_or(TangencyError::Malformed("no event".into())))
+                .and_then(|event| {
+                    registry
+                        .apply(&event, row.ts_unix_ms / 1000)
+                        .map_err(TangencyError::Pact)
+                })
+                .inspect_err(|e| {
+                    tracing::error!("{} seq={} does not replay: {:?}", PACTS_CONTAINER, row.sequence, e)
+                })?;
+            applied += 1;
+        }
+        Ok(applied)
+    }
+
+    /// Every version of a pact, oldest first
+    pub fn pact_history(&self, pact_id: &str) -> Vec<PactVersion> {
+        self.pacts.read().expect("pact registry").history(pact_id).to_vec()
+    }
+
+    /// Rebuild the policy VM by replaying `C.Policy` bundles in order
+    /// SPEC-UBL-POLICY v1.0 §8 - returns how many bundles were published.
+    /// A bundle that no longer publishes fails the whole replay, as for
+    /// `load_pacts`: skipping it would run superseded policies.
+    pub async fn load_policies(&self) -> Result<usize, TangencyError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT sequence, metadata
+            FROM ledger_entry
+            WHERE container_id = $1
+            ORDER BY sequence ASC
+            "#,
+            POLICY_CONTAINER
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut vm = self.policies.write().expect("policy vm");
+        let mut published = 0;
+        for row in rows {
+            serde_json::from_value::<LinkDraft>(row.metadata)
+                .map_err(|e| TangencyError::Malformed(e.to_string()))
+                .and_then(|draft| draft.policy_bundle(&draft.to_commit()?))
+                .and_then(|bundle| bundle.ok_or(TangencyError::Malformed("no bundle".into())))
+                .and_then(|bundle| vm.publish(&bundle).map_err(TangencyError::Policy))
+                .inspect_err(|e| {
+                    tracing::error!("{} seq={} does not replay: {:?}", POLICY_CONTAINER, row.sequence, e)
+                })?;
+            published += 1;
+        }
+        Ok(published)
+    }
+
+    /// Every published version of a policy, oldest first
+    pub fn policy_history(&self, policy_id: &str) -> Vec<Policy> {
+        self.policies.read().expect("policy vm").history(policy_id).to_vec()
+    }
+
+    /// Dry-run: validate a draft against the current state without appending
+    pub async fn validate(&self, link: &LinkDraft) -> Result<(), TangencyError> {
+        let commit = link.to_commit()?;
+        let mut conn = self.pool.acquire().await?;
+        let state = membrane_state(&mut conn, &link.container_id, false, self.checkpoint_signer().as_deref()).await?;
+        self.with_membrane(|m| m.validate_signed(&commit, &state))
+            .map_err(TangencyError::Membrane)?;
+        self.check_policies(link, &commit)?;
+        if let Some(event) = link.pact_event(&commit)? {
+            self.with_pact_event(&event, &commit, link, OffsetDateTime::now_utc().unix_timestamp())?;
+        }
+        if let Some(bundle) = link.policy_bundle(&commit)? {
+            self.with_policy_bundle(&bundle)?;
+        }
+        Ok(())
     }
 
     /// Append transacional com SERIALIZABLE + FOR UPDATE
     /// SPEC-UBL-LEDGER v1.0 §7 - Atomicidade: validate → append → commit
     pub async fn append(&self, link: &LinkDraft) -> Result<LedgerEntry, TangencyError> {
+        let commit = link.to_commit()?;
+        let pact_event = link.pact_event(&commit)?;
+        let policy_bundle = link.policy_bundle(&commit)?;
+
+        // C.Pacts appends hold this from their check until their registry is
+        // swapped in, so each is checked against the registry it replaces
+        let _pact_writes = match pact_event {
+            Some(_) => Some(self.pact_writes.lock().await),
+            None => None,
+        };
+        // Likewise for C.Policy appends and the policy VM
+        let _policy_writes = match policy_bundle {
+            Some(_) => Some(self.policy_writes.lock().await),
+            None => None,
+        };
+
         // Begin SERIALIZABLE transaction
-        let mut tx: Transaction<Postgres> = self
-            .pool
-            .begin()
-            .await
-            .expect("tx begin");
+        let mut tx: Transaction<Postgres> = self.pool.begin().await?;
         
         sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;")
             .execute(&mut *tx)
-            .await
-            .expect("serializable");
+            .await?;
 
-        // Lock and get latest entry (FOR UPDATE)
-        let rec = sqlx::query!(
-            r#"
-            SELECT sequence, entry_hash
-            FROM ledger_entry
-            WHERE container_id = $1
-            ORDER BY sequence DESC
-            LIMIT 1
-            FOR UPDATE
-            "#,
-            link.container_id
-        )
-        .fetch_optional(&mut *tx)
-        .await
-        .expect("select last");
+        // Lock the tail (FOR UPDATE) and project the state the membrane needs
+        let state = membrane_state(&mut tx, &link.container_id, true, self.checkpoint_signer().as_deref()).await?;
+
+        // SPEC-UBL-MEMBRANE v1.0 §6 - full validation inside the transaction
+        self.with_membrane(|m| m.validate_signed(&commit, &state))
+            .map_err(TangencyError::Membrane)?;
+        let policy_chain = self.check_policies(link, &commit)?;
+        let policies = match &policy_bundle {
+            Some(bundle) => Some(self.with_policy_bundle(bundle)?),
+            None => None,
+        };
 
-        let (expected_prev, expected_seq) = match rec {
-            Some(r) => (r.entry_hash, r.sequence + 1),
-            None => ("0x00".to_string(), 1),
+        let entry = self
+            .insert_entry(&mut tx, link, &commit, state, policy_chain.as_ref())
+            .await?;
+
+        // The event takes effect at the entry's commit time; if it does not
+        // apply, dropping the transaction rolls the entry back
+        let pacts = match &pact_event {
+            Some(event) => Some(self.with_pact_event(event, &commit, link, entry.ts_unix_ms / 1000)?),
+            None => None,
         };
 
-        // Validate causality (SPEC-UBL-MEMBRANE v1.0 §V4)
-        if link.previous_hash != expected_prev {
-            return Err(TangencyError::RealityDrift);
+        // Commit transaction
+        tx.commit().await?;
+
+        if let Some(pacts) = pacts {
+            *self.pacts.write().expect("pact registry") = pacts;
+        }
+        if let Some(policies) = policies {
+            *self.policies.write().expect("policy vm") = policies;
         }
 
-        // Validate sequence (SPEC-UBL-MEMBRANE v1.0 §V5)
-        if link.expected_sequence != expected_seq {
-            return Err(TangencyError::SequenceMismatch);
+        Ok(entry)
+    }
+
+    /// Append the debit and credit legs of a transfer in one SERIALIZABLE transaction
+    /// SPEC-UBL-MEMBRANE v1.0 §6 (V6 Conservation) - both legs or neither
+    pub async fn transfer(
+        &self,
+        debit: &LinkDraft,
+        credit: &LinkDraft,
+    ) -> Result<(LedgerEntry, LedgerEntry), TransferError> {
+        let leg = |index| move |e| TransferError { index, error: e };
+        let debit_commit = debit.to_commit().map_err(leg(DEBIT))?;
+        let credit_commit = credit.to_commit().map_err(leg(CREDIT))?;
+        // Their histories replay as pact events and policy bundles only
+        for (index, draft) in [(DEBIT, debit), (CREDIT, credit)] {
+            if [PACTS_CONTAINER, POLICY_CONTAINER].contains(&draft.container_id.as_str()) {
+                return Err(leg(index)(TangencyError::Malformed(format!(
+                    "transfers cannot target {}",
+                    draft.container_id
+                ))));
+            }
         }
+        let debit_chain = self.check_policies(debit, &debit_commit).map_err(leg(DEBIT))?;
+        let credit_chain = self.check_policies(credit, &credit_commit).map_err(leg(CREDIT))?;
+
+        // Failures of the transaction as a whole are reported on the debit leg
+        let db = |e: sqlx::Error| leg(DEBIT)(TangencyError::Database(e));
+        let mut tx: Transaction<Postgres> = self.pool.begin().await.map_err(db)?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE;")
+            .execute(&mut *tx)
+            .await
+            .map_err(db)?;
 
-        // Validate version (SPEC-UBL-MEMBRANE v1.0 §V1)
-        if link.version != 1 {
-            return Err(TangencyError::InvalidVersion);
+        // Lock both tails in container order, so opposite transfers cannot deadlock
+        let debit_first = debit.container_id <= credit.container_id;
+        let order = if debit_first { [DEBIT, CREDIT] } else { [CREDIT, DEBIT] };
+        let signer = self.checkpoint_signer();
+        let mut states = Vec::with_capacity(2);
+        for index in order {
+            let id = if index == DEBIT { &debit.container_id } else { &credit.container_id };
+            let state = membrane_state(&mut tx, id, true, signer.as_deref())
+                .await
+                .map_err(|e| leg(index)(TangencyError::Database(e)))?;
+            states.push(state);
         }
+        let (first, second) = (states.remove(0), states.remove(0));
+        let (debit_state, credit_state) = if debit_first { (first, second) } else { (second, first) };
+
+        self.with_membrane(|m| {
+            m.validate_transfer(
+                TransferLeg::new(&debit_commit, &debit_state),
+                TransferLeg::new(&credit_commit, &credit_state),
+            )
+        })
+        .map_err(|r| TransferError {
+                index: r.index,
+                error: TangencyError::Membrane(r.error),
+            })?;
+
+        let debit_entry = self
+            .insert_entry(&mut tx, debit, &debit_commit, debit_state, debit_chain.as_ref())
+            .await
+            .map_err(leg(DEBIT))?;
+        let credit_entry = self
+            .insert_entry(&mut tx, credit, &credit_commit, credit_state, credit_chain.as_ref())
+            .await
+            .map_err(leg(CREDIT))?;
+
+        tx.commit().await.map_err(db)?;
+
+        Ok((debit_entry, credit_entry))
+    }
 
-        // Compute entry_hash = blake3(container_id || sequence || atom_hash || previous_hash || ts)
+    /// Insert a validated link at the tail described by `state` (inside the caller's tx),
+    /// recording the policy chain that admitted it
+    async fn insert_entry(
+        &self,
+        tx: &mut PgConnection,
+        link: &LinkDraft,
+        commit: &LinkCommit,
+        state: LedgerState,
+        policy_chain: Option<&PolicyChain>,
+    ) -> Result<LedgerEntry, TangencyError> {
+        let expected_prev = state.last_hash;
+        let expected_seq = state.next_sequence as i64;
+
+        // SPEC-UBL-LEDGER v1.0 §5 - entry_hash is derived from the link alone,
+        // so verify_chain can recompute it (ubl_kernel::hash_entry)
         let ts_unix_ms = (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64;
-        let mut h = Hasher::new();
-        h.update(link.container_id.as_bytes());
-        h.update(expected_seq.to_string().as_bytes());
-        h.update(link.atom_hash.as_bytes());
-        h.update(expected_prev.as_bytes());
-        h.update(ts_unix_ms.to_string().as_bytes());
-        let entry_hash = hex::encode(h.finalize().as_bytes());
+        let malformed = |e: ubl_link::LinkError| TangencyError::Malformed(e.to_string());
+        let link_hash = ubl_kernel::hash_link(&commit.signing_bytes().map_err(malformed)?);
+        let entry_hash = ubl_ledger::verify::entry_hash(commit).map_err(malformed)?;
+
+        // Full link is kept in metadata so projections (balance) can be replayed,
+        // next to the policy versions that decided it (SPEC-UBL-POLICY v1.0 §8)
+        let mut metadata = serde_json::to_value(link).expect("serialize link");
+        if let Some(chain) = policy_chain {
+            metadata[POLICY_CHAIN_KEY] = serde_json::to_value(chain).expect("serialize policy chain");
+        }
 
         // Insert new entry (SPEC-UBL-LEDGER v1.0 §7.1 - Append-only)
         sqlx::query!(
             r#"
-            INSERT INTO ledger_entry (container_id, sequence, link_hash, previous_hash, entry_hash, ts_unix_ms, metadata)
-            VALUES ($1, $2, $3, $4, $5, $6, '{}'::jsonb)
+            INSERT INTO ledger_entry (container_id, sequence, link_hash, previous_hash, entry_hash, ts_unix_ms, metadata, hash_scheme)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
             "#,
             link.container_id,
             expected_seq,
-            link.atom_hash,
+            link_hash,
             expected_prev,
             entry_hash,
-            ts_unix_ms
+            ts_unix_ms,
+            metadata,
+            HASH_SCHEME_LINK
         )
         .execute(&mut *tx)
-        .await
-        .expect("insert");
+        .await?;
 
-        // Commit transaction
-        tx.commit().await.expect("commit");
+        // Checkpoint in the same transaction, so it never outlives its entry
+        if let Some((key, every)) = &self.checkpoints {
+            if expected_seq % *every as i64 == 0 {
+                let balance = state.physical_balance + commit.physics_delta;
+                save_checkpoint(tx, key, &link.container_id, expected_seq, &entry_hash, balance).await?;
+            }
+        }
 
         Ok(LedgerEntry {
             container_id: link.container_id.clone(),
             sequence: expected_seq,
-            link_hash: link.atom_hash.clone(),
+            link_hash,
             previous_hash: expected_prev,
             entry_hash,
             ts_unix_ms,
         })
     }
 
+    /// Re-derive a container's whole chain from the stored links
+    /// SPEC-UBL-LEDGER v1.0 §10 - the first broken sequence is returned as `Err`;
+    /// each row is checked under the hash scheme it was written with, and
+    /// legacy rows (which stored no link) from their own columns
+    pub async fn verify_chain(
+        &self,
+        container_id: &str,
+    ) -> Result<Result<ChainSummary, ChainBreak>, sqlx::Error> {
+        const PAGE: i64 = 1000;
+        let mut verifier = ChainVerifier::new(container_id).with_genesis(GENESIS_PREVIOUS_HASH);
+        let mut from = 1i64;
+        loop {
+            let rows = sqlx::query!(
+                r#"
+                SELECT sequence, link_hash, previous_hash, entry_hash, ts_unix_ms, metadata, hash_scheme
+                FROM ledger_entry
+                WHERE container_id = $1 AND sequence >= $2
+                ORDER BY sequence ASC
+                LIMIT $3
+                "#,
+                container_id,
+                from,
+                PAGE
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in &rows {
+                let sequence = row.sequence as u64;
+                let checked = match row.hash_scheme {
+                    HASH_SCHEME_LINK => {
+                        let link = match stored_link(row.metadata.clone()) {
+                            Ok(link) => link,
+                            Err(reason) => {
+                                return Ok(Err(ChainBreak {
+                                    sequence,
+                                    fault: ChainFault::MalformedLink(reason),
+                                }))
+                            }
+                        };
+                        verifier.check(&ubl_ledger::LedgerEntry {
+                            sequence,
+                            entry_hash: row.entry_hash.clone(),
+                            link,
+                            timestamp: row.ts_unix_ms / 1000,
+                        })
+                    }
+                    HASH_SCHEME_LEGACY => verifier.check_unlinked(
+                        sequence,
+                        &row.previous_hash,
+                        &row.entry_hash,
+                        legacy_entry_hash(
+                            container_id,
+                            row.sequence,
+                            &row.link_hash,
+                            &row.previous_hash,
+                            row.ts_unix_ms,
+                        ),
+                    ),
+                    other => {
+                        return Err(sqlx::Error::Decode(
+                            format!("unknown hash_scheme {}", other).into(),
+                        ))
+                    }
+                };
+                if let Err(broken) = checked {
+                    return Ok(Err(broken));
+                }
+            }
+            if (rows.len() as i64) < PAGE {
+                return Ok(Ok(verifier.finish()));
+            }
+            from += PAGE;
+        }
+    }
+
+    /// Latest signed checkpoint of a container
+    pub async fn latest_checkpoint(&self, container_id: &str) -> Result<Option<Checkpoint>, sqlx::Error> {
+        let rec = sqlx::query!(
+            r#"
+            SELECT sequence, last_hash, physical_balance, merkle_root, signer, signature
+            FROM ledger_checkpoint
+            WHERE container_id = $1
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+            container_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        rec.map(|r| {
+            Ok(Checkpoint {
+                container_id: container_id.to_string(),
+                sequence: r.sequence as u64,
+                last_hash: r.last_hash,
+                physical_balance: decode_balance(&r.physical_balance)?,
+                merkle_root: r.merkle_root,
+                signer: r.signer,
+                signature: r.signature,
+            })
+        })
+        .transpose()
+    }
+
     /// Get current state of container
     pub async fn get_state(&self, container_id: &str) -> Result<LedgerEntry, sqlx::Error> {
         let rec = sqlx::query!(
@@ -163,3 +728,479 @@ impl PgLedger {
         })
     }
 }
+
+/// Sign and store a checkpoint of the tail just appended (inside the append tx)
+/// The Merkle root extends the previous checkpoint's frontier, so only the
+/// entries since that checkpoint are read
+async fn save_checkpoint(
+    conn: &mut PgConnection,
+    key: &SigningKey,
+    container_id: &str,
+    sequence: i64,
+    last_hash: &str,
+    physical_balance: i128,
+) -> Result<(), sqlx::Error> {
+    let corrupt = |reason: String| {
+        sqlx::Error::Decode(format!("checkpoint frontier of {}: {}", container_id, reason).into())
+    };
+    let previous = sqlx::query!(
+        r#"
+        SELECT sequence, merkle_root, merkle_frontier
+        FROM ledger_checkpoint
+        WHERE container_id = $1
+        ORDER BY sequence DESC
+        LIMIT 1
+        "#,
+        container_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    // Without an earlier frontier (none yet, or stored before sql/008) start from genesis
+    let mut frontier = match previous.and_then(|p| Some((p.sequence, p.merkle_root, p.merkle_frontier?))) {
+        Some((at, merkle_root, peaks)) => {
+            let peaks: Vec<String> = serde_json::from_value(peaks).map_err(|e| corrupt(e.to_string()))?;
+            let frontier = Frontier::from_hex(at as u64, &peaks).map_err(|e| corrupt(e.to_string()))?;
+            if ubl_ledger::merkle::to_hex(&frontier.root()) != merkle_root {
+                return Err(corrupt("does not match its merkle_root".into()));
+            }
+            frontier
+        }
+        None => Frontier::default(),
+    };
+
+    let hashes = sqlx::query_scalar!(
+        r#"
+        SELECT entry_hash
+        FROM ledger_entry
+        WHERE container_id = $1 AND sequence > $2 AND sequence <= $3
+        ORDER BY sequence ASC
+        "#,
+        container_id,
+        frontier.size() as i64,
+        sequence
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    for hash in &hashes {
+        frontier.push(ubl_ledger::merkle::leaf_from_entry_hash(hash));
+    }
+    if frontier.size() != sequence as u64 {
+        return Err(corrupt(format!("covers {} entries, chain has {}", frontier.size(), sequence)));
+    }
+
+    let checkpoint = Checkpoint::sign(
+        ubl_ledger::LedgerState {
+            container_id: container_id.to_string(),
+            sequence: sequence as u64,
+            last_hash: last_hash.to_string(),
+            physical_balance,
+            merkle_root: ubl_ledger::merkle::to_hex(&frontier.root()),
+        },
+        key,
+    );
+
+    sqlx::query!(
+        r#"
+        INSERT INTO ledger_checkpoint (container_id, sequence, last_hash, physical_balance, merkle_root, merkle_frontier, signer, signature)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#,
+        checkpoint.container_id,
+        checkpoint.sequence as i64,
+        checkpoint.last_hash,
+        checkpoint.physical_balance.to_string(),
+        checkpoint.merkle_root,
+        serde_json::Value::from(frontier.to_hex()),
+        checkpoint.signer,
+        checkpoint.signature
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// Latest checkpoint of a container, if `signer` signed it and its `last_hash`
+/// is still the stored hash of the entry at its sequence (SPEC-UBL-LEDGER v1.0 §8.2)
+async fn trusted_checkpoint(
+    conn: &mut PgConnection,
+    container_id: &str,
+    signer: Option<&str>,
+) -> Result<Option<Checkpoint>, sqlx::Error> {
+    let Some(signer) = signer else {
+        return Ok(None);
+    };
+    let Some(r) = sqlx::query!(
+        r#"
+        SELECT c.sequence, c.last_hash, c.physical_balance, c.merkle_root, c.signer, c.signature,
+               e.entry_hash AS "entry_hash?"
+        FROM ledger_checkpoint c
+        LEFT JOIN ledger_entry e ON e.container_id = c.container_id AND e.sequence = c.sequence
+        WHERE c.container_id = $1
+        ORDER BY c.sequence DESC
+        LIMIT 1
+        "#,
+        container_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let checkpoint = Checkpoint {
+        container_id: container_id.to_string(),
+        sequence: r.sequence as u64,
+        last_hash: r.last_hash,
+        physical_balance: decode_balance(&r.physical_balance)?,
+        merkle_root: r.merkle_root,
+        signer: r.signer,
+        signature: r.signature,
+    };
+    if let Err(e) = checkpoint.verify(signer) {
+        tracing::warn!("ignoring checkpoint {} seq={}: {}", container_id, checkpoint.sequence, e);
+        return Ok(None);
+    }
+    if r.entry_hash.as_deref() != Some(checkpoint.last_hash.as_str()) {
+        tracing::warn!(
+            "ignoring checkpoint {} seq={}: last_hash does not match the stored entry",
+            container_id,
+            checkpoint.sequence
+        );
+        return Ok(None);
+    }
+    Ok(Some(checkpoint))
+}
+
+/// entry_hash of a `HASH_SCHEME_LEGACY` row, from its columns: such rows stored
+/// the atom_hash as `link_hash` and also hashed their insert time
+fn legacy_entry_hash(
+    container_id: &str,
+    sequence: i64,
+    atom_hash: &str,
+    previous_hash: &str,
+    ts_unix_ms: i64,
+) -> String {
+    let mut h = blake3::Hasher::new();
+    h.update(container_id.as_bytes());
+    h.update(sequence.to_string().as_bytes());
+    h.update(atom_hash.as_bytes());
+    h.update(previous_hash.as_bytes());
+    h.update(ts_unix_ms.to_string().as_bytes());
+    hex::encode(h.finalize().as_bytes())
+}
+
+/// Decode the LinkDraft kept in an entry's metadata
+fn stored_link(metadata: serde_json::Value) -> Result<LinkCommit, String> {
+    let draft: LinkDraft = serde_json::from_value(metadata).map_err(|e| e.to_string())?;
+    draft.to_commit().map_err(|e| match e {
+        TangencyError::Malformed(msg) => msg,
+        TangencyError::Membrane(e) => e.to_string(),
+        TangencyError::Pact(e) => e.to_string(),
+        TangencyError::Policy(e) => e.to_string(),
+        TangencyError::Database(e) => e.to_string(),
+    })
+}
+
+/// Decode a stored i128 balance; a corrupt value is an error, never zero
+fn decode_balance(text: &str) -> Result<i128, sqlx::Error> {
+    text.parse()
+        .map_err(|e| sqlx::Error::Decode(format!("balance {:?}: {}", text, e).into()))
+}
+
+/// Project the membrane's LedgerState for a container from Postgres
+/// `lock` takes the tail row FOR UPDATE (commit path); the dry-run reads without locking
+/// Only checkpoints signed by `signer` are resumed from
+async fn membrane_state(
+    conn: &mut PgConnection,
+    container_id: &str,
+    lock: bool,
+    signer: Option<&str>,
+) -> Result<LedgerState, sqlx::Error> {
+    let tail = if lock {
+        sqlx::query!(
+            r#"
+            SELECT sequence, entry_hash
+            FROM ledger_entry
+            WHERE container_id = $1
+            ORDER BY sequence DESC
+            LIMIT 1
+            FOR UPDATE
+            "#,
+            container_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(|r| (r.sequence, r.entry_hash))
+    } else {
+        sqlx::query!(
+            r#"
+            SELECT sequence, entry_hash
+            FROM ledger_entry
+            WHERE container_id = $1
+            ORDER BY sequence DESC
+            LIMIT 1
+            "#,
+            container_id
+        )
+        .fetch_optional(&mut *conn)
+        .await?
+        .map(|r| (r.sequence, r.entry_hash))
+    };
+
+    // Resume from the latest verified checkpoint instead of summing from genesis
+    let (from, checkpoint_balance) = trusted_checkpoint(conn, container_id, signer)
+        .await?
+        .map_or((0, 0), |c| (c.sequence as i64, c.physical_balance));
+
+    // Balance = checkpoint + Σ physics_delta after it (entries without a recorded link count as Δ=0)
+    let tail_balance = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(SUM((metadata->>'physics_delta')::numeric), 0)::text AS "balance!"
+        FROM ledger_entry
+        WHERE container_id = $1 AND sequence > $2
+        "#,
+        container_id,
+        from
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let (last_hash, next_sequence) = match tail {
+        Some((sequence, entry_hash)) => (entry_hash, sequence as u64 + 1),
+        None => (GENESIS_PREVIOUS_HASH.to_string(), 1),
+    };
+
+    Ok(LedgerState {
+        container_id: container_id.to_string(),
+        last_hash,
+        next_sequence,
+        physical_balance: checkpoint_balance + decode_balance(&tail_balance)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ubl_membrane::PhysicsRules;
+
+    /// A ledger whose `wallet/` containers are governed by the `wallet` policy
+    fn governed() -> PgLedger {
+        governed_on(PgPool::connect_lazy("postgres://localhost/unused").unwrap())
+    }
+
+    fn governed_on(pool: PgPool) -> PgLedger {
+        let rules = ContainerRules::standard()
+            .with_prefix("wallet/", PhysicsRules::default().with_policies(["wallet"]));
+        let ledger = PgLedger::new(pool).with_rules(rules);
+        let policy = Policy::from_source(
+            "wallet",
+            1,
+            "small payments",
+            r#"rule small when intent.amount <= 500 allow Conservation constrain max_delta = 500
+               rule big deny "too large""#,
+        )
+        .unwrap();
+        ledger.policies.write().unwrap().register(policy).unwrap();
+        ledger
+    }
+
+    /// A draft for `atom`, binding `max_delta = 500` if `bound`
+    fn draft(container_id: &str, class: &str, atom: Option<serde_json::Value>, bound: bool) -> LinkDraft {
+        let constraints: &[(&str, &str)] = if bound { &[("max_delta", "500")] } else { &[] };
+        serde_json::from_value(serde_json::json!({
+            "version": ubl_link::LINK_V3,
+            "container_id": container_id,
+            "expected_sequence": 1,
+            "previous_hash": GENESIS_PREVIOUS_HASH,
+            "atom_hash": atom.as_ref().map_or("ab".repeat(32), |a| ubl_atom::hash_value(a).unwrap()),
+            "intent_class": class,
+            "physics_delta": "0",
+            "constraints": constraints
+                .iter()
+                .map(|(kind, value)| serde_json::json!({"kind": kind, "value": value}))
+                .collect::<Vec<_>>(),
+            "author_pubkey": "alice",
+            "signature": "",
+            "atom": atom,
+        }))
+        .unwrap()
+    }
+
+    /// Pool for `DATABASE_URL` (with `sql/` applied); `None` skips the test
+    async fn database() -> Option<PgPool> {
+        PgPool::connect(&std::env::var("DATABASE_URL").ok()?).await.ok()
+    }
+
+    /// Insert `count` rows the way the baseline append wrote them: legacy
+    /// hashes, `link_hash` = atom_hash and no link in `metadata`. The row at
+    /// `forged` stores an atom_hash other than the one it was hashed with.
+    async fn insert_baseline_rows(
+        pool: &PgPool,
+        count: i64,
+        forged: Option<i64>,
+    ) -> (String, String) {
+        let container_id = format!("legacy/{}", hex::encode(rand::random::<[u8; 6]>()));
+        let mut previous_hash = GENESIS_PREVIOUS_HASH.to_string();
+        for sequence in 1..=count {
+            let atom_hash = format!("{:064x}", sequence);
+            let ts_unix_ms = 1_700_000_000_000 + sequence;
+            let entry_hash =
+                legacy_entry_hash(&container_id, sequence, &atom_hash, &previous_hash, ts_unix_ms);
+            let stored = if forged == Some(sequence) { "f".repeat(64) } else { atom_hash };
+            sqlx::query(
+                "INSERT INTO ledger_entry (container_id, sequence, link_hash, previous_hash, entry_hash, ts_unix_ms, metadata, hash_scheme)
+                 VALUES ($1, $2, $3, $4, $5, $6, '{}'::jsonb, $7)",
+            )
+            .bind(&container_id)
+            .bind(sequence)
+            .bind(stored)
+            .bind(&previous_hash)
+            .bind(&entry_hash)
+            .bind(ts_unix_ms)
+            .bind(HASH_SCHEME_LEGACY)
+            .execute(pool)
+            .await
+            .unwrap();
+            previous_hash = entry_hash;
+        }
+        (container_id, previous_hash)
+    }
+
+    #[tokio::test]
+    async fn test_verifies_baseline_rows_from_their_columns() {
+        let Some(pool) = database().await else {
+            return;
+        };
+        let ledger = PgLedger::new(pool.clone());
+
+        let (container_id, last_hash) = insert_baseline_rows(&pool, 3, None).await;
+        let summary = ledger.verify_chain(&container_id).await.unwrap().unwrap();
+        assert_eq!((summary.entries, summary.last_hash), (3, last_hash));
+
+        let (forged, _) = insert_baseline_rows(&pool, 3, Some(2)).await;
+        let broken = ledger.verify_chain(&forged).await.unwrap().unwrap_err();
+        assert_eq!(broken.sequence, 2);
+        assert!(matches!(broken.fault, ChainFault::EntryHash { .. }));
+    }
+
+    fn check(ledger: &PgLedger, link: &LinkDraft) -> Result<(), TangencyError> {
+        ledger.check_policies(link, &link.to_commit().unwrap()).map(drop)
+    }
+
+    #[tokio::test]
+    async fn test_links_bind_their_policy_decision() {
+        let ledger = governed();
+        let small = Some(serde_json::json!({"amount": 100}));
+
+        assert!(check(&ledger, &draft("wallet/a", "Conservation", small.clone(), true)).is_ok());
+        assert!(matches!(
+            check(&ledger, &draft("wallet/a", "Conservation", small.clone(), false)),
+            Err(TangencyError::Membrane(MembraneError::PhysicsViolation { .. }))
+        ));
+        assert!(matches!(
+            check(&ledger, &draft("wallet/a", "Entropy", small.clone(), true)),
+            Err(TangencyError::Policy(PolicyError::Denied(_)))
+        ));
+        let large = Some(serde_json::json!({"amount": 900}));
+        assert!(matches!(
+            check(&ledger, &draft("wallet/a", "Conservation", large, true)),
+            Err(TangencyError::Policy(PolicyError::Denied(reason))) if reason == "wallet: too large"
+        ));
+
+        // The atom is what the policy judged, so it must be there and match
+        assert!(matches!(
+            check(&ledger, &draft("wallet/a", "Conservation", None, true)),
+            Err(TangencyError::Malformed(_))
+        ));
+        let mut swapped = draft("wallet/a", "Conservation", small, true);
+        swapped.atom = Some(serde_json::json!({"amount": 1}));
+        assert!(matches!(check(&ledger, &swapped), Err(TangencyError::Malformed(_))));
+
+        // Ungoverned containers are not evaluated
+        assert!(check(&ledger, &draft("notes/a", "Observation", None, false)).is_ok());
+    }
+
+    /// Append a signed draft for a payment of 100 after `previous`
+    async fn append_signed(
+        ledger: &PgLedger,
+        container_id: &str,
+        (pubkey, key): &(String, SigningKey),
+        previous: Option<&LedgerEntry>,
+    ) -> LedgerEntry {
+        let atom = serde_json::json!({"amount": 100});
+        let mut link = draft(container_id, "Conservation", Some(atom), true);
+        if let Some(previous) = previous {
+            link.expected_sequence = previous.sequence + 1;
+            link.previous_hash = previous.entry_hash.clone();
+        }
+        link.author_pubkey = pubkey.clone();
+        let commit = link.to_commit().unwrap();
+        link.signature = ubl_kernel::sign(key, &commit.signing_bytes().unwrap());
+        ledger.append(&link).await.unwrap()
+    }
+
+    /// The policy chain stored with an entry
+    async fn recorded_chain(pool: &PgPool, entry: &LedgerEntry) -> PolicyChain {
+        let chain = sqlx::query_scalar::<_, serde_json::Value>(
+            "SELECT metadata->'policy_chain' FROM ledger_entry WHERE container_id = $1 AND sequence = $2",
+        )
+        .bind(&entry.container_id)
+        .bind(entry.sequence)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        serde_json::from_value(chain).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_entries_record_the_policy_chain() {
+        let Some(pool) = database().await else {
+            return;
+        };
+        let ledger = governed_on(pool.clone());
+        let container_id = format!("wallet/{}", hex::encode(rand::random::<[u8; 6]>()));
+        let author = ubl_kernel::generate_keypair();
+
+        let first = append_signed(&ledger, &container_id, &author, None).await;
+        let v2 = Policy::from_source(
+            "wallet",
+            2,
+            "larger payments",
+            r#"rule small when intent.amount <= 1000 allow Conservation constrain max_delta = 500"#,
+        )
+        .unwrap();
+        let upgraded = ledger.with_policy_bundle(&PolicyBundle::new([v2.clone()])).unwrap();
+        *ledger.policies.write().unwrap() = upgraded;
+        let second = append_signed(&ledger, &container_id, &author, Some(&first)).await;
+
+        // Each entry keeps the version that admitted it, across the upgrade
+        let v1 = ledger.policy_history("wallet").remove(0);
+        let pinned = |chain: PolicyChain| {
+            let policy = &chain.policies[0];
+            (policy.id.clone(), policy.version, policy.bytecode_hash.clone())
+        };
+        assert_eq!(
+            pinned(recorded_chain(&pool, &first).await),
+            ("wallet".to_string(), 1, v1.bytecode_hash)
+        );
+        assert_eq!(
+            pinned(recorded_chain(&pool, &second).await),
+            ("wallet".to_string(), 2, v2.bytecode_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bundles_publish_to_a_copy() {
+        let ledger = governed();
+        let v2 = Policy::from_source("wallet", 2, "closed", r#"rule all deny "closed""#).unwrap();
+        let next = ledger.with_policy_bundle(&PolicyBundle::new([v2.clone()])).unwrap();
+        assert_eq!(next.history("wallet").len(), 2);
+        assert_eq!(ledger.policy_history("wallet").len(), 1);
+
+        *ledger.policies.write().unwrap() = next;
+        assert!(matches!(
+            ledger.with_policy_bundle(&PolicyBundle::new([v2])),
+            Err(TangencyError::Policy(PolicyError::StaleVersion { latest: 2, .. }))
+        ));
+    }
+}
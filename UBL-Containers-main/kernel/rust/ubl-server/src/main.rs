@@ -9,7 +9,10 @@
 //! - GET  /state/:container_id  
 //! - POST /link/validate
 //! - POST /link/commit
+//! - POST /link/pending (collect pact signatures, auto-commit on quorum)
 //! - GET  /ledger/:container_id/tail (SSE with LISTEN/NOTIFY)
+//! - GET  /ledger/:container_id/checkpoint (latest signed checkpoint)
+//! - GET  /admin/ledger/:container_id/verify (step-up session)
 //! - POST /id/agents (create LLM/App)
 //! - POST /id/agents/{sid}/asc (issue ASC)
 //! - POST /id/agents/{sid}/rotate (rotate key)
@@ -26,19 +29,25 @@ mod id_ledger;
 mod id_session_token;
 mod repo_routes;
 mod middleware_require_stepup;
+mod pending;
 
 use axum::{
     extract::{Path, State},
     http::{StatusCode, HeaderMap},
+    middleware::from_fn_with_state,
     response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
-use db::{LedgerEntry, LinkDraft, PgLedger, TangencyError};
-use serde::Serialize;
+use db::{LedgerEntry, LinkDraft, PgLedger, TangencyError, TransferError};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use ubl_ledger::{ChainBreak, ChainSummary, Checkpoint};
+use ubl_membrane::MembraneError;
+use ubl_pact::{Pact, PactRegistry, PactVersion};
+use ubl_policy_vm::{Policy, PolicyError};
 use webauthn_rs::prelude::*;
 
 // ============================================================================
@@ -64,6 +73,10 @@ struct HealthResponse {
 #[derive(Serialize)]
 struct Decision {
     decision: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -72,6 +85,26 @@ struct CommitSuccess {
     entry: LedgerEntry,
 }
 
+#[derive(Deserialize)]
+struct TransferRequest {
+    debit: LinkDraft,
+    credit: LinkDraft,
+}
+
+#[derive(Serialize)]
+struct TransferSuccess {
+    ok: bool,
+    debit: LedgerEntry,
+    credit: LedgerEntry,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum ChainReport {
+    Intact(ChainSummary),
+    Broken(ChainBreak),
+}
+
 #[derive(Serialize)]
 struct StateResponse {
     container_id: String,
@@ -127,17 +160,67 @@ async fn route_state(
     }
 }
 
+/// Map a membrane rejection to its HTTP status and canonical code
+fn membrane_rejection(err: &MembraneError) -> (StatusCode, String) {
+    let status = match err {
+        MembraneError::RealityDrift | MembraneError::SequenceMismatch => StatusCode::CONFLICT,
+        MembraneError::InvalidVersion | MembraneError::InvalidTarget => StatusCode::BAD_REQUEST,
+        MembraneError::InvalidSignature => StatusCode::UNAUTHORIZED,
+        MembraneError::PhysicsViolation { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        MembraneError::PactViolation | MembraneError::UnauthorizedEvolution => StatusCode::FORBIDDEN,
+    };
+    (status, err.code().to_string())
+}
+
+/// Map a policy rejection to its HTTP status: a governing policy denying the
+/// link is forbidden, a bundle that does not publish conflicts
+fn policy_rejection(err: &PolicyError) -> (StatusCode, String) {
+    let status = match err {
+        PolicyError::Denied(_) => StatusCode::FORBIDDEN,
+        _ => StatusCode::CONFLICT,
+    };
+    (status, err.to_string())
+}
+
+/// Log a database failure and hide its details from the client
+fn db_failure(action: &str, e: sqlx::Error) -> (StatusCode, String) {
+    error!("❌ {} DB ERROR: {}", action, e);
+    (StatusCode::INTERNAL_SERVER_ERROR, "db error".to_string())
+}
+
 /// POST /link/validate
-/// Basic validation - in production, inject full Membrane here
+/// Dry-run of the full Membrane (SPEC-UBL-MEMBRANE v1.0 §6) against the Postgres state
 async fn route_validate(
-    State(_state): State<AppState>,
-    Json(_link): Json<LinkDraft>,
-) -> Json<Decision> {
-    // TODO: Apply SPEC-UBL-MEMBRANE v1.0 §V1-V9 validations
-    // For now, simplified validation
-    Json(Decision {
-        decision: "Accept",
-    })
+    State(state): State<AppState>,
+    Json(link): Json<LinkDraft>,
+) -> Result<Json<Decision>, (StatusCode, String)> {
+    match state.ledger.validate(&link).await {
+        Ok(()) => Ok(Json(Decision {
+            decision: "Accept",
+            error: None,
+            reason: None,
+        })),
+        Err(TangencyError::Membrane(e)) => Ok(Json(Decision {
+            decision: "Reject",
+            error: Some(e.code()),
+            reason: Some(e.to_string()),
+        })),
+        Err(TangencyError::Pact(e)) => Ok(Json(Decision {
+            decision: "Reject",
+            error: Some("PactLifecycle"),
+            reason: Some(e.to_string()),
+        })),
+        Err(TangencyError::Policy(e)) => Ok(Json(Decision {
+            decision: "Reject",
+            error: Some(match e {
+                PolicyError::Denied(_) => "PolicyDenied",
+                _ => "PolicyBundle",
+            }),
+            reason: Some(e.to_string()),
+        })),
+        Err(TangencyError::Malformed(msg)) => Err((StatusCode::BAD_REQUEST, msg)),
+        Err(TangencyError::Database(e)) => Err(db_failure("VALIDATE", e)),
+    }
 }
 
 /// POST /link/commit
@@ -196,21 +279,129 @@ async fn route_commit(
                 entry,
             }))
         }
-        Err(TangencyError::RealityDrift) => {
-            error!("❌ REJECTED: RealityDrift");
-            Err((StatusCode::CONFLICT, "RealityDrift".into()))
+        Err(TangencyError::Membrane(e)) => {
+            error!("❌ REJECTED: {}", e);
+            Err(membrane_rejection(&e))
         }
-        Err(TangencyError::SequenceMismatch) => {
-            error!("❌ REJECTED: SequenceMismatch");
-            Err((StatusCode::CONFLICT, "SequenceMismatch".into()))
+        Err(TangencyError::Pact(e)) => {
+            error!("❌ PACT EVENT REJECTED: {}", e);
+            Err((StatusCode::CONFLICT, e.to_string()))
         }
-        Err(TangencyError::InvalidVersion) => {
-            error!("❌ REJECTED: InvalidVersion");
-            Err((StatusCode::BAD_REQUEST, "InvalidVersion".into()))
+        Err(TangencyError::Policy(e)) => {
+            error!("❌ POLICY REJECTED: {}", e);
+            Err(policy_rejection(&e))
         }
-        Err(TangencyError::InvalidTarget) => {
-            error!("❌ REJECTED: InvalidTarget");
-            Err((StatusCode::BAD_REQUEST, "InvalidTarget".into()))
+        Err(TangencyError::Malformed(msg)) => {
+            error!("❌ MALFORMED: {}", msg);
+            Err((StatusCode::BAD_REQUEST, msg))
+        }
+        Err(TangencyError::Database(e)) => Err(db_failure("COMMIT", e)),
+    }
+}
+
+/// GET /pacts/:pact_id
+/// Every version of a pact, as projected from C.Pacts
+async fn route_pact_history(
+    State(state): State<AppState>,
+    Path(pact_id): Path<String>,
+) -> Result<Json<Vec<PactVersion>>, (StatusCode, String)> {
+    let history = state.ledger.pact_history(&pact_id);
+    if history.is_empty() {
+        return Err((StatusCode::NOT_FOUND, "unknown pact".to_string()));
+    }
+    Ok(Json(history))
+}
+
+/// GET /policies/:policy_id
+/// Every published version of a policy, as replayed from C.Policy
+async fn route_policy_history(
+    State(state): State<AppState>,
+    Path(policy_id): Path<String>,
+) -> Result<Json<Vec<Policy>>, (StatusCode, String)> {
+    let history = state.ledger.policy_history(&policy_id);
+    if history.is_empty() {
+        return Err((StatusCode::NOT_FOUND, "unknown policy".to_string()));
+    }
+    Ok(Json(history))
+}
+
+/// POST /link/transfer
+/// Paired Conservation across two containers, appended in one SERIALIZABLE transaction
+async fn route_transfer(
+    State(state): State<AppState>,
+    Json(req): Json<TransferRequest>,
+) -> Result<Json<TransferSuccess>, (StatusCode, String)> {
+    info!(
+        "💸 TRANSFER {} -> {} delta={}",
+        req.debit.container_id, req.credit.container_id, req.credit.physics_delta
+    );
+
+    match state.ledger.transfer(&req.debit, &req.credit).await {
+        Ok((debit, credit)) => {
+            info!(
+                "✅ TRANSFER ACCEPTED debit_seq={} credit_seq={}",
+                debit.sequence, credit.sequence
+            );
+            Ok(Json(TransferSuccess { ok: true, debit, credit }))
+        }
+        Err(TransferError { index, error }) => {
+            let leg = if index == 0 { "debit" } else { "credit" };
+            match error {
+                TangencyError::Membrane(e) => {
+                    error!("❌ TRANSFER REJECTED ({}): {}", leg, e);
+                    let (status, code) = membrane_rejection(&e);
+                    Err((status, format!("{}: {}", leg, code)))
+                }
+                TangencyError::Malformed(msg) => {
+                    error!("❌ MALFORMED ({}): {}", leg, msg);
+                    Err((StatusCode::BAD_REQUEST, format!("{}: {}", leg, msg)))
+                }
+                TangencyError::Pact(e) => Err((StatusCode::CONFLICT, format!("{}: {}", leg, e))),
+                TangencyError::Policy(e) => {
+                    let (status, reason) = policy_rejection(&e);
+                    Err((status, format!("{}: {}", leg, reason)))
+                }
+                TangencyError::Database(e) => Err(db_failure("TRANSFER", e)),
+            }
+        }
+    }
+}
+
+/// GET /ledger/:container_id/checkpoint
+/// Latest signed checkpoint, for nodes restoring without a full replay
+async fn route_checkpoint(
+    State(state): State<AppState>,
+    Path(container_id): Path<String>,
+) -> Result<Json<Checkpoint>, (StatusCode, String)> {
+    match state.ledger.latest_checkpoint(&container_id).await {
+        Ok(Some(checkpoint)) => Ok(Json(checkpoint)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "no checkpoint".to_string())),
+        Err(e) => {
+            error!("❌ CHECKPOINT LOOKUP FAILED container={}: {}", container_id, e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "db error".to_string()))
+        }
+    }
+}
+
+/// GET /admin/ledger/:container_id/verify
+/// Recompute every entry hash and signature (SPEC-UBL-LEDGER v1.0 §10)
+async fn route_verify_chain(
+    State(state): State<AppState>,
+    Path(container_id): Path<String>,
+) -> Result<Json<ChainReport>, (StatusCode, String)> {
+    let report = state.ledger.verify_chain(&container_id).await.map_err(|e| {
+        error!("❌ VERIFY FAILED container={}: {}", container_id, e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "db error".to_string())
+    })?;
+
+    match report {
+        Ok(summary) => {
+            info!("✅ CHAIN INTACT container={} entries={}", container_id, summary.entries);
+            Ok(Json(ChainReport::Intact(summary)))
+        }
+        Err(broken) => {
+            warn!("⚠️  CHAIN BROKEN container={}: {}", container_id, broken);
+            Ok(Json(ChainReport::Broken(broken)))
         }
     }
 }
@@ -250,8 +441,58 @@ async fn main() -> anyhow::Result<()> {
     let pool = PgPool::connect(&database_url).await?;
     info!("✅ PostgreSQL connected");
 
+    // Per-container physics (JSON `ContainerRules`); defaults to the standard rules
+    let mut ledger = PgLedger::new(pool.clone());
+    if let Ok(path) = std::env::var("UBL_CONTAINER_RULES") {
+        let rules = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        info!("📐 Container rules loaded from {}", path);
+        ledger = ledger.with_rules(rules);
+    }
+
+    // Genesis pacts (JSON array of `Pact`), in force before any C.Pacts history
+    if let Ok(path) = std::env::var("UBL_GENESIS_PACTS") {
+        let pacts: Vec<Pact> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        let mut genesis = PactRegistry::new();
+        info!("🤝 {} genesis pacts loaded from {}", pacts.len(), path);
+        pacts.into_iter().for_each(|p| genesis.register(p));
+        ledger = ledger.with_pacts(genesis);
+    }
+    let replayed = ledger
+        .load_pacts()
+        .await
+        .map_err(|e| anyhow::anyhow!("C.Pacts does not replay: {:?}", e))?;
+    info!("🤝 {} pact events replayed from C.Pacts", replayed);
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    if ledger.with_registry(|registry| registry.is_empty_at(now)) {
+        warn!("⚠️  No pact in force: every Entropy and Evolution commit will be rejected (set UBL_GENESIS_PACTS)");
+    }
+    let published = ledger
+        .load_policies()
+        .await
+        .map_err(|e| anyhow::anyhow!("C.Policy does not replay: {:?}", e))?;
+    info!("📜 {} policy bundles replayed from C.Policy", published);
+
+    // Signed checkpoints (hex Ed25519 seed); projections resume from the latest one
+    if let Ok(seed) = std::env::var("UBL_CHECKPOINT_KEY") {
+        let key = ubl_kernel::signing_key_from_hex(&seed)?;
+        let every = std::env::var("UBL_CHECKPOINT_EVERY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1000);
+        info!(
+            "📌 Checkpoints every {} entries, signer {}",
+            every,
+            ubl_kernel::pubkey_from_signing_key(&key)
+        );
+        ledger = ledger.with_checkpoints(key, every);
+    }
+
+    // Links awaiting pact signatures expire when their pact window closes,
+    // and commits interrupted mid-append are settled against the ledger
+    tokio::spawn(pending::sweep(pool.clone(), ledger.clone()));
+
     let state = AppState {
-        ledger: PgLedger::new(pool.clone()),
+        ledger,
         pool: pool.clone(),
     };
 
@@ -284,18 +525,30 @@ async fn main() -> anyhow::Result<()> {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    // Admin routes require a step-up admin session
+    let admin = Router::new()
+        .route("/admin/ledger/:container_id/verify", get(route_verify_chain))
+        .route_layer(from_fn_with_state(id_state.clone(), auth::require_stepup::require_stepup))
+        .with_state(state.clone());
+
     // Build router
     let app = Router::new()
         .route("/health", get(route_health))
         .route("/state/:container_id", get(route_state))
         .route("/link/validate", post(route_validate))
         .route("/link/commit", post(route_commit))
+        .route("/link/transfer", post(route_transfer))
         .route("/ledger/:container_id/tail", get(route_tail))
+        .route("/ledger/:container_id/checkpoint", get(route_checkpoint))
+        .route("/pacts/:pact_id", get(route_pact_history))
+        .route("/policies/:policy_id", get(route_policy_history))
         .route("/metrics", get(metrics::metrics_handler))
         .with_state(state.clone())
         .merge(id_routes::id_router().with_state(id_state))
         .merge(id_session_token::router().with_state(state.clone()))
         .merge(repo_routes::router().with_state(state.clone()))
+        .merge(pending::router().with_state(state.clone()))
+        .merge(admin)
         .layer(cors);
 
     let port = std::env::var("PORT").unwrap_or_else(|_| "8080".to_string());
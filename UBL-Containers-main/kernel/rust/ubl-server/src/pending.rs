@@ -0,0 +1,797 @@
+//! Asynchronous pact signature collection (SPEC-UBL-PACT v1.0 §7)
+//!
+//! A high-risk link is registered once, signed by its author but without a
+//! `PactProof`. Signers fetch it, sign its `PactSubject::signing_bytes` and
+//! submit the signature; every read reports progress toward the pact's
+//! threshold and quorum. As soon as the pact is satisfied the link is
+//! committed through the regular append path. A link still pending when the
+//! window of the pact version in force closes expires; an amendment that
+//! moves the window moves the deadline with it.
+//!
+//! The author signed `expected_sequence` and `previous_hash`, so a container
+//! that moves while signatures are collected fails the link with the
+//! membrane's error; the author re-registers it against the new tail.
+//!
+//! A link stays `committing` while it is appended. If the server stops (or
+//! Postgres fails) before the outcome is recorded, the sweep settles the
+//! claim against the ledger: `committed` if the entry exists, else back to
+//! `pending`, to be committed by the next signature submitted.
+//!
+//! Rotas:
+//! - POST /link/pending
+//! - GET  /link/pending/:pending_id
+//! - POST /link/pending/:pending_id/signatures
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use time::OffsetDateTime;
+use tracing::{error, info, warn};
+use ubl_link::{LinkCommit, PactProof, PactSignature};
+use ubl_membrane::MembraneError;
+use ubl_pact::{Pact, PactError, PactSubject, UnmetClause};
+
+use crate::db::{LinkDraft, PgLedger, TangencyError};
+use crate::AppState;
+
+/// How often expired links and interrupted commits are swept
+const SWEEP_EVERY: Duration = Duration::from_secs(30);
+
+/// Seconds after which a `committing` claim is taken to be interrupted
+const STALE_CLAIM: i64 = 60;
+
+#[derive(Debug, Deserialize)]
+pub struct PendingRequest {
+    pub pact_id: String,
+    pub link: LinkDraft,
+}
+
+/// Signatures counted against the pact version in force now
+#[derive(Debug, Serialize)]
+pub struct Progress {
+    pub signatures: usize,
+    pub threshold: usize,
+    /// Quorum clauses still unmet (empty without a quorum expression)
+    pub unmet: Vec<UnmetClause>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PendingLink {
+    pub pending_id: String,
+    pub pact_id: String,
+    pub container_id: String,
+    /// pending | committing | committed | expired | failed
+    pub status: String,
+    pub expires_at: i64,
+    /// Hex of the message each signer signs
+    pub signing_bytes: String,
+    /// Signers whose signatures were accepted
+    pub signers: Vec<String>,
+    /// `None` once the pact is no longer in force
+    pub progress: Option<Progress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip)]
+    draft: serde_json::Value,
+    #[serde(skip)]
+    signatures: Vec<PactSignature>,
+}
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/link/pending", post(route_register))
+        .route("/link/pending/:pending_id", get(route_pending))
+        .route("/link/pending/:pending_id/signatures", post(route_sign))
+}
+
+type ApiError = (StatusCode, String);
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn db_error(e: sqlx::Error) -> ApiError {
+    error!("❌ PENDING DB ERROR: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, "db error".to_string())
+}
+
+/// A stored row that no longer decodes
+fn corrupt(pending_id: &str, what: impl std::fmt::Display) -> ApiError {
+    error!("❌ PENDING {} CORRUPT: {}", pending_id, what);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "corrupt pending link".to_string(),
+    )
+}
+
+/// The draft stored with a pending link, and its commit
+fn stored_link(
+    pending_id: &str,
+    draft: &serde_json::Value,
+) -> Result<(LinkDraft, LinkCommit), ApiError> {
+    let draft: LinkDraft =
+        serde_json::from_value(draft.clone()).map_err(|e| corrupt(pending_id, e))?;
+    let commit = draft
+        .to_commit()
+        .map_err(|e| corrupt(pending_id, describe(&e)))?;
+    Ok((draft, commit))
+}
+
+fn pact_rejection(e: PactError) -> ApiError {
+    let status = match e {
+        PactError::UnknownPact(_) => StatusCode::NOT_FOUND,
+        PactError::UnauthorizedSigner(_) => StatusCode::FORBIDDEN,
+        PactError::InvalidSignature(_) => StatusCode::UNAUTHORIZED,
+        _ => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    (status, e.to_string())
+}
+
+fn describe(e: &TangencyError) -> String {
+    match e {
+        TangencyError::Membrane(e) => e.code().to_string(),
+        TangencyError::Pact(e) => e.to_string(),
+        TangencyError::Policy(e) => e.to_string(),
+        TangencyError::Malformed(msg) => msg.clone(),
+        TangencyError::Database(_) => "db error".to_string(),
+    }
+}
+
+/// POST /link/pending
+/// Register an author-signed link that still needs its pact's signatures
+async fn route_register(
+    State(state): State<AppState>,
+    Json(req): Json<PendingRequest>,
+) -> Result<Json<PendingLink>, ApiError> {
+    let mut link = req.link;
+    link.pact = None;
+    let commit = link
+        .to_commit()
+        .map_err(|e| (StatusCode::BAD_REQUEST, describe(&e)))?;
+
+    // Everything but the pact must already hold, or collecting signatures is moot
+    match state.ledger.validate(&link).await {
+        Ok(()) | Err(TangencyError::Membrane(MembraneError::PactViolation)) => {}
+        Err(TangencyError::Membrane(e)) => return Err(crate::membrane_rejection(&e)),
+        Err(TangencyError::Pact(e)) => return Err((StatusCode::CONFLICT, e.to_string())),
+        Err(TangencyError::Policy(e)) => return Err(crate::policy_rejection(&e)),
+        Err(TangencyError::Malformed(msg)) => return Err((StatusCode::BAD_REQUEST, msg)),
+        Err(TangencyError::Database(e)) => return Err(db_error(e)),
+    }
+
+    let at = now();
+    let expires_at = state
+        .ledger
+        .with_registry(|registry| {
+            let pact = registry.get_at(&req.pact_id, at)?;
+            if !pact.applies_to(&commit.container_id) {
+                return Err(PactError::ScopeMismatch {
+                    pact_id: req.pact_id.clone(),
+                    container_id: commit.container_id.clone(),
+                });
+            }
+            if !pact.window.is_valid(at) {
+                return Err(PactError::PactExpired);
+            }
+            Ok(pact.window.not_after)
+        })
+        .map_err(pact_rejection)?;
+
+    // The link hash identifies the draft, so registering it again is a no-op
+    let signing_bytes = commit
+        .signing_bytes()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let pending_id = ubl_kernel::hash_link(&signing_bytes);
+    let draft = serde_json::to_value(&link).map_err(|e| corrupt(&pending_id, e))?;
+    sqlx::query!(
+        r#"
+        INSERT INTO pact_pending (pending_id, pact_id, container_id, draft, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (pending_id) DO NOTHING
+        "#,
+        pending_id,
+        req.pact_id,
+        link.container_id,
+        draft,
+        expires_at
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+
+    let pending = load(&state, &pending_id)
+        .await?
+        .ok_or_else(|| corrupt(&pending_id, "missing after registration"))?;
+    if pending.pact_id != req.pact_id {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("link already pending under pact {}", pending.pact_id),
+        ));
+    }
+    info!(
+        "🖊️  PENDING {} pact={} container={}",
+        &pending_id[..8],
+        pending.pact_id,
+        pending.container_id
+    );
+    Ok(Json(pending))
+}
+
+/// GET /link/pending/:pending_id
+/// The link, what to sign, and progress toward the pact
+async fn route_pending(
+    State(state): State<AppState>,
+    Path(pending_id): Path<String>,
+) -> Result<Json<PendingLink>, ApiError> {
+    load(&state, &pending_id)
+        .await?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown pending link".to_string()))
+}
+
+/// POST /link/pending/:pending_id/signatures
+/// Accept one signer's signature; commits the link once the pact is satisfied
+async fn route_sign(
+    State(state): State<AppState>,
+    Path(pending_id): Path<String>,
+    Json(sig): Json<PactSignature>,
+) -> Result<Json<PendingLink>, ApiError> {
+    let pending = load(&state, &pending_id)
+        .await?
+        .ok_or((StatusCode::NOT_FOUND, "unknown pending link".to_string()))?;
+    if pending.status != "pending" {
+        return Err((StatusCode::CONFLICT, format!("link is {}", pending.status)));
+    }
+
+    let (draft, commit) = stored_link(&pending_id, &pending.draft)?;
+    let message = PactSubject::of(&commit).signing_bytes(&pending.pact_id);
+    let at = now();
+    state
+        .ledger
+        .with_registry(|registry| {
+            let pact = registry.get_at(&pending.pact_id, at)?;
+            if !pact.signers.contains(&sig.pubkey) {
+                return Err(PactError::UnauthorizedSigner(sig.pubkey.clone()));
+            }
+            ubl_kernel::verify(&sig.pubkey, &message, &sig.signature)
+                .map_err(|_| PactError::InvalidSignature(sig.pubkey.clone()))
+        })
+        .map_err(pact_rejection)?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO pact_pending_signature (pending_id, pubkey, signature)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (pending_id, pubkey) DO NOTHING
+        "#,
+        pending_id,
+        sig.pubkey,
+        sig.signature
+    )
+    .execute(&state.pool)
+    .await
+    .map_err(db_error)?;
+    info!("✍️  SIGNED {} by {}", &pending_id[..8], &sig.pubkey[..8]);
+
+    let pending = load(&state, &pending_id)
+        .await?
+        .ok_or((StatusCode::NOT_FOUND, "unknown pending link".to_string()))?;
+    let proof = state.ledger.with_registry(|registry| {
+        let pact = registry.get_at(&pending.pact_id, at).ok()?;
+        let proof = PactProof {
+            pact_id: pending.pact_id.clone(),
+            signatures: pending
+                .signatures
+                .iter()
+                .filter(|s| pact.signers.contains(&s.pubkey))
+                .cloned()
+                .collect(),
+        };
+        registry
+            .validate(&proof, &PactSubject::of(&commit), at)
+            .is_ok()
+            .then_some(proof)
+    });
+    if let Some(proof) = proof {
+        commit_pending(&state, &pending_id, draft, proof).await?;
+    }
+
+    load(&state, &pending_id)
+        .await?
+        .map(Json)
+        .ok_or((StatusCode::NOT_FOUND, "unknown pending link".to_string()))
+}
+
+/// Commit a satisfied link; the status claim makes sure only one request appends it
+///
+/// A database failure leaves the claim in place: the entry may or may not
+/// have been written, and only the ledger can tell (see [`recover_claims`]).
+async fn commit_pending(
+    state: &AppState,
+    pending_id: &str,
+    mut draft: LinkDraft,
+    proof: PactProof,
+) -> Result<(), ApiError> {
+    let claimed = sqlx::query_scalar!(
+        r#"
+        UPDATE pact_pending SET status = 'committing', claimed_at = $2
+        WHERE pending_id = $1 AND status = 'pending' AND expires_at >= $2
+        RETURNING pending_id
+        "#,
+        pending_id,
+        now()
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?;
+    if claimed.is_none() {
+        return Ok(());
+    }
+
+    draft.pact = Some(proof);
+    match state.ledger.append(&draft).await {
+        Ok(entry) => {
+            info!(
+                "✅ PENDING {} COMMITTED seq={}",
+                &pending_id[..8],
+                entry.sequence
+            );
+            sqlx::query!(
+                r#"
+                UPDATE pact_pending SET status = 'committed', sequence = $2, entry_hash = $3
+                WHERE pending_id = $1
+                "#,
+                pending_id,
+                entry.sequence,
+                entry.entry_hash
+            )
+            .execute(&state.pool)
+            .await
+            .map_err(db_error)?;
+        }
+        Err(TangencyError::Database(e)) => {
+            warn!(
+                "⚠️  PENDING {} left committing for recovery",
+                &pending_id[..8]
+            );
+            return Err(db_error(e));
+        }
+        Err(e) => {
+            let reason = describe(&e);
+            warn!("⚠️  PENDING {} FAILED: {}", &pending_id[..8], reason);
+            sqlx::query!(
+                "UPDATE pact_pending SET status = 'failed', reason = $2 WHERE pending_id = $1",
+                pending_id,
+                reason
+            )
+            .execute(&state.pool)
+            .await
+            .map_err(db_error)?;
+        }
+    }
+    Ok(())
+}
+
+/// Load a pending link, expiring it first if its pact window has closed
+async fn load(state: &AppState, pending_id: &str) -> Result<Option<PendingLink>, ApiError> {
+    let at = now();
+    let pact_id = sqlx::query_scalar!(
+        "SELECT pact_id FROM pact_pending WHERE pending_id = $1",
+        pending_id
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?;
+    let Some(pact_id) = pact_id else {
+        return Ok(None);
+    };
+    follow_window(&state.pool, &state.ledger, &pact_id, at)
+        .await
+        .map_err(db_error)?;
+
+    let Some(row) = sqlx::query!(
+        r#"
+        SELECT pact_id, container_id, draft, status, expires_at, sequence, entry_hash, reason
+        FROM pact_pending
+        WHERE pending_id = $1
+        "#,
+        pending_id
+    )
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(db_error)?
+    else {
+        return Ok(None);
+    };
+
+    let signatures: Vec<PactSignature> = sqlx::query!(
+        r#"
+        SELECT pubkey, signature
+        FROM pact_pending_signature
+        WHERE pending_id = $1
+        ORDER BY pubkey
+        "#,
+        pending_id
+    )
+    .fetch_all(&state.pool)
+    .await
+    .map_err(db_error)?
+    .into_iter()
+    .map(|s| PactSignature {
+        pubkey: s.pubkey,
+        signature: s.signature,
+    })
+    .collect();
+
+    let (_, commit) = stored_link(pending_id, &row.draft)?;
+    let signing_bytes = hex::encode(PactSubject::of(&commit).signing_bytes(&row.pact_id));
+    let signed: BTreeSet<&str> = signatures.iter().map(|s| s.pubkey.as_str()).collect();
+    let progress = state.ledger.with_registry(|registry| {
+        registry
+            .get_at(&row.pact_id, at)
+            .ok()
+            .map(|pact| progress(pact, &signed))
+    });
+
+    Ok(Some(PendingLink {
+        pending_id: pending_id.to_string(),
+        pact_id: row.pact_id,
+        container_id: row.container_id,
+        status: row.status,
+        expires_at: row.expires_at,
+        signing_bytes,
+        signers: signed.iter().map(|pk| pk.to_string()).collect(),
+        progress,
+        sequence: row.sequence,
+        entry_hash: row.entry_hash,
+        reason: row.reason,
+        draft: row.draft,
+        signatures,
+    }))
+}
+
+/// Signatures from current signers, and the quorum clauses they leave unmet
+fn progress(pact: &Pact, signed: &BTreeSet<&str>) -> Progress {
+    let counted: BTreeSet<&str> = signed
+        .iter()
+        .copied()
+        .filter(|pk| pact.signers.contains(*pk))
+        .collect();
+    let unmet = match &pact.quorum {
+        Some(quorum) => quorum.evaluate(&counted).err().unwrap_or_default(),
+        None => Vec::new(),
+    };
+    Progress {
+        signatures: counted.len(),
+        threshold: pact.threshold,
+        unmet,
+    }
+}
+
+/// Move the deadline of `pact_id`'s pending links to the end of the window
+/// of the version in force at `at` (now, if none is), expiring them once it
+/// has passed. Returns how many expired.
+async fn follow_window(
+    pool: &PgPool,
+    ledger: &PgLedger,
+    pact_id: &str,
+    at: i64,
+) -> Result<usize, sqlx::Error> {
+    let window_end = ledger.with_registry(|registry| {
+        registry
+            .get_at(pact_id, at)
+            .map_or(at - 1, |pact| pact.window.not_after)
+    });
+    let statuses = sqlx::query_scalar!(
+        r#"
+        UPDATE pact_pending
+        SET expires_at = $2,
+            status = CASE WHEN $2::BIGINT < $3::BIGINT THEN 'expired' ELSE status END
+        WHERE pact_id = $1 AND status = 'pending'
+        RETURNING status
+        "#,
+        pact_id,
+        window_end,
+        at
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(statuses.iter().filter(|s| *s == "expired").count())
+}
+
+/// Settle `committing` claims older than [`STALE_CLAIM`] against the ledger:
+/// `committed` where the entry was appended, otherwise back to `pending`.
+/// Returns `(committed, released)`.
+async fn recover_claims(pool: &PgPool, at: i64) -> Result<(u64, u64), sqlx::Error> {
+    let stale_before = at - STALE_CLAIM;
+    let committed = sqlx::query!(
+        r#"
+        UPDATE pact_pending p
+        SET status = 'committed', sequence = e.sequence, entry_hash = e.entry_hash
+        FROM ledger_entry e
+        WHERE p.status = 'committing'
+          AND (p.claimed_at IS NULL OR p.claimed_at < $1)
+          AND e.container_id = p.container_id
+          AND e.link_hash = p.pending_id
+        "#,
+        stale_before
+    )
+    .execute(pool)
+    .await?;
+    let released = sqlx::query!(
+        r#"
+        UPDATE pact_pending SET status = 'pending', claimed_at = NULL
+        WHERE status = 'committing' AND (claimed_at IS NULL OR claimed_at < $1)
+        "#,
+        stale_before
+    )
+    .execute(pool)
+    .await?;
+    Ok((committed.rows_affected(), released.rows_affected()))
+}
+
+/// One pass of [`sweep`]
+async fn sweep_once(pool: &PgPool, ledger: &PgLedger) -> Result<(), sqlx::Error> {
+    let at = now();
+    let (committed, released) = recover_claims(pool, at).await?;
+    if committed + released > 0 {
+        info!(
+            "🔁 interrupted commits settled: {} committed, {} pending again",
+            committed, released
+        );
+    }
+
+    let pacts =
+        sqlx::query_scalar!("SELECT DISTINCT pact_id FROM pact_pending WHERE status = 'pending'")
+            .fetch_all(pool)
+            .await?;
+    let mut expired = 0;
+    for pact_id in pacts {
+        expired += follow_window(pool, ledger, &pact_id, at).await?;
+    }
+    if expired > 0 {
+        info!("⌛ {} pending links expired", expired);
+    }
+    Ok(())
+}
+
+/// Every [`SWEEP_EVERY`]: settle interrupted commits and expire links whose
+/// pact window closed
+pub async fn sweep(pool: PgPool, ledger: PgLedger) {
+    let mut interval = tokio::time::interval(SWEEP_EVERY);
+    loop {
+        interval.tick().await;
+        if let Err(e) = sweep_once(&pool, &ledger).await {
+            error!("❌ PENDING SWEEP FAILED: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Run against `DATABASE_URL` (with `sql/` applied); skipped without it
+
+    use super::*;
+    use ubl_kernel::SigningKey;
+    use ubl_pact::{PactRegistry, PactScope, RiskLevel, TimeWindow};
+
+    struct Fixture {
+        state: AppState,
+        pact_id: String,
+        container_id: String,
+        signers: [(String, SigningKey); 2],
+        author: (String, SigningKey),
+    }
+
+    impl Fixture {
+        async fn new(name: &str) -> Option<Self> {
+            let pool = PgPool::connect(&std::env::var("DATABASE_URL").ok()?)
+                .await
+                .ok()?;
+            let tag = format!("{}-{}", name, hex::encode(rand::random::<[u8; 6]>()));
+            let fixture = Self {
+                state: AppState {
+                    ledger: PgLedger::new(pool.clone()),
+                    pool,
+                },
+                pact_id: format!("pact/{}", tag),
+                container_id: format!("wallet/{}", tag),
+                signers: [
+                    ubl_kernel::generate_keypair(),
+                    ubl_kernel::generate_keypair(),
+                ],
+                author: ubl_kernel::generate_keypair(),
+            };
+            fixture.set_window_end(now() + 3600);
+            Some(fixture)
+        }
+
+        /// Replace the pact with a version whose window ends at `not_after`
+        fn set_window_end(&self, not_after: i64) {
+            let mut registry = PactRegistry::new();
+            registry.register(Pact {
+                pact_id: self.pact_id.clone(),
+                version: 1,
+                scope: PactScope::Container,
+                threshold: 2,
+                signers: self.signers.iter().map(|(pk, _)| pk.clone()).collect(),
+                window: TimeWindow {
+                    not_before: 0,
+                    not_after,
+                },
+                risk_level: RiskLevel::L4,
+                container_id: Some(self.container_id.clone()),
+                quorum: None,
+            });
+            let _ = self.state.ledger.clone().with_pacts(registry);
+        }
+
+        /// Author-signed Entropy draft at the container's genesis
+        fn draft(&self) -> LinkDraft {
+            let mut commit = LinkCommit {
+                version: 2,
+                container_id: self.container_id.clone(),
+                expected_sequence: 1,
+                previous_hash: crate::db::GENESIS_PREVIOUS_HASH.to_string(),
+                atom_hash: "ab".repeat(32),
+                intent_class: ubl_link::IntentClass::Entropy,
+                physics_delta: 10,
+                constraints: Vec::new(),
+                pact: None,
+                author_pubkey: self.author.0.clone(),
+                signature: String::new(),
+            };
+            commit.signature = ubl_kernel::sign(&self.author.1, &commit.signing_bytes().unwrap());
+            serde_json::from_value(serde_json::json!({
+                "version": commit.version,
+                "container_id": commit.container_id,
+                "expected_sequence": 1,
+                "previous_hash": commit.previous_hash,
+                "atom_hash": commit.atom_hash,
+                "intent_class": "Entropy",
+                "physics_delta": "10",
+                "author_pubkey": commit.author_pubkey,
+                "signature": commit.signature,
+            }))
+            .unwrap()
+        }
+
+        async fn register(&self) -> PendingLink {
+            let request = PendingRequest {
+                pact_id: self.pact_id.clone(),
+                link: self.draft(),
+            };
+            route_register(State(self.state.clone()), Json(request))
+                .await
+                .unwrap()
+                .0
+        }
+
+        async fn sign(&self, pending: &PendingLink, signer: usize) -> PendingLink {
+            let (pubkey, key) = &self.signers[signer];
+            let message = hex::decode(&pending.signing_bytes).unwrap();
+            let signature = PactSignature {
+                pubkey: pubkey.clone(),
+                signature: ubl_kernel::sign(key, &message),
+            };
+            route_sign(
+                State(self.state.clone()),
+                Path(pending.pending_id.clone()),
+                Json(signature),
+            )
+            .await
+            .unwrap()
+            .0
+        }
+
+        async fn get(&self, pending: &PendingLink) -> PendingLink {
+            load(&self.state, &pending.pending_id)
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        async fn force_status(&self, pending: &PendingLink, status: &str, claimed_at: i64) {
+            sqlx::query(
+                "UPDATE pact_pending SET status = $2, claimed_at = $3 WHERE pending_id = $1",
+            )
+            .bind(&pending.pending_id)
+            .bind(status)
+            .bind(claimed_at)
+            .execute(&self.state.pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_commits_once_the_pact_is_satisfied() {
+        let Some(fixture) = Fixture::new("p019-commit").await else {
+            return;
+        };
+        let pending = fixture.register().await;
+        assert_eq!(pending.status, "pending");
+        let progress = pending.progress.as_ref().unwrap();
+        assert_eq!((progress.signatures, progress.threshold), (0, 2));
+        // Registering the same draft again is a no-op
+        assert_eq!(fixture.register().await.pending_id, pending.pending_id);
+
+        let once = fixture.sign(&pending, 0).await;
+        assert_eq!(once.status, "pending");
+        assert_eq!(once.progress.unwrap().signatures, 1);
+
+        let done = fixture.sign(&pending, 1).await;
+        assert_eq!(done.status, "committed");
+        assert_eq!(done.sequence, Some(1));
+        assert_eq!(done.signers.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_follows_the_pact_window() {
+        let Some(fixture) = Fixture::new("p019-expire").await else {
+            return;
+        };
+        let pending = fixture.register().await;
+        fixture.sign(&pending, 0).await;
+
+        let later = now() + 7200;
+        fixture.set_window_end(later);
+        let extended = fixture.get(&pending).await;
+        assert_eq!(
+            (extended.status.as_str(), extended.expires_at),
+            ("pending", later)
+        );
+
+        fixture.set_window_end(now() - 10);
+        assert_eq!(fixture.get(&pending).await.status, "expired");
+        // Expired links take no more signatures
+        let (pubkey, key) = &fixture.signers[1];
+        let late = route_sign(
+            State(fixture.state.clone()),
+            Path(pending.pending_id.clone()),
+            Json(PactSignature {
+                pubkey: pubkey.clone(),
+                signature: ubl_kernel::sign(key, &hex::decode(&pending.signing_bytes).unwrap()),
+            }),
+        )
+        .await;
+        assert_eq!(late.unwrap_err().0, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_recovers_interrupted_commits() {
+        let Some(fixture) = Fixture::new("p019-recover").await else {
+            return;
+        };
+        let pool = &fixture.state.pool;
+        let pending = fixture.register().await;
+
+        // Claimed but never appended: released for the next signature
+        fixture.force_status(&pending, "committing", now()).await;
+        recover_claims(pool, now()).await.unwrap();
+        assert_eq!(fixture.get(&pending).await.status, "committing");
+        recover_claims(pool, now() + STALE_CLAIM + 1).await.unwrap();
+        assert_eq!(fixture.get(&pending).await.status, "pending");
+
+        // Appended, but the outcome was never recorded
+        fixture.sign(&pending, 0).await;
+        let committed = fixture.sign(&pending, 1).await;
+        fixture.force_status(&pending, "committing", 0).await;
+        recover_claims(pool, now()).await.unwrap();
+        let recovered = fixture.get(&pending).await;
+        assert_eq!(recovered.status, "committed");
+        assert_eq!(recovered.entry_hash, committed.entry_hash);
+    }
+}
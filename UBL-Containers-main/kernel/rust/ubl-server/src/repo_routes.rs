@@ -3,6 +3,11 @@ use serde::{Deserialize, Serialize};
 use std::process::Command;
 use crate::AppState;
 use axum::http::StatusCode;
+use once_cell::sync::Lazy;
+use ubl_atom::SchemaRegistry;
+
+/// Atom schemas known to the repo routes (`git/ref@1`)
+static SCHEMAS: Lazy<SchemaRegistry> = Lazy::new(SchemaRegistry::with_builtins);
 
 #[derive(Debug, Deserialize)]
 pub struct PresignBody {
@@ -42,6 +47,8 @@ pub struct CommitRefBody {
 pub struct CommitRefResult {
     pub status: String,
     pub link_hash: String,
+    pub atom_hash: String,
+    pub schema_hash: String,
 }
 
 pub fn router() -> Router<AppState> {
@@ -93,9 +100,20 @@ async fn route_repo_commit_ref(
     State(_state): State<AppState>,
     Json(body): Json<CommitRefBody>,
 ) -> Result<Json<CommitRefResult>, (StatusCode, String)> {
-    if body.mode != "ff" && body.mode != "force" {
-        return Err((StatusCode::BAD_REQUEST, "mode must be 'ff' or 'force'".into()));
-    }
+    let atom = serde_json::json!({
+        "type": "git/ref",
+        "tenant": body.tenant,
+        "repo": body.repo,
+        "ref": body.r#ref,
+        "old": body.old,
+        "new": body.new,
+        "mode": body.mode,
+    });
+    let schema_hash = SCHEMAS
+        .validate_atom(&atom, "git/ref@1")
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let atom_hash = ubl_atom::hash_value(&atom)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
     
     // TODO: Build proper LinkDraft with signatures and append to ledger
     // For now, just return success with placeholder hash
@@ -107,10 +125,11 @@ async fn route_repo_commit_ref(
         old = %body.old,
         new = %body.new,
         mode = %body.mode,
+        atom_hash = %atom_hash,
         "Repo ref commit (ledger append not yet implemented)"
     );
     
     let link_hash = format!("0x{}", hex::encode(blake3::hash(body.new.as_bytes()).as_bytes()));
     
-    Ok(Json(CommitRefResult { status: "accepted".into(), link_hash }))
+    Ok(Json(CommitRefResult { status: "accepted".into(), link_hash, atom_hash, schema_hash }))
 }
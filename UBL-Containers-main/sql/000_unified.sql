@@ -90,6 +90,11 @@ CREATE TABLE IF NOT EXISTS ledger_entry (
 );
 CREATE INDEX IF NOT EXISTS ix_ledger_container ON ledger_entry (container_id);
 
+-- Entry hash scheme (SPEC-UBL-LEDGER §5): 0 = legacy, 1 = hash_link/hash_entry
+ALTER TABLE ledger_entry ADD COLUMN IF NOT EXISTS hash_scheme smallint NOT NULL DEFAULT 0
+  CHECK (hash_scheme IN (0, 1));
+ALTER TABLE ledger_entry ALTER COLUMN hash_scheme DROP DEFAULT;
+
 -- Notify trigger for SSE
 CREATE OR REPLACE FUNCTION notify_ledger_change() RETURNS trigger AS $$
 BEGIN
@@ -101,3 +106,42 @@ $$ LANGUAGE plpgsql;
 DROP TRIGGER IF EXISTS ledger_notify ON ledger_entry;
 CREATE TRIGGER ledger_notify AFTER INSERT ON ledger_entry
 FOR EACH ROW EXECUTE FUNCTION notify_ledger_change();
+
+-- Ledger checkpoints (signed state, SPEC-UBL-LEDGER §8.2)
+CREATE TABLE IF NOT EXISTS ledger_checkpoint (
+  container_id      text NOT NULL,
+  sequence          bigint NOT NULL,
+  last_hash         text NOT NULL,
+  physical_balance  text NOT NULL,
+  merkle_root       text NOT NULL,
+  merkle_frontier   jsonb,
+  signer            text NOT NULL,
+  signature         text NOT NULL,
+  created_at        timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (container_id, sequence)
+);
+
+-- Pending links awaiting pact signatures (SPEC-UBL-PACT §7)
+CREATE TABLE IF NOT EXISTS pact_pending (
+  pending_id    text PRIMARY KEY,
+  pact_id       text NOT NULL,
+  container_id  text NOT NULL,
+  draft         jsonb NOT NULL,
+  status        text NOT NULL DEFAULT 'pending',
+  expires_at    bigint NOT NULL,
+  sequence      bigint,
+  entry_hash    text,
+  reason        text,
+  claimed_at    bigint,
+  created_at    timestamptz NOT NULL DEFAULT now()
+);
+CREATE INDEX IF NOT EXISTS ix_pact_pending_open ON pact_pending (expires_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS ix_pact_pending_committing ON pact_pending (claimed_at) WHERE status = 'committing';
+
+CREATE TABLE IF NOT EXISTS pact_pending_signature (
+  pending_id    text NOT NULL REFERENCES pact_pending(pending_id),
+  pubkey        text NOT NULL,
+  signature     text NOT NULL,
+  created_at    timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (pending_id, pubkey)
+);
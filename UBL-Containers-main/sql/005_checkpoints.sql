@@ -0,0 +1,16 @@
+-- SPEC-UBL-LEDGER v1.0 §8.2 — checkpoints de estado assinados
+-- Projeções retomam do último checkpoint em vez de somar desde o genesis
+CREATE TABLE IF NOT EXISTS ledger_checkpoint (
+  container_id      TEXT        NOT NULL,
+  sequence          BIGINT      NOT NULL,
+  last_hash         TEXT        NOT NULL,
+  physical_balance  TEXT        NOT NULL,  -- i128 em decimal
+  merkle_root       TEXT        NOT NULL,
+  signer            TEXT        NOT NULL,  -- pubkey Ed25519 (hex)
+  signature         TEXT        NOT NULL,
+  created_at        TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  PRIMARY KEY (container_id, sequence)
+);
+
+CREATE INDEX IF NOT EXISTS ix_ledger_checkpoint_latest ON ledger_checkpoint (container_id, sequence DESC);
@@ -0,0 +1,26 @@
+-- SPEC-UBL-PACT v1.0 §7 — coleta assíncrona de assinaturas de pacto
+-- O link fica pendente até o quórum ser atingido (commit automático)
+-- ou até o fim da janela do pacto (expira)
+CREATE TABLE IF NOT EXISTS pact_pending (
+  pending_id    TEXT        PRIMARY KEY,  -- link_hash do rascunho
+  pact_id       TEXT        NOT NULL,
+  container_id  TEXT        NOT NULL,
+  draft         JSONB       NOT NULL,     -- LinkDraft assinado pelo autor, sem prova
+  status        TEXT        NOT NULL DEFAULT 'pending',  -- pending|committing|committed|expired|failed
+  expires_at    BIGINT      NOT NULL,     -- unix (s), not_after da janela do pacto
+  sequence      BIGINT,                   -- entrada gerada no commit
+  entry_hash    TEXT,
+  reason        TEXT,                     -- motivo de falha
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS ix_pact_pending_open ON pact_pending (expires_at) WHERE status = 'pending';
+
+CREATE TABLE IF NOT EXISTS pact_pending_signature (
+  pending_id    TEXT        NOT NULL REFERENCES pact_pending(pending_id),
+  pubkey        TEXT        NOT NULL,
+  signature     TEXT        NOT NULL,
+  created_at    TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  PRIMARY KEY (pending_id, pubkey)
+);
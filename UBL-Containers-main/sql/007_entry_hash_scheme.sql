@@ -0,0 +1,8 @@
+-- SPEC-UBL-LEDGER v1.0 §5 — esquema de hash de cada entrada
+-- 0: legado — link_hash = atom_hash,
+--    entry_hash = blake3(container_id || sequence || atom_hash || previous_hash || ts_unix_ms)
+-- 1: link_hash = hash_link(signing_bytes), entry_hash = hash_entry(...)
+-- Linhas existentes ficam em 0 e continuam verificáveis; novas linhas declaram o esquema
+ALTER TABLE ledger_entry ADD COLUMN IF NOT EXISTS hash_scheme SMALLINT NOT NULL DEFAULT 0
+  CHECK (hash_scheme IN (0, 1));
+ALTER TABLE ledger_entry ALTER COLUMN hash_scheme DROP DEFAULT;
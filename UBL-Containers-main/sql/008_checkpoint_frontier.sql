@@ -0,0 +1,5 @@
+-- SPEC-UBL-LEDGER v1.0 §8.2 — fronteira Merkle do checkpoint
+-- Raízes das subárvores perfeitas (hex, maior primeiro): o próximo checkpoint
+-- estende a árvore a partir daqui, lendo só as entradas novas.
+-- NULL em checkpoints antigos: o próximo refaz a árvore desde o genesis uma vez.
+ALTER TABLE ledger_checkpoint ADD COLUMN IF NOT EXISTS merkle_frontier JSONB;
@@ -0,0 +1,7 @@
+-- SPEC-UBL-PACT v1.0 §7 — recuperação de commits pendentes interrompidos
+-- Quando o quórum é atingido o link passa a 'committing' até o append terminar.
+-- Se o servidor cair (ou o banco falhar) nesse meio tempo, a varredura consulta
+-- o ledger: o link vira 'committed' se a entrada existir, senão volta a 'pending'.
+ALTER TABLE pact_pending ADD COLUMN IF NOT EXISTS claimed_at BIGINT;  -- unix (s) do início do commit
+
+CREATE INDEX IF NOT EXISTS ix_pact_pending_committing ON pact_pending (claimed_at) WHERE status = 'committing';
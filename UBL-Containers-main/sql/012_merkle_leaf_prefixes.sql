@@ -0,0 +1,5 @@
+-- SPEC-UBL-LEDGER v1.0 §9 — folhas e nós Merkle com prefixo (RFC 9162)
+-- Folhas passam a ser blake3(0x00 || entry_hash) e nós blake3(0x01 || esq || dir).
+-- Fronteiras salvas antes disso estão no esquema antigo: descartadas, o próximo
+-- checkpoint de cada container refaz a árvore desde o genesis uma vez.
+UPDATE ledger_checkpoint SET merkle_frontier = NULL WHERE merkle_frontier IS NOT NULL;